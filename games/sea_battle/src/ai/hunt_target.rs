@@ -0,0 +1,85 @@
+use super::{blocked_cells, open_hits, pick, remaining_ships};
+use crate::board::Board;
+use crate::game::{GameRng, ShotStrategy};
+
+// Охота и добивание: пока нет раненых кораблей, стреляет в шахматном порядке
+// с шагом по самому маленькому оставшемуся кораблю, после попадания добивает корабль
+pub struct HuntTargetStrategy;
+
+impl ShotStrategy for HuntTargetStrategy {
+    fn choose_shot(&self, board: &Board, rng: &mut GameRng) -> (usize, usize) {
+        let blocked = blocked_cells(board);
+        let free = |row: usize, col: usize| !blocked[row][col];
+
+        // Добивание: сначала продолжаем линии из двух и более попаданий подряд.
+        // Если корабли могут касаться, диагональные клетки не исключены, и без этого
+        // стратегия стреляла бы вокруг всей линии
+        let hits = open_hits(board);
+        let is_hit = |r: usize, c: usize| r < board.height && c < board.width && hits.contains(&(r, c));
+        let mut targets = Vec::new();
+        for &(row, col) in &hits {
+            for (dr, dc) in [(0usize, 1usize), (1, 0)] {
+                if !is_hit(row + dr, col + dc) {
+                    continue;
+                }
+                let (mut end_r, mut end_c) = (row, col);
+                while is_hit(end_r, end_c) {
+                    end_r += dr;
+                    end_c += dc;
+                }
+                let (mut start_r, mut start_c) = (row, col);
+                while is_hit(start_r, start_c) {
+                    start_r = start_r.wrapping_sub(dr);
+                    start_c = start_c.wrapping_sub(dc);
+                }
+                for (r, c) in [(start_r, start_c), (end_r, end_c)] {
+                    if r < board.height && c < board.width && free(r, c) && !targets.contains(&(r, c)) {
+                        targets.push((r, c));
+                    }
+                }
+            }
+        }
+        if !targets.is_empty() {
+            return pick(rng, &targets);
+        }
+
+        // одиночные попадания: соседние по стороне клетки
+        for &(row, col) in &hits {
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (r, c) in neighbours {
+                if r < board.height && c < board.width && free(r, c) && !targets.contains(&(r, c)) {
+                    targets.push((r, c));
+                }
+            }
+        }
+        if !targets.is_empty() {
+            return pick(rng, &targets);
+        }
+
+        // охота: клетки, в которые может поместиться самый маленький из оставшихся кораблей
+        let step = remaining_ships(board).into_iter().min().unwrap_or(1).max(1);
+        let free_cells: Vec<(usize, usize)> = board
+            .unshot_cells()
+            .into_iter()
+            .filter(|&(row, col)| free(row, col))
+            .collect();
+        let parity: Vec<(usize, usize)> = free_cells
+            .iter()
+            .copied()
+            .filter(|&(row, col)| (row + col) % step == 0)
+            .collect();
+
+        if !parity.is_empty() {
+            pick(rng, &parity)
+        } else if !free_cells.is_empty() {
+            pick(rng, &free_cells)
+        } else {
+            pick(rng, &board.unshot_cells())
+        }
+    }
+}
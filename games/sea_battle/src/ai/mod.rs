@@ -0,0 +1,278 @@
+use crate::board::{AutoShipPlacer, Board, ShipPlacer};
+use crate::game::{GameRng, RandomShotStrategy, ShotStrategy};
+use crate::rules::Rules;
+use rand::{Rng, SeedableRng};
+
+mod hunt_target;
+mod placers;
+mod probability;
+
+pub use hunt_target::HuntTargetStrategy;
+pub use placers::{EdgeShipPlacer, SpreadShipPlacer};
+pub use probability::ProbabilityDensityStrategy;
+
+// Подбитые, но не потопленные корабли. Соседние по диагонали клетки с попаданиями
+// не бывают частью одного корабля, а правило "корабли не касаются" исключает
+// и клетки вокруг потопленных кораблей. Если правила разрешают кораблям касаться,
+// исключаются только уже обстрелянные клетки
+fn blocked_cells(board: &Board) -> Vec<Vec<bool>> {
+    let mut blocked = vec![vec![false; board.width]; board.height];
+    for row in 0..board.height {
+        for col in 0..board.width {
+            if board.shots[row][col].is_some() {
+                blocked[row][col] = true;
+            }
+            if board.rules.ships_may_touch || board.shots[row][col] != Some(true) {
+                continue;
+            }
+            let sunk = board.is_sunk(row, col);
+            for dr in -1..=1isize {
+                for dc in -1..=1isize {
+                    let diagonal = dr != 0 && dc != 0;
+                    if !(sunk || diagonal) {
+                        continue;
+                    }
+                    let (nr, nc) = (row as isize + dr, col as isize + dc);
+                    if nr >= 0 && nr < board.height as isize && nc >= 0 && nc < board.width as isize {
+                        blocked[nr as usize][nc as usize] = true;
+                    }
+                }
+            }
+        }
+    }
+    blocked
+}
+
+// размеры кораблей флота, которые еще не потоплены
+fn remaining_ships(board: &Board) -> Vec<usize> {
+    let mut remaining = board.rules.ship_sizes();
+    for size in board.sunk_ship_sizes() {
+        if let Some(index) = remaining.iter().position(|&s| s == size) {
+            remaining.remove(index);
+        }
+    }
+    remaining
+}
+
+// попадания по кораблям, которые еще не потоплены
+fn open_hits(board: &Board) -> Vec<(usize, usize)> {
+    (0..board.height)
+        .flat_map(|row| (0..board.width).map(move |col| (row, col)))
+        .filter(|&(row, col)| board.shots[row][col] == Some(true) && !board.is_sunk(row, col))
+        .collect()
+}
+
+// уровень сложности компьютера
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Difficulty {
+    Easy, // случайные выстрелы
+    #[default]
+    Medium, // охота и добивание
+    Hard, // плотность вероятности
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Легкий",
+            Difficulty::Medium => "Средний",
+            Difficulty::Hard => "Сложный",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        Difficulty::ALL.into_iter().find(|difficulty| difficulty.name().to_lowercase() == name.to_lowercase())
+    }
+
+    pub fn strategy(&self) -> Box<dyn ShotStrategy> {
+        match self {
+            Difficulty::Easy => Box::new(RandomShotStrategy),
+            Difficulty::Medium => Box::new(HuntTargetStrategy),
+            Difficulty::Hard => Box::new(ProbabilityDensityStrategy),
+        }
+    }
+}
+
+// как компьютер расставляет свои корабли
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    #[default]
+    Random,
+    Edge,   // вдоль краев поля
+    Spread, // как можно дальше друг от друга
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::Random, Layout::Edge, Layout::Spread];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Layout::Random => "Случайная",
+            Layout::Edge => "Края",
+            Layout::Spread => "Вразброс",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Layout> {
+        Layout::ALL.into_iter().find(|layout| layout.name().to_lowercase() == name.to_lowercase())
+    }
+
+    pub fn placer(&self) -> Box<dyn ShipPlacer> {
+        match self {
+            Layout::Random => Box::new(AutoShipPlacer),
+            Layout::Edge => Box::new(EdgeShipPlacer),
+            Layout::Spread => Box::new(SpreadShipPlacer),
+        }
+    }
+}
+
+// итоги прогона стратегии на серии партий
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkReport {
+    pub games: usize,
+    pub average_shots: f64,
+    pub min_shots: usize,
+    pub max_shots: usize,
+}
+
+// Сколько выстрелов нужно стратегии, чтобы потопить весь флот.
+// Партия i использует генератор с зерном seed + i и для расстановки, и для выстрелов
+pub fn benchmark(difficulty: Difficulty, rules: &Rules, games: usize, seed: u64) -> BenchmarkReport {
+    let strategy = difficulty.strategy();
+    let mut shots_per_game = Vec::with_capacity(games);
+    for game in 0..games {
+        let mut rng = GameRng::seed_from_u64(seed.wrapping_add(game as u64));
+        let mut board = Board::with_rules(rules.clone());
+        board.place_ships_randomly(&mut rng).expect("Не удалось разместить корабли");
+
+        let mut shots = 0;
+        while !board.all_ships_destroyed() {
+            let (row, col) = strategy.choose_shot(&board, &mut rng);
+            board.shoot(row, col);
+            shots += 1;
+        }
+        shots_per_game.push(shots);
+    }
+
+    BenchmarkReport {
+        games,
+        average_shots: shots_per_game.iter().sum::<usize>() as f64 / games.max(1) as f64,
+        min_shots: shots_per_game.iter().copied().min().unwrap_or(0),
+        max_shots: shots_per_game.iter().copied().max().unwrap_or(0),
+    }
+}
+
+// случайный элемент из лучших кандидатов
+fn pick(rng: &mut GameRng, candidates: &[(usize, usize)]) -> (usize, usize) {
+    candidates[rng.random_range(0..candidates.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ship::{Ship, ShootResult};
+
+    fn play(strategy: &dyn ShotStrategy, seed: u64) -> usize {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut board = Board::new(10, 10);
+        board.place_ships_randomly(&mut rng).unwrap();
+        let mut shots = 0;
+        while !board.all_ships_destroyed() {
+            let (row, col) = strategy.choose_shot(&board, &mut rng);
+            assert!(board.shots[row][col].is_none(), "Повторный выстрел в ({}, {})", row, col);
+            board.shoot(row, col);
+            shots += 1;
+        }
+        shots
+    }
+
+    #[test]
+    fn test_strategies_never_repeat_shots() {
+        for difficulty in Difficulty::ALL {
+            for seed in 0..5 {
+                assert!(play(&*difficulty.strategy(), seed) <= 100);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hunt_target_finishes_wounded_ship() {
+        let mut board = Board::new(10, 10);
+        board.place_ship(Ship::new(vec![(4, 4), (4, 5), (4, 6)], 3)).unwrap();
+        assert_eq!(board.shoot(4, 5), ShootResult::Hit);
+
+        let mut rng = GameRng::seed_from_u64(1);
+        let mut shots = 0;
+        loop {
+            let (row, col) = HuntTargetStrategy.choose_shot(&board, &mut rng);
+            if board.shoot(row, col) == ShootResult::Destroy {
+                break;
+            }
+            shots += 1;
+            assert!(shots < 4, "Раненый корабль должен быть добит за несколько выстрелов");
+        }
+    }
+
+    #[test]
+    fn test_hunt_target_respects_no_touch_rule() {
+        let mut board = Board::new(10, 10);
+        board.place_ship(Ship::new(vec![(0, 0)], 1)).unwrap();
+        board.place_ship(Ship::new(vec![(5, 5)], 1)).unwrap();
+        assert_eq!(board.shoot(0, 0), ShootResult::Destroy);
+
+        let mut rng = GameRng::seed_from_u64(2);
+        for _ in 0..20 {
+            let (row, col) = HuntTargetStrategy.choose_shot(&board, &mut rng);
+            assert!(row > 1 || col > 1, "Клетки вокруг потопленного корабля пустые");
+            board.shoot(row, col);
+        }
+    }
+
+    #[test]
+    fn test_strategies_finish_touching_fleet() {
+        let rules = Rules::milton_bradley();
+        for difficulty in Difficulty::ALL {
+            let report = benchmark(difficulty, &rules, 10, 3);
+            assert!(report.max_shots <= 100);
+        }
+        let random = benchmark(Difficulty::Easy, &rules, 50, 3);
+        let hunt = benchmark(Difficulty::Medium, &rules, 50, 3);
+        assert!(hunt.average_shots < random.average_shots);
+    }
+
+    #[test]
+    fn test_smarter_strategies_need_fewer_shots() {
+        let random = benchmark(Difficulty::Easy, &Rules::classic(), 100, 7);
+        let hunt = benchmark(Difficulty::Medium, &Rules::classic(), 100, 7);
+        let density = benchmark(Difficulty::Hard, &Rules::classic(), 100, 7);
+        assert!(hunt.average_shots < random.average_shots);
+        assert!(density.average_shots < hunt.average_shots);
+    }
+
+    #[test]
+    fn test_layouts_place_whole_fleet() {
+        for rules in [Rules::classic(), Rules::milton_bradley()] {
+            for layout in Layout::ALL {
+                let mut board = Board::with_rules(rules.clone());
+                layout.placer().place_ships(&mut board, &mut GameRng::seed_from_u64(4)).unwrap();
+                let mut sizes: Vec<usize> = board.ships.iter().map(|ship| ship.size).collect();
+                sizes.sort_unstable_by(|a, b| b.cmp(a));
+                assert_eq!(sizes, rules.ship_sizes(), "{}", layout.name());
+            }
+        }
+
+        // самый большой корабль ставится первым и целиком ложится на край
+        let mut board = Board::with_rules(Rules::classic());
+        EdgeShipPlacer.place_ships(&mut board, &mut GameRng::seed_from_u64(5)).unwrap();
+        let on_edge = |&(row, col): &(usize, usize)| row == 0 || col == 0 || row == 9 || col == 9;
+        assert!(board.ships[0].coords.iter().all(on_edge));
+    }
+
+    #[test]
+    fn test_benchmark_is_reproducible() {
+        let rules = Rules::classic();
+        assert_eq!(benchmark(Difficulty::Hard, &rules, 5, 42), benchmark(Difficulty::Hard, &rules, 5, 42));
+    }
+}
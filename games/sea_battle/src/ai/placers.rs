@@ -0,0 +1,56 @@
+use crate::board::{AutoShipPlacer, Board, ShipPlacer};
+use crate::game::GameRng;
+use crate::ship::Ship;
+use rand::Rng;
+
+// сколько раз расстановка начинается заново, если очередной корабль не поместился
+const LAYOUT_ATTEMPTS: usize = 100;
+
+// Прижимает корабли к краям поля: стратегии, стреляющие в середину, ищут их дольше
+pub struct EdgeShipPlacer;
+
+impl ShipPlacer for EdgeShipPlacer {
+    fn place_ships(&self, board: &mut Board, rng: &mut GameRng) -> Result<(), String> {
+        place_best(board, rng, |board, coords| {
+            coords
+                .iter()
+                .filter(|&&(row, col)| row == 0 || col == 0 || row + 1 == board.height || col + 1 == board.width)
+                .count()
+        })
+    }
+}
+
+// Разносит корабли как можно дальше друг от друга, чтобы попадание
+// по одному ничего не говорило о соседних клетках
+pub struct SpreadShipPlacer;
+
+impl ShipPlacer for SpreadShipPlacer {
+    fn place_ships(&self, board: &mut Board, rng: &mut GameRng) -> Result<(), String> {
+        place_best(board, rng, |board, coords| {
+            let placed = board.ships.iter().flat_map(|ship| &ship.coords);
+            placed
+                .flat_map(|&(r, c)| coords.iter().map(move |&(row, col)| r.abs_diff(row).max(c.abs_diff(col))))
+                .min()
+                .unwrap_or(board.width + board.height)
+        })
+    }
+}
+
+// Корабли от больших к меньшим, каждый - в случайную из позиций с наибольшей оценкой
+fn place_best(board: &mut Board, rng: &mut GameRng, score: impl Fn(&Board, &[(usize, usize)]) -> usize) -> Result<(), String> {
+    'attempt: for _ in 0..LAYOUT_ATTEMPTS {
+        *board = Board::with_rules(board.rules.clone());
+        for size in board.rules.ship_sizes() {
+            let positions = board.positions(size);
+            let Some(best) = positions.iter().map(|coords| score(board, coords)).max() else {
+                continue 'attempt;
+            };
+            let best: Vec<_> = positions.into_iter().filter(|coords| score(board, coords) == best).collect();
+            let coords = best[rng.random_range(0..best.len())].clone();
+            board.place_ship(Ship::new(coords, size))?;
+        }
+        return Ok(());
+    }
+    // жадная расстановка не удалась, остается обычная случайная
+    AutoShipPlacer.place_ships(board, rng)
+}
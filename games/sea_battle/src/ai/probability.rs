@@ -0,0 +1,78 @@
+use super::{blocked_cells, open_hits, pick, remaining_ships};
+use crate::board::Board;
+use crate::game::{GameRng, ShotStrategy};
+
+// вес расстановки, проходящей через раненый корабль
+const HIT_WEIGHT: u64 = 50;
+
+// Плотность вероятности: для каждой клетки считает, сколько расстановок оставшихся
+// кораблей через нее проходит, и стреляет в самую вероятную. Если есть раненые корабли,
+// учитываются только расстановки, которые их накрывают
+pub struct ProbabilityDensityStrategy;
+
+impl ProbabilityDensityStrategy {
+
+    pub fn density(board: &Board) -> Vec<Vec<u64>> {
+        let blocked = blocked_cells(board);
+        let hits = open_hits(board);
+        let is_hit = |row: usize, col: usize| hits.contains(&(row, col));
+        let mut scores = vec![vec![0u64; board.width]; board.height];
+
+        for size in remaining_ships(board) {
+            for vertical in [false, true] {
+                if size == 1 && vertical {
+                    continue; // однопалубный корабль не поворачивается
+                }
+                for row in 0..board.height {
+                    for col in 0..board.width {
+                        let cells: Vec<(usize, usize)> = (0..size)
+                            .map(|i| if vertical { (row + i, col) } else { (row, col + i) })
+                            .collect();
+                        if cells.iter().any(|&(r, c)| r >= board.height || c >= board.width) {
+                            continue;
+                        }
+
+                        let mut covered_hits = 0;
+                        let mut valid = true;
+                        for &(r, c) in &cells {
+                            if is_hit(r, c) {
+                                covered_hits += 1;
+                            } else if blocked[r][c] {
+                                valid = false;
+                                break;
+                            }
+                        }
+                        if !valid || (!hits.is_empty() && covered_hits == 0) {
+                            continue;
+                        }
+
+                        let weight = if covered_hits > 0 { HIT_WEIGHT * covered_hits } else { 1 };
+                        for &(r, c) in &cells {
+                            if !is_hit(r, c) {
+                                scores[r][c] += weight;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        scores
+    }
+}
+
+impl ShotStrategy for ProbabilityDensityStrategy {
+    fn choose_shot(&self, board: &Board, rng: &mut GameRng) -> (usize, usize) {
+        let scores = Self::density(board);
+        let best = scores.iter().flatten().copied().max().unwrap_or(0);
+        if best == 0 {
+            // расстановок не осталось (нестандартный флот) - любая свободная клетка
+            return pick(rng, &board.unshot_cells());
+        }
+
+        let candidates: Vec<(usize, usize)> = (0..board.height)
+            .flat_map(|row| (0..board.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| scores[row][col] == best)
+            .collect();
+        pick(rng, &candidates)
+    }
+}
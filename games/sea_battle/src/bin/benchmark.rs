@@ -0,0 +1,34 @@
+use sea_battle::ai::{benchmark, Difficulty};
+use sea_battle::rules::Rules;
+
+// Сравнение стратегий компьютера: среднее число выстрелов до победы.
+// Запуск: cargo run --release --bin benchmark -- [партий] [зерно] [правила]
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let games: usize = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(1000);
+    let seed: u64 = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(0);
+    let rules = match args.next() {
+        Some(name) => match Rules::preset(&name) {
+            Some(rules) => rules,
+            None => {
+                let names: Vec<String> = Rules::presets().into_iter().map(|rules| rules.name).collect();
+                eprintln!("Неизвестные правила '{}'. Доступны: {}", name, names.join(", "));
+                std::process::exit(2);
+            }
+        },
+        None => Rules::classic(),
+    };
+
+    println!("Партий: {}, зерно: {}, правила: {}", games, seed, rules.name);
+    println!("{:<10} {:>10} {:>6} {:>6}", "Уровень", "Среднее", "Мин", "Макс");
+    for difficulty in Difficulty::ALL {
+        let report = benchmark(difficulty, &rules, games, seed);
+        println!(
+            "{:<10} {:>10.2} {:>6} {:>6}",
+            difficulty.name(),
+            report.average_shots,
+            report.min_shots,
+            report.max_shots,
+        );
+    }
+}
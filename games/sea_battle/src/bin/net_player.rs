@@ -0,0 +1,75 @@
+use rand::SeedableRng;
+use sea_battle::ai::Difficulty;
+use sea_battle::board::Board;
+use sea_battle::game::GameRng;
+use sea_battle::net::{listen, NetGame, Peer, Phase, DEFAULT_ADDR};
+use sea_battle::rules::Rules;
+
+// Компьютер, играющий по сети. Две копии на одной машине проверяют протокол без окна:
+//   cargo run --bin net_player -- host [адрес] [уровень] [правила]
+//   cargo run --bin net_player -- join [адрес] [уровень]
+// Против него можно играть и из окна игры, выбрав "Подключиться"
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mode = args.next().unwrap_or_default();
+    let addr = args.next().unwrap_or_else(|| DEFAULT_ADDR.to_string());
+    let difficulty = match args.next() {
+        Some(name) => Difficulty::from_name(&name).unwrap_or_else(|| fail(&format!("Неизвестный уровень '{}'", name))),
+        None => Difficulty::Hard,
+    };
+    let rules = match args.next() {
+        Some(name) => Rules::preset(&name).unwrap_or_else(|| fail(&format!("Неизвестные правила '{}'", name))),
+        None => Rules::classic(),
+    };
+
+    let mut rng = GameRng::seed_from_u64(rand::random());
+    let (mut peer, rules, host) = match mode.as_str() {
+        "host" => {
+            println!("Ожидание соперника на {}...", addr);
+            let accepted = listen(&addr).unwrap_or_else(|e| fail(&e));
+            let peer = accepted.recv().map_err(|e| e.to_string()).and_then(|peer| peer).unwrap_or_else(|e| fail(&e));
+            (peer, rules, true)
+        }
+        "join" => {
+            let peer = Peer::connect(&addr).unwrap_or_else(|e| fail(&e));
+            let rules = peer.receive().and_then(|hello| NetGame::rules_from_hello(&hello)).unwrap_or_else(|e| fail(&e));
+            (peer, rules, false)
+        }
+        _ => fail("Использование: net_player host|join [адрес] [уровень] [правила]"),
+    };
+    println!("Соперник подключен, правила: {}", rules.name);
+
+    let mut board = Board::with_rules(rules.clone());
+    board.place_ships_randomly(&mut rng).unwrap_or_else(|e| fail(&e));
+    let mut game = NetGame::new(rules, board, host, &mut rng);
+    let strategy = difficulty.strategy();
+
+    let mut outgoing = game.opening_messages();
+    while game.phase != Phase::Over {
+        for message in outgoing.drain(..) {
+            peer.send(&message).unwrap_or_else(|e| fail(&e));
+        }
+        if game.phase == Phase::MyTurn {
+            let cells = strategy.choose_volley(&game.enemy_board, game.volley_size(), &mut rng);
+            outgoing.push(game.fire(&cells).unwrap_or_else(|e| fail(&e)));
+            continue;
+        }
+        let message = peer.receive().unwrap_or_else(|e| fail(&e));
+        outgoing = game.handle(message).unwrap_or_else(|e| fail(&e));
+    }
+    for message in outgoing {
+        let _ = peer.send(&message);
+    }
+
+    let outcome = game.outcome.expect("Партия закончена без итога");
+    println!("{}", if outcome.won { "Победа!" } else { "Поражение." });
+    match outcome.verification {
+        Ok(()) => println!("Расстановка соперника проверена"),
+        Err(e) => fail(&format!("Соперник нарушил правила: {}", e)),
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,263 @@
+use std::io::{self, Write};
+use std::time::Duration;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use sea_battle::ai::Difficulty;
+use sea_battle::board::AutoShipPlacer;
+use sea_battle::game::{Game, ShotStrategy, Turn};
+use sea_battle::render::{BoardRenderer, BoardView, CellView};
+use sea_battle::replay::Side;
+use sea_battle::rules::Rules;
+use sea_battle::ship::ShootResult;
+use sea_battle::stats::{GameRecord, Profile, DEFAULT_STATS_PATH};
+use std::path::Path;
+
+// Морской бой в терминале: работает по SSH и там, где нет графического окружения.
+// Запуск: cargo run --bin sea_battle_tui --no-default-features -- [уровень] [правила] [зерно]
+// Стрелки или hjkl - курсор, Enter или пробел - выстрел (в залпе - выбор клетки),
+// f - залп, n - новая партия, q - выход
+
+// ширина клетки в символах
+const CELL_WIDTH: u16 = 3;
+// строк журнала выстрелов под полями
+const LOG_LINES: usize = 8;
+// пауза перед ходом компьютера, чтобы было видно, что происходит
+const COMPUTER_DELAY: Duration = Duration::from_millis(300);
+
+fn cell_color(cell: CellView) -> Color {
+    match cell {
+        CellView::Water => Color::Blue,
+        CellView::Ship => Color::Green,
+        CellView::Selected => Color::Yellow,
+        CellView::Miss => Color::DarkGrey,
+        CellView::Hit => Color::Red,
+        CellView::Sunk => Color::DarkRed,
+    }
+}
+
+// Поле в терминале с подписями строк и столбцов, начиная с позиции (x, y)
+struct TerminalRenderer<'w, W: Write> {
+    out: &'w mut W,
+    x: u16,
+    y: u16,
+}
+
+impl<W: Write> BoardRenderer for TerminalRenderer<'_, W> {
+    type Output = io::Result<()>;
+
+    fn render(&mut self, board: &sea_battle::board::Board, view: &BoardView) -> io::Result<()> {
+        queue!(self.out, MoveTo(self.x + 2, self.y))?;
+        for col in 0..board.width {
+            queue!(self.out, Print(format!("{:^3}", col)))?;
+        }
+        for row in 0..board.height {
+            let letter = (b'A' + row as u8) as char;
+            queue!(self.out, MoveTo(self.x, self.y + 1 + row as u16), Print(format!("{} ", letter)))?;
+            for col in 0..board.width {
+                let cell = CellView::of(board, row, col, view);
+                let (left, right) = if view.cursor == Some((row, col)) { ('[', ']') } else { (' ', ' ') };
+                queue!(
+                    self.out,
+                    Print(left),
+                    SetForegroundColor(cell_color(cell)),
+                    Print(cell.symbol()),
+                    ResetColor,
+                    Print(right),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Tui {
+    game: Game,
+    difficulty: Difficulty,
+    rules: Rules,
+    strategy: Box<dyn ShotStrategy>,
+    cursor: (usize, usize),
+    volley: Vec<(usize, usize)>,
+    message: String,
+}
+
+impl Tui {
+    fn new(difficulty: Difficulty, rules: Rules, seed: u64) -> Self {
+        let game = Game::with_rules(rules.clone(), seed, &AutoShipPlacer, &AutoShipPlacer);
+        Tui {
+            game,
+            difficulty,
+            rules,
+            strategy: difficulty.strategy(),
+            cursor: (0, 0),
+            volley: Vec::new(),
+            message: "Ваш ход".to_string(),
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let board_width = 2 + self.game.player_board.width as u16 * CELL_WIDTH;
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        queue!(
+            out,
+            SetAttribute(Attribute::Bold),
+            Print(format!("Морской бой - {}, {} (зерно {})", self.rules.name, self.difficulty.name(), self.game.seed)),
+            SetAttribute(Attribute::Reset),
+            MoveTo(0, 1),
+            Print(&self.message),
+        )?;
+        if self.rules.salvo && self.game.turn == Turn::Player {
+            let size = self.game.volley_size(Side::Player);
+            queue!(out, Print(format!("  Залп: выбрано {} из {} (f - огонь)", self.volley.len(), size)))?;
+        }
+
+        queue!(out, MoveTo(0, 3), Print("Ваше поле"), MoveTo(board_width + 4, 3), Print("Поле компьютера"))?;
+        TerminalRenderer { out, x: 0, y: 4 }.render(&self.game.player_board, &BoardView::own())?;
+        let enemy_view = BoardView { selected: &self.volley, cursor: Some(self.cursor), ..BoardView::enemy() };
+        TerminalRenderer { out, x: board_width + 4, y: 4 }.render(&self.game.computer_board, &enemy_view)?;
+
+        // журнал последних выстрелов из записи партии
+        let log_y = 6 + self.game.player_board.height as u16;
+        queue!(out, MoveTo(0, log_y), Print("Журнал:"))?;
+        let shots = &self.game.replay.shots;
+        for (index, shot) in shots.iter().skip(shots.len().saturating_sub(LOG_LINES)).enumerate() {
+            let shooter = match shot.shooter {
+                Side::Player => "Вы",
+                Side::Computer => "Компьютер",
+            };
+            let result = match shot.result {
+                ShootResult::Miss => "промах",
+                ShootResult::Hit => "попадание",
+                ShootResult::Destroy => "корабль потоплен",
+            };
+            let letter = (b'A' + shot.row as u8) as char;
+            queue!(out, MoveTo(2, log_y + 1 + index as u16), Print(format!("{}: {}{} - {}", shooter, letter, shot.col, result)))?;
+        }
+        out.flush()
+    }
+
+    fn move_cursor(&mut self, d_row: isize, d_col: isize) {
+        let board = &self.game.computer_board;
+        let row = (self.cursor.0 as isize + d_row).clamp(0, board.height as isize - 1);
+        let col = (self.cursor.1 as isize + d_col).clamp(0, board.width as isize - 1);
+        self.cursor = (row as usize, col as usize);
+    }
+
+    // выстрел по курсору, в залпе - выбор клетки
+    fn select(&mut self) {
+        if self.game.turn != Turn::Player {
+            return;
+        }
+        let cell = self.cursor;
+        if !self.rules.salvo {
+            self.fire(vec![cell]);
+        } else if let Some(index) = self.volley.iter().position(|&selected| selected == cell) {
+            self.volley.remove(index);
+        } else if self.game.computer_board.shots[cell.0][cell.1].is_some() {
+            self.message = "В эту клетку уже стреляли".to_string();
+        } else if self.volley.len() < self.game.volley_size(Side::Player) {
+            self.volley.push(cell);
+        }
+    }
+
+    fn fire(&mut self, cells: Vec<(usize, usize)>) {
+        match self.game.player_volley(&cells) {
+            Ok(_) => {
+                self.volley.clear();
+                self.message = self.status();
+                self.record_if_over();
+            }
+            Err(e) => self.message = e,
+        }
+    }
+
+    fn computer_turn(&mut self) {
+        self.game.computer_volley(&*self.strategy);
+        self.message = self.status();
+        self.record_if_over();
+    }
+
+    // итог партии попадает в тот же профиль, что и у окна игры
+    fn record_if_over(&mut self) {
+        if self.game.turn != Turn::Over {
+            return;
+        }
+        let path = Path::new(DEFAULT_STATS_PATH);
+        let record = GameRecord::from_game(&self.game, self.difficulty.name());
+        if let Err(e) = Profile::append(path, record) {
+            self.message = format!("{} ({})", self.message, e);
+        }
+    }
+
+    fn status(&self) -> String {
+        match self.game.turn {
+            Turn::Player => "Ваш ход".to_string(),
+            Turn::Computer => "Ход компьютера...".to_string(),
+            Turn::Over if self.game.computer_board.all_ships_destroyed() => "Вы победили! n - новая партия, q - выход".to_string(),
+            Turn::Over => "Компьютер победил! n - новая партия, q - выход".to_string(),
+        }
+    }
+}
+
+fn run(tui: &mut Tui) -> io::Result<()> {
+    let mut out = io::stdout();
+    loop {
+        tui.draw(&mut out)?;
+
+        let timeout = if tui.game.turn == Turn::Computer { COMPUTER_DELAY } else { Duration::from_secs(3600) };
+        if !event::poll(timeout)? {
+            if tui.game.turn == Turn::Computer {
+                tui.computer_turn();
+            }
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => tui.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => tui.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => tui.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => tui.move_cursor(0, 1),
+            KeyCode::Enter | KeyCode::Char(' ') => tui.select(),
+            KeyCode::Char('f') if tui.rules.salvo && tui.game.turn == Turn::Player => {
+                let volley = tui.volley.clone();
+                tui.fire(volley);
+            }
+            KeyCode::Char('n') if tui.game.turn == Turn::Over => {
+                *tui = Tui::new(tui.difficulty, tui.rules.clone(), rand::random());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let difficulty = args.next().map(|name| Difficulty::from_name(&name).ok_or(name));
+    let rules = args.next().map(|name| Rules::preset(&name).ok_or(name));
+    let seed = args.next().and_then(|arg| arg.parse().ok()).unwrap_or_else(rand::random);
+    let (difficulty, rules) = match (difficulty.unwrap_or(Ok(Difficulty::default())), rules.unwrap_or(Ok(Rules::classic()))) {
+        (Ok(difficulty), Ok(rules)) => (difficulty, rules),
+        (Err(name), _) | (_, Err(name)) => {
+            eprintln!("Неизвестный уровень или правила: '{}'", name);
+            eprintln!("Использование: sea_battle_tui [уровень] [правила] [зерно]");
+            std::process::exit(2);
+        }
+    };
+
+    let mut tui = Tui::new(difficulty, rules, seed);
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+    let result = run(&mut tui);
+    execute!(io::stdout(), Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
@@ -0,0 +1,76 @@
+use sea_battle::ai::{Difficulty, Layout};
+use sea_battle::rules::Rules;
+use sea_battle::tournament::{report, run, write_csv, Contender};
+use std::path::Path;
+use std::thread;
+use std::time::Instant;
+
+// Турнир двух компьютеров: каждый участник - уровень и способ расстановки.
+// Запуск: cargo run --release --bin tournament -- [участник_а] [участник_б] [партий] [зерно] [правила] [файл.csv]
+// Участник записывается как "Сложный/Края"; без расстановки берется случайная
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let first = contender(args.next(), Contender { difficulty: Difficulty::Hard, layout: Layout::Random });
+    let second = contender(args.next(), Contender { difficulty: Difficulty::Medium, layout: Layout::Random });
+    let games: usize = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(1000);
+    let seed: u64 = args.next().and_then(|arg| arg.parse().ok()).unwrap_or(0);
+    let rules = match args.next() {
+        Some(name) => Rules::preset(&name).unwrap_or_else(|| {
+            let names: Vec<String> = Rules::presets().into_iter().map(|rules| rules.name).collect();
+            fail(&format!("Неизвестные правила '{}'. Доступны: {}", name, names.join(", ")))
+        }),
+        None => Rules::classic(),
+    };
+    let csv = args.next().unwrap_or_else(|| "tournament.csv".to_string());
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+
+    let contenders = [first, second];
+    println!("Партий: {}, зерно: {}, правила: {}, потоков: {}", games, seed, rules.name, threads);
+    let started = Instant::now();
+    let results = run(contenders, &rules, games, seed, threads);
+    println!("Сыграно за {:.1} с", started.elapsed().as_secs_f64());
+
+    println!(
+        "{:<20} {:>7} {:>7} {:>9} {:>7} {:>7} {:>7} {:>10}",
+        "Участник", "Побед", "Доля", "Выстр.", "p50", "p90", "p99", "До победы"
+    );
+    for (index, contender) in contenders.iter().enumerate() {
+        let report = report(&results, index);
+        let to_win = report.mean_shots_to_win.map_or("-".to_string(), |shots| format!("{:.2}", shots));
+        println!(
+            "{:<20} {:>7} {:>6.1}% {:>9.2} {:>7} {:>7} {:>7} {:>10}",
+            contender.name(),
+            report.wins,
+            report.win_rate * 100.0,
+            report.mean_shots,
+            report.median_shots,
+            report.p90_shots,
+            report.p99_shots,
+            to_win,
+        );
+    }
+
+    write_csv(&results, contenders, Path::new(&csv)).unwrap_or_else(|e| fail(&e));
+    println!("Партии записаны в {}", csv);
+}
+
+fn contender(arg: Option<String>, default: Contender) -> Contender {
+    match arg {
+        Some(name) => Contender::from_name(&name).unwrap_or_else(|| {
+            let levels: Vec<&str> = Difficulty::ALL.iter().map(|difficulty| difficulty.name()).collect();
+            let layouts: Vec<&str> = Layout::ALL.iter().map(|layout| layout.name()).collect();
+            fail(&format!(
+                "Неизвестный участник '{}'. Уровни: {}; расстановки: {}",
+                name,
+                levels.join(", "),
+                layouts.join(", ")
+            ))
+        }),
+        None => default,
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(2);
+}
@@ -1,55 +1,94 @@
+use crate::render::{BoardRenderer, BoardView, TextRenderer};
+use crate::rules::Rules;
 use crate::ship::{Ship, ShootResult};
+use crate::game::GameRng;
 use rand::Rng;
-use std::{io, rc::Rc, cell::RefCell};
-use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::io;
 
+// Клетка с кораблем хранит его номер в списке ships, поэтому поле
+// сохраняется в файл целиком, а клон не зависит от оригинала
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Board {
     pub shots: Vec<Vec<Option<bool>>>,
-    pub cells: Vec<Vec<Option<Rc<RefCell<Ship>>>>>,
+    pub cells: Vec<Vec<Option<usize>>>,
+    pub ships: Vec<Ship>,
     pub width: usize,
     pub height: usize,
+    pub rules: Rules,
 }
 
 impl Board {
+    // поле заданного размера с классическим флотом
     pub fn new(width: usize, height: usize) -> Self {
+        Self::with_rules(Rules::classic().with_size(width, height))
+    }
+
+    pub fn with_rules(rules: Rules) -> Self {
         Board {
-            shots: vec![vec![None; width]; height],
-            cells: vec![vec![None; width]; height],
-            width,
-            height,
+            shots: vec![vec![None; rules.width]; rules.height],
+            cells: vec![vec![None; rules.width]; rules.height],
+            ships: Vec::new(),
+            width: rules.width,
+            height: rules.height,
+            rules,
         }
     }
 
-    pub fn place_ships_randomly(&mut self)-> Result<(), String> {
+    // расстановка с переданным генератором, чтобы партии можно было повторить
+    pub fn place_ships_randomly(&mut self, rng: &mut impl Rng) -> Result<(), String> {
         const MAX_ATTEMPTS: usize = 1000;
 
-        let chips_to_place = vec![
-            (4, 1), // 1 четырёхпалубный корабль
-            (3, 2), // 2 трёхпалубных корабля
-            (2, 3), // 3 двухпалубных корабля
-            (1, 4), // 4 однопалубных корабля
-        ];
-
-        for &(size, count) in &chips_to_place {
-            for _ in 0..count {
-                let mut attempts = 0;
-                while !self.place_random_ship(size) {
-                    attempts += 1;
-                    if attempts > MAX_ATTEMPTS {
-                        return Err("Не удалось разместить все корабли".to_string());
-                    }
+        for size in self.rules.ship_sizes() {
+            let mut attempts = 0;
+            while !self.place_random_ship(size, rng) {
+                attempts += 1;
+                if attempts > MAX_ATTEMPTS {
+                    return Err("Не удалось разместить все корабли".to_string());
                 }
             }
         }
         Ok(())
     }
 
-    pub fn place_random_ship(&mut self, size: usize) -> bool {
-        let mut rng = rand::rng();
+    // Каждый корабль, от больших к меньшим, ставится в случайную из свободных позиций.
+    // Если для очередного корабля места не осталось, ошибка возвращается сразу
+    pub fn place_ships_in_free_positions(&mut self, rng: &mut impl Rng) -> Result<(), String> {
+        for size in self.rules.ship_sizes() {
+            let positions = self.positions(size);
+            if positions.is_empty() {
+                return Err("Не удалось разместить все корабли".to_string());
+            }
+            let coords = positions[rng.random_range(0..positions.len())].clone();
+            self.add_ship(Ship::new(coords, size));
+        }
+        Ok(())
+    }
+
+    // все позиции, куда можно поставить корабль такого размера
+    pub fn positions(&self, size: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut positions = Vec::new();
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let horizontal: Vec<_> = (col..col + size).map(|c| (row, c)).collect();
+                let vertical: Vec<_> = (row..row + size).map(|r| (r, col)).collect();
+                positions.push(horizontal);
+                // однопалубный корабль в обеих ориентациях один и тот же
+                if size > 1 {
+                    positions.push(vertical);
+                }
+            }
+        }
+        positions.retain(|coords| self.can_place_ship(coords));
+        positions
+    }
+
+    pub fn place_random_ship(&mut self, size: usize, rng: &mut impl Rng) -> bool {
         let vertical = rng.random_bool(0.5);
 
-        let max_row = if vertical { self.height - size } else { self.height };
-        let max_col = if vertical { self.width } else { self.width - size };
+        // корабль может стоять вплотную к нижнему и правому краю
+        let max_row = if vertical { (self.height + 1).saturating_sub(size) } else { self.height };
+        let max_col = if vertical { self.width } else { (self.width + 1).saturating_sub(size) };
 
         if max_row == 0 || max_col == 0 {
             return false; // не хватает места
@@ -67,10 +106,7 @@ impl Board {
     
             if self.can_place_ship(&coords) {
                 //размещаем корабль
-                let ship = Rc::new(RefCell::new(Ship::new(coords.clone(), size)));
-                for &(r, c) in &coords {
-                    self.cells[r][c] = Some(Rc::clone(&ship));
-                }
+                self.add_ship(Ship::new(coords, size));
                 return true;
             }
         }
@@ -78,24 +114,33 @@ impl Board {
         false
     }
 
-    pub fn place_ship(&mut self, ship: Rc<RefCell<Ship>>) -> Result<(), String> {
-        let ship_ref = ship.borrow_mut();
-        let coords = &ship_ref.coords;
-        let size = ship_ref.size;
-        if coords.len() != size {
+    pub fn place_ship(&mut self, ship: Ship) -> Result<(), String> {
+        if ship.coords.len() != ship.size {
             return Err("Количество координат не соответствует размеру корабля".to_string());
         }
 
-        if !self.can_place_ship(&coords) {
+        if !self.can_place_ship(&ship.coords) {
             return Err("Корабль пересекается с другим кораблем или координаты корабля выходят за границы поля".to_string());
         }
 
-        for &(row, col) in coords {
-            self.cells[row][col] = Some(Rc::clone(&ship));
-        }
+        self.add_ship(ship);
         Ok(())
     }
 
+    // добавляет корабль в список и отмечает его номер в клетках, без проверок
+    fn add_ship(&mut self, ship: Ship) {
+        let id = self.ships.len();
+        for &(row, col) in &ship.coords {
+            self.cells[row][col] = Some(id);
+        }
+        self.ships.push(ship);
+    }
+
+    // корабль в клетке, если он там есть
+    pub fn ship_at(&self, row: usize, col: usize) -> Option<&Ship> {
+        self.cells[row][col].map(|id| &self.ships[id])
+    }
+
     pub fn shoot(&mut self, row: usize, col: usize) -> ShootResult {
         if row >= self.height || col >= self.width {
             panic!("Выстрел за пределы поля!");
@@ -107,11 +152,11 @@ impl Board {
 
         self.shots[row][col] = Some(self.cells[row][col].is_some());
 
-        if let Some(ship) = &mut self.cells[row][col] {
-            let mut ship_ref = ship.borrow_mut();
-            if let Some(index) = ship_ref.coords.iter().position(|&coord| coord == (row, col)) {
-                ship_ref.hit(index);
-                if ship_ref.is_destroyed() {
+        if let Some(id) = self.cells[row][col] {
+            let ship = &mut self.ships[id];
+            if let Some(index) = ship.coords.iter().position(|&coord| coord == (row, col)) {
+                ship.hit(index);
+                if ship.is_destroyed() {
                     return ShootResult::Destroy;
                 } else {
                     return ShootResult::Hit;
@@ -124,17 +169,53 @@ impl Board {
     }
 
     pub fn all_ships_destroyed(&self) -> bool {
-        for row in &self.cells {
-            for cell in row {
-                if let Some(ship) = cell {
-                    let ship_ref = ship.borrow();
-                    if !ship_ref.is_destroyed() {
-                        return false;
-                    }
-                }
-            }
+        self.ships.iter().all(Ship::is_destroyed)
+    }
+
+    // координаты всех кораблей на поле в порядке расстановки
+    pub fn ship_coords(&self) -> Vec<Vec<(usize, usize)>> {
+        self.ships.iter().map(|ship| ship.coords.clone()).collect()
+    }
+
+    // Потопленный корабль соперника в сетевой игре: после Destroy его клетки становятся известны
+    pub fn mark_sunk(&mut self, coords: Vec<(usize, usize)>) -> Result<(), String> {
+        if coords.is_empty() || coords.iter().any(|&(row, col)| row >= self.height || col >= self.width) {
+            return Err("Неверные координаты потопленного корабля".to_string());
+        }
+        let mut ship = Ship::new(coords.clone(), coords.len());
+        for index in 0..coords.len() {
+            ship.hit(index);
         }
-        return true
+        for &(row, col) in &coords {
+            self.shots[row][col] = Some(true);
+        }
+        self.add_ship(ship);
+        Ok(())
+    }
+
+    // сколько кораблей еще не потоплено
+    pub fn surviving_ships(&self) -> usize {
+        self.ship_coords().len() - self.sunk_ship_sizes().len()
+    }
+
+    // клетки, по которым еще не стреляли
+    pub fn unshot_cells(&self) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|row| (0..self.width).map(move |col| (row, col)))
+            .filter(|&(row, col)| self.shots[row][col].is_none())
+            .collect()
+    }
+
+    // Клетка с попаданием по уже потопленному кораблю. Стреляющий узнает об этом
+    // из результата Destroy, поэтому стратегии могут на это опираться
+    pub fn is_sunk(&self, row: usize, col: usize) -> bool {
+        self.shots[row][col] == Some(true)
+            && self.ship_at(row, col).is_some_and(Ship::is_destroyed)
+    }
+
+    // размеры потопленных кораблей
+    pub fn sunk_ship_sizes(&self) -> Vec<usize> {
+        self.ships.iter().filter(|ship| ship.is_destroyed()).map(|ship| ship.size).collect()
     }
 
     pub fn can_place_ship(&self, coords: &[(usize, usize)]) -> bool {
@@ -146,15 +227,17 @@ impl Board {
                 return false;
             }
         }
+        if self.rules.ships_may_touch {
+            return true;
+        }
         for &(row, col) in coords {
             for dr in -1..=1 {
                 for dc in -1..=1 {
                     let nr = row as isize + dr;
                     let nc = col as isize + dc;
-                    if nr >= 0 && nr < self.height as isize && nc >=0 && nc < self.width as isize {
-                        if self.cells[nr as usize][nc as usize].is_some() {
-                            return false;
-                        }
+                    if nr >= 0 && nr < self.height as isize && nc >=0 && nc < self.width as isize
+                        && self.cells[nr as usize][nc as usize].is_some() {
+                        return false;
                     }
                 }
             }
@@ -162,112 +245,18 @@ impl Board {
         true
     }
 
-    pub fn print_board(&self, hide_ships: bool) {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                if let Some(_ship) = &self.cells[row][col] {
-                    if hide_ships {
-                        print!("~ ");
-                    } else {
-                        print!("■ ");
-                    }
-                } else {
-                    print!("~ ");
-                }
-            }
-            println!();
-        }
-    }
-
-    pub fn draw_board(&self, ui: &mut egui::Ui, hide_ships: bool) -> Option<(usize, usize)> {
-        let cell_size = 30.0;
-        let mut clicked_cell = None;
-
-        egui::Grid::new(format!("board_grid_{}", if hide_ships { "computer" } else { "player" }))
-            .min_col_width(cell_size)
-            .min_row_height(cell_size)
-            .show(ui, |ui| {
-                ui.label("");
-                for col in 0..self.width {
-                    ui.label(format!("{}", col));
-                }
-                ui.end_row();
-
-                for row in 0..self.height {
-                    let letter = (b'A' + row as u8) as char;
-                    ui.label(format!("{}", letter));
-                    for col in 0..self.width {
-                        ui.push_id(format!("cell_{}_{}_{}", row, col, hide_ships), |ui| {
-                            let cell = &self.cells[row][col];
-                            let shot = self.shots[row][col];
-                            let color = match shot {
-                                Some(false) => egui::Color32::ORANGE,
-                                Some(true) => {
-                                    if let Some(ship) = cell {
-                                        let ship_ref = ship.borrow();
-                                        if ship_ref.is_destroyed() {
-                                            egui::Color32::DARK_RED // Потопленный корабль
-                                        } else {
-                                            egui::Color32::RED // Подбитый корабль
-                                        }
-                                    } else {
-                                        egui::Color32::ORANGE // Попадание в пустую клетку
-                                    }
-                                },
-                                None => {
-                                    if let Some(_ship) = cell {
-                                        if !hide_ships {
-                                            egui::Color32::GREEN // Живой корабль (виден только для игрока)
-                                        } else {
-                                            egui::Color32::DARK_BLUE // Скрытая клетка
-                                        }
-                                    } else {
-                                        egui::Color32::DARK_BLUE // Пустая клетка
-                                    }
-                                }
-                            };
-
-                            let rect = egui::Rect::from_min_size(
-                                egui::Pos2::new(
-                                    if !hide_ships { (col + 1) as f32 * 1.25 * cell_size } else { (col as f32 + 12.5) * 1.25 * cell_size },
-                                    (row + 2) as f32 * 1.25 * cell_size,
-                                ),
-                                egui::Vec2::splat(25.0),
-                            );
-
-                            ui.painter().rect_filled(rect, 0.0, color);
-
-                            let id = ui.make_persistent_id((row, col, hide_ships));
-                            let response = ui.interact(rect, id, egui::Sense::click());
-                            if response.clicked() {
-                                clicked_cell = Some((row, col));
-                            }
-                        });
-                    }
-                    ui.end_row();
-                }
-            });
-        clicked_cell
-    }
 }
 
 pub fn place_ships_manually(board: &mut Board) -> Result<(), String> {
-    let chips_to_place = vec![
-        (4, 1),
-        (3, 2),
-        (2, 3),
-        (1, 4),
-    ];
-
-    for &(size, count) in &chips_to_place {
+    let fleet = board.rules.fleet.clone();
+    for &(size, count) in &fleet {
         for i in 0..count {
             println!(
                 "\nРазместите {}-палубный корабль (осталось {}).",
                 size,
                 count - i
             );
-            board.print_board(false);
-            // board.draw_board(false);
+            print!("{}", TextRenderer.render(board, &BoardView::own()));
 
             loop {
                 println!("Введите координаты через пробел (например A1 A2 A3):");
@@ -275,9 +264,8 @@ pub fn place_ships_manually(board: &mut Board) -> Result<(), String> {
                 let mut input = String::new();
                 io::stdin().read_line(&mut input).unwrap();
                 let coords: Vec<Result<(usize, usize), String>> = input
-                    .trim()
                     .split_whitespace()
-                    .map(|coord| parse_coordinates(coord))
+                    .map(|coord| parse_coordinates(coord, board.width, board.height))
                     .collect();
 
                 let mut valid_coords = Vec::new();
@@ -307,7 +295,7 @@ pub fn place_ships_manually(board: &mut Board) -> Result<(), String> {
                     continue;
                 }
         
-                let ship = Rc::new(RefCell::new(Ship::new(valid_coords.clone(), size)));
+                let ship = Ship::new(valid_coords.clone(), size);
         
                 if let Err(err) = board.place_ship(ship) {
                     println!("{}", err);
@@ -320,20 +308,20 @@ pub fn place_ships_manually(board: &mut Board) -> Result<(), String> {
     Ok(())
 }
 
-pub fn parse_coordinates(input: &str) -> Result<(usize, usize), String> {
-    let chars: Vec<char> = input.chars().collect();
-    if chars.len() != 2 {
-        return Err("Неверный формат координат. Введите два символа (например, 'A5').".to_string());
-    }
-
-    let row = match chars[0] {
-        'A'..='J' => Ok(chars[0] as usize - 'A' as usize),
-        _ => Err("Первая буква должна быть от 'A' до 'J'.".to_string()),
-    }?;
-    let col = match chars[1].to_digit(10) {
-        Some(num) if num <= 10 => Ok((num) as usize),
-        _ => Err("Второй символ должен быть числом от 0 до 9.".to_string()),
-    }?;
+// Координаты вида "A5" или "C12": буква строки и номер столбца с нуля
+pub fn parse_coordinates(input: &str, width: usize, height: usize) -> Result<(usize, usize), String> {
+    let mut chars = input.chars();
+    let last_row = (b'A' + height.saturating_sub(1) as u8) as char;
+    let row = match chars.next() {
+        Some(letter) if letter.is_ascii_uppercase() && (letter as usize - 'A' as usize) < height => {
+            letter as usize - 'A' as usize
+        }
+        _ => return Err(format!("Первая буква должна быть от 'A' до '{}'.", last_row)),
+    };
+    let col = match chars.as_str().parse::<usize>() {
+        Ok(num) if num < width => num,
+        _ => return Err(format!("После буквы должно идти число от 0 до {}.", width.saturating_sub(1))),
+    };
     Ok((row, col))
 }
 
@@ -378,30 +366,50 @@ pub fn validate_ship_coordinates(coords: &[(usize, usize)]) -> Result<(), String
 }
 
 pub trait ShipPlacer {
-    fn place_ships(&self, board: &mut Board) -> Result<(), String>;
+    fn place_ships(&self, board: &mut Board, rng: &mut GameRng) -> Result<(), String>;
 }
 
+// сколько раз расстановка начинается заново, прежде чем автоматическое размещение сдается
+pub const PLACEMENT_ATTEMPTS: usize = 100;
+
 pub struct AutoShipPlacer;
 impl ShipPlacer for AutoShipPlacer {
-    fn place_ships(&self, board: &mut Board) -> Result<(), String> {
-        if board.place_ships_randomly().is_err() {
-            println!("Не удалось разместить корабли автоматически. Перегенерация...");
-            loop {
-                if board.place_ships_randomly().is_ok() {
-                    break;
-                }
+    fn place_ships(&self, board: &mut Board, rng: &mut GameRng) -> Result<(), String> {
+        if board.place_ships_randomly(rng).is_ok() {
+            return Ok(());
+        }
+        println!("Не удалось разместить корабли автоматически. Перегенерация...");
+        for _ in 0..PLACEMENT_ATTEMPTS {
+            *board = Board::with_rules(board.rules.clone());
+            if board.place_ships_in_free_positions(rng).is_ok() {
+                return Ok(());
             }
         }
-        Ok(())
+        Err("Не удалось разместить все корабли".to_string())
     }
 }
 
 pub struct ManualShipPlacer;
 impl ShipPlacer for ManualShipPlacer {
-    fn place_ships(&self, board: &mut Board) -> Result<(), String> {
+    fn place_ships(&self, board: &mut Board, _rng: &mut GameRng) -> Result<(), String> {
         if place_ships_manually(board).is_err() {
             panic!("Не удалось разместить корабли вручную");
         }
         Ok(())
     }
 }
+
+// Готовая расстановка, например собранная в редакторе расстановки
+pub struct LayoutShipPlacer {
+    pub ships: Vec<Vec<(usize, usize)>>,
+}
+
+impl ShipPlacer for LayoutShipPlacer {
+    fn place_ships(&self, board: &mut Board, _rng: &mut GameRng) -> Result<(), String> {
+        for coords in &self.ships {
+            validate_ship_coordinates(coords)?;
+            board.place_ship(Ship::new(coords.clone(), coords.len()))?;
+        }
+        Ok(())
+    }
+}
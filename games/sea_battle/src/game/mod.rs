@@ -2,63 +2,199 @@ use crate::board::Board;
 use crate::ship::ShootResult;
 use rand::Rng;
 use crate::board::ShipPlacer;
+use crate::render::{BoardRenderer, BoardView, TextRenderer};
+use crate::replay::{Replay, Side};
+use crate::rules::Rules;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::fmt::Formatter;
 
+// Генератор партии. Его состояние сохраняется вместе с партией, чего StdRng не умеет,
+// а с тем же зерном он дает те же числа, поэтому старые повторы воспроизводятся
+pub type GameRng = rand_chacha::ChaCha12Rng;
+
+// чей сейчас ход
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Turn {
+    Player,
+    Computer,
+    Over,
+}
+
+// Состояние партии целиком, включая генератор: сохраненная партия
+// после загрузки продолжается так же, как продолжилась бы без сохранения
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     pub player_board: Board,
     pub computer_board: Board,
+    pub rules: Rules,
+    pub seed: u64,
+    pub replay: Replay,
+    pub turn: Turn,
+    rng: GameRng,
 }
 
 impl Debug for Game {
-    fn fmt(&self, _: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
-        self.player_board.print_board(false);
-        println!("\n");
-        self.computer_board.print_board(false);
-        Ok(())
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        writeln!(f, "{}", TextRenderer.render(&self.player_board, &BoardView::own()))?;
+        write!(f, "{}", TextRenderer.render(&self.computer_board, &BoardView::own()))
     }
 }
 
+// Стратегия выстрелов компьютера. Случайность берется только из переданного генератора,
+// поэтому партия с тем же зерном повторяется выстрел в выстрел
 pub trait ShotStrategy {
-    fn choose_shot(&self, board: &Board) -> (usize, usize);
+    fn choose_shot(&self, board: &Board, rng: &mut GameRng) -> (usize, usize);
+
+    // Залп из count разных клеток. Результаты станут известны только после залпа,
+    // поэтому выбранные клетки помечаются на копии поля как промахи
+    fn choose_volley(&self, board: &Board, count: usize, rng: &mut GameRng) -> Vec<(usize, usize)> {
+        let mut view = board.clone();
+        let mut volley = Vec::with_capacity(count);
+        while volley.len() < count && !view.unshot_cells().is_empty() {
+            let (row, col) = self.choose_shot(&view, rng);
+            view.shots[row][col] = Some(false);
+            volley.push((row, col));
+        }
+        volley
+    }
 }
 
+// случайный выстрел по клеткам, куда еще не стреляли
 pub struct RandomShotStrategy;
 
 impl ShotStrategy for RandomShotStrategy {
-    fn choose_shot(&self, board: &Board) -> (usize, usize) {
-        let mut rng = rand::rng();
-        let row = rng.random_range(0..board.height);
-        let col = rng.random_range(0..board.width);
-        (row, col)
+    fn choose_shot(&self, board: &Board, rng: &mut GameRng) -> (usize, usize) {
+        let cells = board.unshot_cells();
+        cells[rng.random_range(0..cells.len())]
     }
 }
 
 impl Game {
     pub fn new(player_placer: &dyn ShipPlacer, computer_placer: &dyn ShipPlacer) -> Self {
-        let mut game = Game {
-            player_board: Board::new(10, 10),
-            computer_board: Board::new(10, 10),
-        };
+        Self::with_seed(rand::random(), player_placer, computer_placer)
+    }
 
-        if let Err(err) = player_placer.place_ships(&mut game.player_board) {
-            panic!("Ошибка при размещении кораблей игрока: {}", err);
-        }
+    // партия с заданным зерном: расстановка и выстрелы компьютера всегда одинаковые
+    pub fn with_seed(seed: u64, player_placer: &dyn ShipPlacer, computer_placer: &dyn ShipPlacer) -> Self {
+        Self::with_rules(Rules::classic(), seed, player_placer, computer_placer)
+    }
 
-        if let Err(err) = computer_placer.place_ships(&mut game.computer_board) {
-            panic!("Ошибка при размещении кораблей коспьютера: {}", err);
-        }
+    pub fn with_rules(
+        rules: Rules,
+        seed: u64,
+        player_placer: &dyn ShipPlacer,
+        computer_placer: &dyn ShipPlacer,
+    ) -> Self {
+        Self::try_with_rules(rules, seed, player_placer, computer_placer).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    // то же, но ошибку расстановки возвращает, чтобы интерфейс мог ее показать
+    pub fn try_with_rules(
+        rules: Rules,
+        seed: u64,
+        player_placer: &dyn ShipPlacer,
+        computer_placer: &dyn ShipPlacer,
+    ) -> Result<Self, String> {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut player_board = Board::with_rules(rules.clone());
+        let mut computer_board = Board::with_rules(rules.clone());
+
+        player_placer
+            .place_ships(&mut player_board, &mut rng)
+            .map_err(|err| format!("Ошибка при размещении кораблей игрока: {}", err))?;
+        computer_placer
+            .place_ships(&mut computer_board, &mut rng)
+            .map_err(|err| format!("Ошибка при размещении кораблей коспьютера: {}", err))?;
+
+        let replay = Replay::new(seed, &rules, &player_board, &computer_board);
+        Ok(Game { player_board, computer_board, rules, seed, replay, turn: Turn::Player, rng })
+    }
+
+    // переходит ли ход к сопернику после выстрела с таким результатом
+    pub fn passes_turn(&self, result: ShootResult) -> bool {
+        self.rules.passes_turn(&[result])
+    }
 
-        game
+    // Сколько выстрелов в залпе стороны: в режиме залпа по числу уцелевших кораблей,
+    // но не больше, чем осталось клеток, иначе один
+    pub fn volley_size(&self, shooter: Side) -> usize {
+        if !self.rules.salvo {
+            return 1;
+        }
+        let (own, target) = match shooter {
+            Side::Player => (&self.player_board, &self.computer_board),
+            Side::Computer => (&self.computer_board, &self.player_board),
+        };
+        own.surviving_ships().min(target.unshot_cells().len())
     }
 
     pub fn player_shoot(&mut self, row: usize, col: usize) -> ShootResult {
-        self.computer_board.shoot(row, col)
+        let result = self.computer_board.shoot(row, col);
+        self.replay.record(Side::Player, row, col, result);
+        self.end_turn(Side::Player, self.passes_turn(result));
+        result
     }
 
     pub fn computer_shoot(&mut self, strategy: &dyn ShotStrategy) -> ShootResult {
-        let (row, col) = strategy.choose_shot(&self.player_board);
-        self.player_board.shoot(row, col)
+        let (row, col) = strategy.choose_shot(&self.player_board, &mut self.rng);
+        let result = self.player_board.shoot(row, col);
+        self.replay.record(Side::Computer, row, col, result);
+        self.end_turn(Side::Computer, self.passes_turn(result));
+        result
+    }
+
+    // Залп игрока: все клетки разные, еще не обстреляны, и их ровно volley_size.
+    // Результаты применяются после проверки всего залпа
+    pub fn player_volley(&mut self, cells: &[(usize, usize)]) -> Result<Vec<ShootResult>, String> {
+        if self.turn != Turn::Player {
+            return Err("Сейчас не ваш ход".to_string());
+        }
+        let expected = self.volley_size(Side::Player);
+        if cells.len() != expected {
+            return Err(format!("В залпе должно быть выстрелов: {}", expected));
+        }
+        for (index, &(row, col)) in cells.iter().enumerate() {
+            if row >= self.computer_board.height || col >= self.computer_board.width {
+                return Err("Выстрел за пределы поля".to_string());
+            }
+            if self.computer_board.shots[row][col].is_some() || cells[..index].contains(&(row, col)) {
+                return Err("В эту клетку уже стреляли".to_string());
+            }
+        }
+        Ok(self.fire_volley(Side::Player, cells))
+    }
+
+    pub fn computer_volley(&mut self, strategy: &dyn ShotStrategy) -> Vec<ShootResult> {
+        let count = self.volley_size(Side::Computer);
+        let cells = strategy.choose_volley(&self.player_board, count, &mut self.rng);
+        self.fire_volley(Side::Computer, &cells)
+    }
+
+    fn fire_volley(&mut self, shooter: Side, cells: &[(usize, usize)]) -> Vec<ShootResult> {
+        let target = match shooter {
+            Side::Player => &mut self.computer_board,
+            Side::Computer => &mut self.player_board,
+        };
+        let results: Vec<ShootResult> = cells.iter().map(|&(row, col)| target.shoot(row, col)).collect();
+        for (&(row, col), &result) in cells.iter().zip(&results) {
+            self.replay.record(shooter, row, col, result);
+        }
+        let passes = self.rules.passes_turn(&results);
+        self.end_turn(shooter, passes);
+        results
+    }
+
+    fn end_turn(&mut self, shooter: Side, passes: bool) {
+        self.turn = if self.check_game_over() {
+            Turn::Over
+        } else {
+            match (shooter, passes) {
+                (Side::Player, true) | (Side::Computer, false) => Turn::Computer,
+                (Side::Computer, true) | (Side::Player, false) => Turn::Player,
+            }
+        };
     }
 
     pub fn check_game_over(&self) -> bool {
@@ -69,33 +205,47 @@ impl Game {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::board::AutoShipPlacer;
     use crate::ship::Ship;
-    use std::{rc::Rc, cell::RefCell};
 
     struct MockShipPlacer {
-        ship: Rc<RefCell<Ship>>,
+        ship: Ship,
     }
 
     impl ShipPlacer for MockShipPlacer {
-        fn place_ships(&self, board: &mut Board) -> Result<(), String> {
+        fn place_ships(&self, board: &mut Board, _rng: &mut GameRng) -> Result<(), String> {
             board.place_ship(self.ship.clone())
         }
     }
 
+    struct FixedVolley {
+        cells: Vec<(usize, usize)>,
+    }
+
+    impl ShotStrategy for FixedVolley {
+        fn choose_shot(&self, _board: &Board, _rng: &mut GameRng) -> (usize, usize) {
+            self.cells[0]
+        }
+
+        fn choose_volley(&self, _board: &Board, _count: usize, _rng: &mut GameRng) -> Vec<(usize, usize)> {
+            self.cells.clone()
+        }
+    }
+
     pub struct MockShotStrategy {
         coords: (usize, usize),
     }
 
     impl ShotStrategy for MockShotStrategy {
-        fn choose_shot(&self, _board: &Board) -> (usize, usize) {
+        fn choose_shot(&self, _board: &Board, _rng: &mut GameRng) -> (usize, usize) {
             self.coords
         }
     }
 
     #[test]
     fn test_check_game_over_player_wins() {
-        let player_ship = Rc::new(RefCell::new(Ship::new(vec![(0, 0)], 1)));
-        let computer_ship = Rc::new(RefCell::new(Ship::new(vec![(1, 1)], 1)));
+        let player_ship = Ship::new(vec![(0, 0)], 1);
+        let computer_ship = Ship::new(vec![(1, 1)], 1);
 
         let player_placer = MockShipPlacer { ship: player_ship };
         let computer_placer = MockShipPlacer { ship: computer_ship };
@@ -109,8 +259,8 @@ mod tests {
 
     #[test]
     fn test_check_game_over_computer_wins() {
-        let player_ship = Rc::new(RefCell::new(Ship::new(vec![(0, 0)], 1)));
-        let computer_ship = Rc::new(RefCell::new(Ship::new(vec![(1, 1)], 1)));
+        let player_ship = Ship::new(vec![(0, 0)], 1);
+        let computer_ship = Ship::new(vec![(1, 1)], 1);
 
         let player_placer = MockShipPlacer { ship: player_ship };
         let computer_placer = MockShipPlacer { ship: computer_ship };
@@ -124,4 +274,70 @@ mod tests {
 
         assert!(game.check_game_over(), "Игра должна быть закончена");
     }
+
+    #[test]
+    fn test_same_seed_replays_same_game() {
+        let strategy = crate::ai::HuntTargetStrategy;
+        let mut first = Game::with_seed(42, &AutoShipPlacer, &AutoShipPlacer);
+        let mut second = Game::with_seed(42, &AutoShipPlacer, &AutoShipPlacer);
+        assert_eq!(first.player_board.ship_coords(), second.player_board.ship_coords());
+        assert_eq!(first.computer_board.ship_coords(), second.computer_board.ship_coords());
+
+        for _ in 0..30 {
+            first.computer_shoot(&strategy);
+            second.computer_shoot(&strategy);
+        }
+        assert_eq!(first.replay, second.replay);
+    }
+
+    #[test]
+    fn test_salvo_volley_size_follows_surviving_ships() {
+        let mut game = Game::with_rules(Rules::salvo(), 4, &AutoShipPlacer, &AutoShipPlacer);
+        assert_eq!(game.volley_size(Side::Player), 5);
+        assert!(game.player_volley(&[(0, 0)]).is_err(), "Залп должен быть полным");
+        assert!(game.player_volley(&[(0, 0); 5]).is_err(), "Клетки залпа не должны повторяться");
+
+        let ship = game.player_board.ship_coords().into_iter().min_by_key(|coords| coords.len()).unwrap();
+        let mut cells = ship.clone();
+        let water = game.player_board.unshot_cells().into_iter().filter(|&(r, c)| game.player_board.cells[r][c].is_none());
+        cells.extend(water.take(5 - ship.len()));
+        game.turn = Turn::Computer;
+
+        // компьютер топит корабль игрока своим залпом
+        let strategy = FixedVolley { cells };
+        let results = game.computer_volley(&strategy);
+        assert_eq!(results.len(), 5);
+        assert!(results.contains(&ShootResult::Destroy));
+        assert_eq!(game.turn, Turn::Player);
+        assert_eq!(game.volley_size(Side::Player), 4);
+        assert_eq!(game.volley_size(Side::Computer), 5);
+    }
+
+    #[test]
+    fn test_ai_volley_has_distinct_unshot_cells() {
+        let mut game = Game::with_rules(Rules::salvo(), 9, &AutoShipPlacer, &AutoShipPlacer);
+        for difficulty in crate::ai::Difficulty::ALL {
+            let strategy = difficulty.strategy();
+            game.turn = Turn::Computer;
+            let before = game.player_board.unshot_cells().len();
+            let expected = game.volley_size(Side::Computer);
+            let results = game.computer_volley(&*strategy);
+            assert_eq!(results.len(), expected);
+            assert_eq!(game.player_board.unshot_cells().len(), before - expected);
+        }
+    }
+
+    #[test]
+    fn test_turn_passing_follows_rules() {
+        let classic = Game::with_seed(1, &AutoShipPlacer, &AutoShipPlacer);
+        assert!(classic.passes_turn(ShootResult::Miss));
+        assert!(!classic.passes_turn(ShootResult::Hit));
+        assert!(!classic.passes_turn(ShootResult::Destroy));
+
+        let mb = Game::with_rules(Rules::milton_bradley(), 1, &AutoShipPlacer, &AutoShipPlacer);
+        assert!(mb.passes_turn(ShootResult::Miss));
+        assert!(mb.passes_turn(ShootResult::Hit));
+        assert_eq!(mb.replay.rules, Rules::milton_bradley());
+        assert_eq!(mb.player_board.ship_coords().len(), 5);
+    }
 }
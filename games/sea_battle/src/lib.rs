@@ -0,0 +1,12 @@
+pub mod ai;
+pub mod board;
+pub mod game;
+pub mod net;
+pub mod placement;
+pub mod render;
+pub mod replay;
+pub mod rules;
+pub mod save;
+pub mod ship;
+pub mod stats;
+pub mod tournament;
@@ -1,17 +1,338 @@
+use std::path::Path;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::Duration;
 use eframe::egui;
-use crate::game::{Game, RandomShotStrategy};
-use crate::board::{ManualShipPlacer, AutoShipPlacer, ShipPlacer};
+use rand::SeedableRng;
+use sea_battle::ai::Difficulty;
+use sea_battle::game::{Game, GameRng, ShotStrategy, Turn};
+use sea_battle::board::{Board, AutoShipPlacer, LayoutShipPlacer, ShipPlacer};
+use sea_battle::net::{self, NetGame, NetMessage, Peer, Phase};
+use sea_battle::placement::PlacementEditor;
+use sea_battle::render::BoardView;
+use sea_battle::render::gui::{draw_board, placement_editor};
+use sea_battle::replay::{Replay, Side};
+use sea_battle::rules::{Rules, MAX_BOARD_SIZE};
+use sea_battle::save::{SavedGame, DEFAULT_SAVE_PATH};
+use sea_battle::ship::ShootResult;
+use sea_battle::stats::{GameRecord, Profile, DEFAULT_STATS_PATH};
 
-mod ship;
-mod board;
-mod game;
+// файл повтора по умолчанию
+const DEFAULT_REPLAY_PATH: &str = "sea_battle_replay.json";
+
+// просмотр записанной партии по шагам
+struct ReplayViewer {
+    replay: Replay,
+    step: usize,
+    player_board: Board,
+    computer_board: Board,
+}
+
+impl ReplayViewer {
+    fn new(replay: Replay) -> Result<Self, String> {
+        let (player_board, computer_board) = replay.boards_at(0)?;
+        Ok(ReplayViewer { replay, step: 0, player_board, computer_board })
+    }
+
+    fn go_to(&mut self, step: usize) {
+        let step = step.min(self.replay.shots.len());
+        if let Ok((player_board, computer_board)) = self.replay.boards_at(step) {
+            self.step = step;
+            self.player_board = player_board;
+            self.computer_board = computer_board;
+        }
+    }
+
+    // возвращает true, если нужно вернуться в меню
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.heading(format!("Повтор партии (зерно {})", self.replay.seed));
+
+        let total = self.replay.shots.len();
+        let mut step = self.step;
+        ui.horizontal(|ui| {
+            if ui.button("⏮").clicked() {
+                step = 0;
+            }
+            if ui.button("◀ Назад").clicked() {
+                step = step.saturating_sub(1);
+            }
+            if ui.button("Вперед ▶").clicked() {
+                step += 1;
+            }
+            if ui.button("⏭").clicked() {
+                step = total;
+            }
+            ui.add(egui::Slider::new(&mut step, 0..=total).text("выстрел"));
+        });
+        if step != self.step {
+            self.go_to(step);
+        }
+
+        if let Some(shot) = self.step.checked_sub(1).map(|index| &self.replay.shots[index]) {
+            let shooter = match shot.shooter {
+                Side::Player => "Игрок",
+                Side::Computer => "Компьютер",
+            };
+            let letter = (b'A' + shot.row as u8) as char;
+            ui.label(format!("{}: {}{} - {:?}", shooter, letter, shot.col, shot.result));
+        }
+
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Доска игрока:");
+                ui.push_id("replay_player", |ui| draw_board(ui, &self.player_board, &BoardView::own()));
+            });
+            ui.add_space(20.0);
+            ui.vertical(|ui| {
+                ui.label("Доска компьютера:");
+                ui.push_id("replay_computer", |ui| draw_board(ui, &self.computer_board, &BoardView::own()));
+            });
+        });
+
+        ui.button("В меню").clicked()
+    }
+}
+
+// Сетевая партия: хозяин ждет подключения, второй игрок ждет правила,
+// после этого оба расставляют корабли автоматически и обмениваются хешами
+struct NetScreen {
+    waiting: Option<Receiver<Result<Peer, String>>>,
+    peer: Option<Peer>,
+    game: Option<NetGame>,
+    rules: Rules,
+    rng: GameRng,
+    volley: Vec<(usize, usize)>,
+    error: Option<String>,
+}
+
+impl NetScreen {
+    fn host(addr: &str, rules: Rules) -> Result<Self, String> {
+        rules.validate()?;
+        let waiting = net::listen(addr)?;
+        Ok(Self::new(Some(waiting), None, rules))
+    }
+
+    fn join(addr: &str) -> Result<Self, String> {
+        let peer = Peer::connect(addr)?;
+        Ok(Self::new(None, Some(peer), Rules::default()))
+    }
+
+    fn new(waiting: Option<Receiver<Result<Peer, String>>>, peer: Option<Peer>, rules: Rules) -> Self {
+        NetScreen {
+            waiting,
+            peer,
+            game: None,
+            rules,
+            rng: GameRng::seed_from_u64(rand::random()),
+            volley: Vec::new(),
+            error: None,
+        }
+    }
+
+    fn start(&mut self, host: bool) -> Result<(), String> {
+        let mut board = Board::with_rules(self.rules.clone());
+        AutoShipPlacer.place_ships(&mut board, &mut self.rng)?;
+        let game = NetGame::new(self.rules.clone(), board, host, &mut self.rng);
+        let opening = game.opening_messages();
+        self.game = Some(game);
+        self.send(opening)
+    }
+
+    fn send(&mut self, messages: Vec<NetMessage>) -> Result<(), String> {
+        let peer = self.peer.as_mut().ok_or("Нет соединения с соперником")?;
+        for message in &messages {
+            peer.send(message)?;
+        }
+        Ok(())
+    }
+
+    // принимает соперника и входящие сообщения, не блокируя окно
+    fn poll(&mut self) -> Result<(), String> {
+        if let Some(waiting) = &self.waiting {
+            match waiting.try_recv() {
+                Ok(peer) => {
+                    self.peer = Some(peer?);
+                    self.waiting = None;
+                    self.start(true)?;
+                }
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => return Err("Ожидание соперника прервано".to_string()),
+            }
+        }
+        while let Some(message) = self.peer.as_ref().and_then(Peer::try_receive) {
+            let message = message?;
+            let replies = match &mut self.game {
+                Some(game) => game.handle(message)?,
+                None => {
+                    self.rules = NetGame::rules_from_hello(&message)?;
+                    self.start(false)?;
+                    continue;
+                }
+            };
+            self.send(replies)?;
+        }
+        Ok(())
+    }
+
+    fn fire(&mut self, cells: Vec<(usize, usize)>) -> Result<(), String> {
+        let shots = self.game.as_mut().ok_or("Партия еще не началась")?.fire(&cells)?;
+        self.send(vec![shots])
+    }
+
+    // возвращает true, если нужно вернуться в меню
+    fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.error.is_none()
+            && let Err(e) = self.poll()
+        {
+            self.error = Some(e);
+        }
+        if self.game.as_ref().is_none_or(|game| game.phase != Phase::Over) {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+
+        ui.heading("Сетевая игра");
+        let status = match &self.game {
+            _ if self.waiting.is_some() => "Ожидание соперника...".to_string(),
+            None => "Ожидание правил от хозяина игры...".to_string(),
+            Some(game) => match game.phase {
+                Phase::WaitingCommit => "Соперник расставляет корабли...".to_string(),
+                Phase::MyTurn => "Ваш ход".to_string(),
+                Phase::TheirTurn | Phase::WaitingResults => "Ход соперника...".to_string(),
+                Phase::WaitingReveal => "Проверка расстановки соперника...".to_string(),
+                Phase::Over => match &game.outcome {
+                    Some(outcome) => {
+                        let result = if outcome.won { "Вы победили!" } else { "Соперник победил!" };
+                        match &outcome.verification {
+                            Ok(()) => format!("{} Расстановка соперника проверена.", result),
+                            Err(e) => format!("{} Соперник нарушил правила: {}", result, e),
+                        }
+                    }
+                    None => String::new(),
+                },
+            },
+        };
+        ui.label(status);
+        if let Some(error) = &self.error {
+            ui.colored_label(egui::Color32::RED, error);
+        }
+
+        let mut fire = None;
+        if let Some(game) = &self.game {
+            ui.label(format!("Правила: {}", game.rules.name));
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.label("Ваша доска:");
+                    ui.push_id("net_own", |ui| draw_board(ui, &game.own_board, &BoardView::own()));
+                });
+                ui.add_space(20.0);
+                ui.vertical(|ui| {
+                    ui.label("Доска соперника:");
+                    let volley = &self.volley;
+                    let clicked = ui
+                        .push_id("net_enemy", |ui| draw_board(ui, &game.enemy_board, &BoardView { selected: volley, ..BoardView::enemy() }))
+                        .inner;
+                    if let Some((row, col)) = clicked
+                        && game.phase == Phase::MyTurn
+                        && game.enemy_board.shots[row][col].is_none() {
+                        if !game.rules.salvo {
+                            fire = Some(vec![(row, col)]);
+                        } else if let Some(index) = self.volley.iter().position(|&cell| cell == (row, col)) {
+                            self.volley.remove(index);
+                        } else if self.volley.len() < game.volley_size() {
+                            self.volley.push((row, col));
+                        }
+                    }
+                    if game.rules.salvo && game.phase == Phase::MyTurn {
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Залп: выбрано {} из {}", self.volley.len(), game.volley_size()));
+                            if ui.add_enabled(self.volley.len() == game.volley_size(), egui::Button::new("Огонь!")).clicked() {
+                                fire = Some(std::mem::take(&mut self.volley));
+                            }
+                        });
+                    }
+                });
+            });
+        }
+        if let Some(cells) = fire
+            && let Err(e) = self.fire(cells)
+        {
+            self.error = Some(e);
+        }
+
+        ui.button("В меню").clicked()
+    }
+}
+
+// Экран статистики: сводка и история партий, новые сверху.
+// Возвращает true, если нужно вернуться в меню
+fn show_stats(ui: &mut egui::Ui, profile: &Profile) -> bool {
+    ui.heading("Статистика");
+    let summary = profile.summary();
+    ui.label(format!(
+        "Партий: {}, побед: {}, поражений: {} ({:.0}% побед)",
+        summary.games,
+        summary.wins,
+        summary.losses,
+        summary.win_rate() * 100.0,
+    ));
+    ui.label(format!("Точность: {:.1}%", summary.accuracy * 100.0));
+    if let Some(turns) = summary.average_turns_to_win {
+        ui.label(format!("Ходов до победы в среднем: {:.1}", turns));
+    }
+    let streak = match summary.current_streak {
+        0 => "нет".to_string(),
+        wins if wins > 0 => format!("{} побед подряд", wins),
+        losses => format!("{} поражений подряд", -losses),
+    };
+    ui.label(format!(
+        "Текущая серия: {}. Лучшая серия побед: {}, худшая серия поражений: {}",
+        streak, summary.best_win_streak, summary.worst_loss_streak,
+    ));
+
+    ui.add_space(10.0);
+    egui::ScrollArea::vertical().max_height(350.0).show(ui, |ui| {
+        egui::Grid::new("stats_history").striped(true).show(ui, |ui| {
+            for title in ["Дата", "Итог", "Соперник", "Правила", "Выстрелов", "Точность", "Ходов", "Потеряно кораблей"] {
+                ui.strong(title);
+            }
+            ui.end_row();
+            for game in profile.games.iter().rev() {
+                ui.label(game.finished_at.with_timezone(&chrono::Local).format("%d.%m.%Y %H:%M").to_string());
+                ui.label(if game.won { "победа" } else { "поражение" });
+                ui.label(&game.opponent);
+                ui.label(&game.rules);
+                ui.label(game.shots.to_string());
+                ui.label(format!("{:.0}%", game.accuracy() * 100.0));
+                ui.label(game.turns.to_string());
+                ui.label(game.ships_lost.to_string());
+                ui.end_row();
+            }
+        });
+    });
+
+    ui.button("В меню").clicked()
+}
 
 struct GameApp {
     game: Option<Game>,
     player_placer: Box<dyn ShipPlacer>,
     computer_placer: Box<dyn ShipPlacer>,
-    is_player_turn: bool,
+    difficulty: Difficulty,
+    rules: Rules,
+    computer_strategy: Box<dyn ShotStrategy>,
+    volley: Vec<(usize, usize)>, // клетки, выбранные в залп
     last_shot_result: Option<String>,
+    last_computer_result: Option<String>,
+    seed_input: String, // пустое поле - случайная партия
+    replay_path: String,
+    save_path: String,
+    notice: Option<String>,
+    viewer: Option<ReplayViewer>,
+    net_addr: String,
+    net: Option<NetScreen>,
+    placement: Option<PlacementEditor>,
+    profile: Profile,
+    stats_recorded: bool, // итог текущей партии уже записан
+    show_stats: bool,
 }
 
 impl Default for GameApp {
@@ -19,12 +340,34 @@ impl Default for GameApp {
         let player_placer = Box::new(AutoShipPlacer);
         let computer_placer = Box::new(AutoShipPlacer);
 
+        // Испорченный файл статистики не должен мешать играть. Пустой профиль только показывается,
+        // записи идут через Profile::append, который такой файл не перезаписывает
+        let (profile, notice) = match Profile::load(Path::new(DEFAULT_STATS_PATH)) {
+            Ok(profile) => (profile, None),
+            Err(e) => (Profile::default(), Some(e)),
+        };
+
         GameApp {
             game: None,
             player_placer,
             computer_placer,
-            is_player_turn: true,
+            difficulty: Difficulty::default(),
+            rules: Rules::default(),
+            computer_strategy: Difficulty::default().strategy(),
+            volley: Vec::new(),
             last_shot_result: None,
+            last_computer_result: None,
+            seed_input: String::new(),
+            replay_path: DEFAULT_REPLAY_PATH.to_string(),
+            save_path: DEFAULT_SAVE_PATH.to_string(),
+            notice,
+            viewer: None,
+            net_addr: net::DEFAULT_ADDR.to_string(),
+            net: None,
+            placement: None,
+            profile,
+            stats_recorded: false,
+            show_stats: false,
         }
     }
 }
@@ -32,16 +375,141 @@ impl Default for GameApp {
 impl eframe::App for GameApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(viewer) = &mut self.viewer {
+                if viewer.show(ui) {
+                    self.viewer = None;
+                }
+                return;
+            }
+
+            if let Some(net) = &mut self.net {
+                if net.show(ui) {
+                    self.net = None;
+                }
+                return;
+            }
+
+            if self.show_stats {
+                if show_stats(ui, &self.profile) {
+                    self.show_stats = false;
+                }
+                return;
+            }
+
+            if let Some(editor) = &mut self.placement {
+                ui.heading("Расстановка кораблей");
+                ui.label("Перетащите корабль из палитры на поле или выберите его и кликните по клетке.");
+                if placement_editor(ui, editor, &mut rand::rng()) {
+                    self.player_placer = Box::new(LayoutShipPlacer { ships: editor.ships() });
+                    self.placement = None;
+                    self.start_game();
+                } else if ui.button("В меню").clicked() {
+                    self.placement = None;
+                }
+                return;
+            }
+
             if self.game.is_none() {
                 ui.heading("Добро пожаловать в игру 'Морской бой'!");
+                ui.label("Уровень сложности:");
+                ui.horizontal(|ui| {
+                    for difficulty in Difficulty::ALL {
+                        ui.radio_value(&mut self.difficulty, difficulty, difficulty.name());
+                    }
+                });
+                ui.label("Правила:");
+                ui.horizontal(|ui| {
+                    for preset in Rules::presets() {
+                        let selected = self.rules.name == preset.name;
+                        if ui.radio(selected, &preset.name).clicked() {
+                            self.rules = preset;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Поле:");
+                    ui.add(egui::DragValue::new(&mut self.rules.width).range(5..=MAX_BOARD_SIZE));
+                    ui.label("x");
+                    ui.add(egui::DragValue::new(&mut self.rules.height).range(5..=MAX_BOARD_SIZE));
+                });
+                ui.checkbox(&mut self.rules.ships_may_touch, "Корабли могут касаться");
+                ui.checkbox(&mut self.rules.shoot_again_on_hit, "Повторный выстрел после попадания");
+                ui.checkbox(&mut self.rules.salvo, "Залп: выстрел за каждый уцелевший корабль");
+                let fleet: Vec<String> = self
+                    .rules
+                    .fleet
+                    .iter()
+                    .map(|&(size, count)| format!("{}x{}", size, count))
+                    .collect();
+                ui.label(format!("Флот (палуб x кораблей): {}", fleet.join(", ")));
+
                 ui.label("Выберите режим размещения кораблей:");
                 if ui.button("1 - автоматически").clicked() {
                     self.player_placer = Box::new(AutoShipPlacer);
                     self.start_game();
                 }
                 if ui.button("2 - вручную").clicked() {
-                    self.player_placer = Box::new(ManualShipPlacer);
-                    self.start_game();
+                    match self.rules.validate() {
+                        Ok(()) => self.placement = Some(PlacementEditor::new(self.rules.clone())),
+                        Err(e) => self.notice = Some(e),
+                    }
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Зерно партии (необязательно):");
+                    ui.text_edit_singleline(&mut self.seed_input);
+                });
+
+                if ui.button("Статистика").clicked() {
+                    // партии могли добавиться из терминальной версии
+                    match Profile::load(Path::new(DEFAULT_STATS_PATH)) {
+                        Ok(profile) => self.profile = profile,
+                        Err(e) => self.notice = Some(e),
+                    }
+                    self.show_stats = true;
+                }
+
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.label("Сетевая игра, адрес:");
+                    ui.text_edit_singleline(&mut self.net_addr);
+                    // хозяин играет по правилам, выбранным выше
+                    if ui.button("Создать игру").clicked() {
+                        match NetScreen::host(&self.net_addr, self.rules.clone()) {
+                            Ok(net) => self.net = Some(net),
+                            Err(e) => self.notice = Some(e),
+                        }
+                    }
+                    if ui.button("Подключиться").clicked() {
+                        match NetScreen::join(&self.net_addr) {
+                            Ok(net) => self.net = Some(net),
+                            Err(e) => self.notice = Some(e),
+                        }
+                    }
+                });
+
+                ui.add_space(20.0);
+                ui.horizontal(|ui| {
+                    ui.label("Файл повтора:");
+                    ui.text_edit_singleline(&mut self.replay_path);
+                    if ui.button("Просмотр повтора").clicked() {
+                        match Replay::load(Path::new(&self.replay_path)).and_then(ReplayViewer::new) {
+                            Ok(viewer) => self.viewer = Some(viewer),
+                            Err(e) => self.notice = Some(e),
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Файл сохранения:");
+                    ui.text_edit_singleline(&mut self.save_path);
+                    if ui.button("Загрузить партию").clicked() {
+                        match SavedGame::load(Path::new(&self.save_path)) {
+                            Ok(saved) => self.resume_game(saved),
+                            Err(e) => self.notice = Some(e),
+                        }
+                    }
+                });
+                if let Some(notice) = &self.notice {
+                    ui.label(notice);
                 }
                 return;
             }
@@ -49,43 +517,65 @@ impl eframe::App for GameApp {
             ui.heading("Морской бой");
 
             let game = self.game.as_mut().unwrap();
+            ui.label(format!("Зерно партии: {}", game.seed));
 
             ui.horizontal(|ui| {
                 ui.vertical(|ui| {
                     ui.label("Ваша доска:");
-                    game.player_board.draw_board(ui, false);
+                    ui.push_id("player", |ui| draw_board(ui, &game.player_board, &BoardView::own()));
                 });
 
                 ui.add_space(20.0);
 
                 ui.vertical(|ui| {
                     ui.label("Доска компьютера:");
-                    if let Some((row, col)) = game.computer_board.draw_board(ui, true) {
-                        if self.is_player_turn {
-                            // Выполняем выстрел по компьютеру
-                            let result = game.player_shoot(row, col);
-                            match result {
-                                crate::ship::ShootResult::Miss => {
-                                    println!("Промах!");
-                                    self.last_shot_result = Some("Промах!".to_string());
-                                    // Передаём ход компьютеру
-                                    self.is_player_turn = false;
-                                }
-                                crate::ship::ShootResult::Hit => {
-                                    println!("Попадание!");
-                                    self.last_shot_result = Some("Попадание!".to_string());
-                                }
-                                crate::ship::ShootResult::Destroy => {
-                                    println!("Корабль уничтожен!");
-                                    self.last_shot_result = Some("Корабль уничтожен!".to_string());
-                                }
+                    let volley = &self.volley;
+                    let clicked = ui
+                        .push_id("computer", |ui| draw_board(ui, &game.computer_board, &BoardView { selected: volley, ..BoardView::enemy() }))
+                        .inner;
+                    if let Some((row, col)) = clicked
+                        && game.turn == Turn::Player
+                        && game.computer_board.shots[row][col].is_none() {
+                        if game.rules.salvo {
+                            // в залпе клик выбирает или снимает клетку
+                            if let Some(index) = self.volley.iter().position(|&cell| cell == (row, col)) {
+                                self.volley.remove(index);
+                            } else if self.volley.len() < game.volley_size(Side::Player) {
+                                self.volley.push((row, col));
                             }
+                        } else if let Ok(results) = game.player_volley(&[(row, col)]) {
+                            self.last_shot_result = Some(describe_volley(&results));
                         }
-                    };
+                    }
+
+                    if game.rules.salvo && game.turn == Turn::Player {
+                        let size = game.volley_size(Side::Player);
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Залп: выбрано {} из {}", self.volley.len(), size));
+                            if ui.add_enabled(self.volley.len() == size, egui::Button::new("Огонь!")).clicked() {
+                                match game.player_volley(&self.volley) {
+                                    Ok(results) => self.last_shot_result = Some(describe_volley(&results)),
+                                    Err(e) => self.last_shot_result = Some(e),
+                                }
+                                self.volley.clear();
+                            }
+                            if ui.button("Сбросить").clicked() {
+                                self.volley.clear();
+                            }
+                        });
+                    }
                 });
             });
 
             if game.check_game_over() {
+                if !self.stats_recorded {
+                    let record = GameRecord::from_game(game, self.difficulty.name());
+                    match Profile::append(Path::new(DEFAULT_STATS_PATH), record) {
+                        Ok(profile) => self.profile = profile,
+                        Err(e) => self.notice = Some(format!("Партия не записана в статистику: {}", e)),
+                    }
+                    self.stats_recorded = true;
+                }
                 ui.add_space(20.0);
                 ui.horizontal_centered(|ui| {
                     ui.heading(if game.player_board.all_ships_destroyed() {
@@ -94,33 +584,116 @@ impl eframe::App for GameApp {
                         "Вы победили!"
                     });
                 });
+                let mut back_to_menu = false;
+                ui.horizontal(|ui| {
+                    if ui.button("Сохранить повтор").clicked() {
+                        self.notice = Some(match game.replay.save(Path::new(&self.replay_path)) {
+                            Ok(()) => format!("Повтор сохранен в {}", self.replay_path),
+                            Err(e) => e,
+                        });
+                    }
+                    back_to_menu = ui.button("В меню").clicked();
+                });
+                if let Some(notice) = &self.notice {
+                    ui.label(notice);
+                }
+                if back_to_menu {
+                    self.game = None;
+                    self.last_shot_result = None;
+                    self.last_computer_result = None;
+                    self.volley.clear();
+                }
                 return;
-            } else if let Some(result) = &self.last_shot_result {
+            }
+            if let Some(result) = &self.last_shot_result {
                 ui.add_space(50.0);
                 ui.heading(format!("Результат выстрела: {}", result));
             }
+            if let Some(result) = &self.last_computer_result {
+                ui.label(format!("Компьютер: {}", result));
+            }
 
-            if !self.is_player_turn {
-                let shoot_result = game.computer_shoot(&RandomShotStrategy);
-                match shoot_result {
-                    crate::ship::ShootResult::Miss => {
-                        self.is_player_turn = true;
-                    },
-                    crate::ship::ShootResult::Hit => {}
-                    crate::ship::ShootResult::Destroy => {}
+            ui.add_space(20.0);
+            ui.horizontal(|ui| {
+                ui.label("Файл сохранения:");
+                ui.text_edit_singleline(&mut self.save_path);
+                if ui.button("Сохранить партию").clicked() {
+                    let saved = SavedGame::new(game.clone(), self.difficulty.name());
+                    self.notice = Some(match saved.save(Path::new(&self.save_path)) {
+                        Ok(()) => format!("Партия сохранена в {}", self.save_path),
+                        Err(e) => e,
+                    });
                 }
+            });
+            if let Some(notice) = &self.notice {
+                ui.label(notice);
+            }
+
+            if game.turn == Turn::Computer {
+                let results = game.computer_volley(&*self.computer_strategy);
+                self.last_computer_result = Some(describe_volley(&results));
             }
         });
     }
 }
 
+// текст результата выстрела или залпа
+fn describe_volley(results: &[ShootResult]) -> String {
+    if let [result] = results {
+        let text = match result {
+            ShootResult::Miss => "Промах!",
+            ShootResult::Hit => "Попадание!",
+            ShootResult::Destroy => "Корабль уничтожен!",
+        };
+        println!("{}", text);
+        return text.to_string();
+    }
+    let count = |expected: ShootResult| results.iter().filter(|&&result| result == expected).count();
+    format!(
+        "попаданий {}, потоплено {}, промахов {}",
+        count(ShootResult::Hit) + count(ShootResult::Destroy),
+        count(ShootResult::Destroy),
+        count(ShootResult::Miss),
+    )
+}
+
 impl GameApp {
     fn start_game(&mut self) {
-        let game = Game::new(&*self.player_placer, &*self.computer_placer);
+        if let Err(e) = self.rules.validate() {
+            self.notice = Some(e);
+            return;
+        }
+        let seed = self.seed_input.trim().parse::<u64>().unwrap_or_else(|_| rand::random());
+        let game = match Game::try_with_rules(self.rules.clone(), seed, &*self.player_placer, &*self.computer_placer) {
+            Ok(game) => game,
+            Err(e) => {
+                self.notice = Some(e);
+                return;
+            }
+        };
+        self.computer_strategy = self.difficulty.strategy();
+        self.notice = None;
+        self.stats_recorded = false;
         println!("{:?}", game);
 
         self.game = Some(game);
     }
+
+    // Продолжает сохраненную партию против того же уровня компьютера.
+    // Правила берутся из сохранения, чтобы меню показывало их после партии
+    fn resume_game(&mut self, saved: SavedGame) {
+        if let Some(difficulty) = Difficulty::from_name(&saved.opponent) {
+            self.difficulty = difficulty;
+        }
+        self.computer_strategy = self.difficulty.strategy();
+        self.rules = saved.game.rules.clone();
+        self.volley.clear();
+        self.last_shot_result = None;
+        self.last_computer_result = None;
+        self.notice = None;
+        self.stats_recorded = false;
+        self.game = Some(saved.game);
+    }
 }
 
 fn main() {
@@ -0,0 +1,119 @@
+use crate::rules::Rules;
+use crate::ship::ShootResult;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+mod session;
+
+pub use session::{NetGame, Outcome, Phase};
+
+// версия сетевого протокола, соперники с разными версиями не играют
+pub const NET_PROTOCOL_VERSION: u32 = 1;
+
+// адрес по умолчанию для создания и подключения к игре
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7878";
+
+// Сообщения сетевой игры, по одному JSON объекту в строке.
+// Порядок: Hello (от хозяина) -> Commit от обоих -> Shots / Results по очереди -> Reveal от обоих
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum NetMessage {
+    // хозяин сообщает правила, подключившийся расставляет корабли по ним
+    Hello { version: u32, rules: Rules },
+    // хеш расстановки с солью: расстановку нельзя поменять посреди партии
+    Commit { hash: String },
+    Shots { cells: Vec<(usize, usize)> },
+    // результаты залпа по порядку и координаты потопленных им кораблей
+    Results { results: Vec<ShootResult>, sunk: Vec<Vec<(usize, usize)>> },
+    // расстановка и соль в конце партии для проверки хеша и ответов
+    Reveal { ships: Vec<Vec<(usize, usize)>>, salt: String },
+}
+
+// случайная соль для хеша расстановки
+pub fn random_salt(rng: &mut impl Rng) -> String {
+    let bytes: [u8; 16] = rng.random();
+    hex::encode(bytes)
+}
+
+pub fn layout_hash(ships: &[Vec<(usize, usize)>], salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_string(ships).unwrap());
+    hasher.update(b":");
+    hasher.update(salt);
+    hex::encode(hasher.finalize())
+}
+
+// Соединение с соперником. Входящие сообщения читает отдельный поток,
+// поэтому окно игры может опрашивать их, не блокируясь
+pub struct Peer {
+    writer: TcpStream,
+    incoming: Receiver<Result<NetMessage, String>>,
+}
+
+impl Peer {
+    pub fn connect(addr: &str) -> Result<Self, String> {
+        let stream = TcpStream::connect(addr).map_err(|e| format!("Не удалось подключиться к {}: {}", addr, e))?;
+        Self::from_stream(stream)
+    }
+
+    pub fn from_stream(stream: TcpStream) -> Result<Self, String> {
+        let reader = stream.try_clone().map_err(|e| e.to_string())?;
+        let (incoming_tx, incoming) = mpsc::channel();
+
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let message = match line {
+                    Ok(line) => serde_json::from_str(&line).map_err(|e| format!("Неверное сообщение соперника: {}", e)),
+                    Err(e) => Err(format!("Ошибка соединения: {}", e)),
+                };
+                let failed = message.is_err();
+                if incoming_tx.send(message).is_err() || failed {
+                    return;
+                }
+            }
+            let _ = incoming_tx.send(Err("Соперник отключился".to_string()));
+        });
+
+        Ok(Peer { writer: stream, incoming })
+    }
+
+    pub fn send(&mut self, message: &NetMessage) -> Result<(), String> {
+        let mut line = serde_json::to_string(message).map_err(|e| e.to_string())?;
+        line.push('\n');
+        self.writer
+            .write_all(line.as_bytes())
+            .map_err(|e| format!("Не удалось отправить сообщение: {}", e))
+    }
+
+    // следующее сообщение, если оно уже пришло
+    pub fn try_receive(&self) -> Option<Result<NetMessage, String>> {
+        match self.incoming.try_recv() {
+            Ok(message) => Some(message),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err("Соперник отключился".to_string())),
+        }
+    }
+
+    pub fn receive(&self) -> Result<NetMessage, String> {
+        self.incoming.recv().unwrap_or_else(|_| Err("Соперник отключился".to_string()))
+    }
+}
+
+// Создание игры: адрес занимается сразу, а соперник принимается в отдельном потоке
+pub fn listen(addr: &str) -> Result<Receiver<Result<Peer, String>>, String> {
+    let listener = TcpListener::bind(addr).map_err(|e| format!("Не удалось занять адрес {}: {}", addr, e))?;
+    let (peer_tx, peer_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let peer = listener
+            .accept()
+            .map_err(|e| format!("Не удалось принять соперника: {}", e))
+            .and_then(|(stream, _)| Peer::from_stream(stream));
+        let _ = peer_tx.send(peer);
+    });
+    Ok(peer_rx)
+}
@@ -0,0 +1,374 @@
+use super::{layout_hash, random_salt, NetMessage, NET_PROTOCOL_VERSION};
+use crate::board::{validate_ship_coordinates, Board};
+use crate::rules::Rules;
+use crate::ship::{Ship, ShootResult};
+use rand::Rng;
+
+// этап сетевой партии с точки зрения локального игрока
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    WaitingCommit,
+    MyTurn,
+    TheirTurn,
+    WaitingResults,
+    WaitingReveal,
+    Over,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub won: bool,
+    // Err с описанием, если раскрытая расстановка соперника не сходится с его ответами
+    pub verification: Result<(), String>,
+}
+
+// Сетевая партия одного игрока. Корабли соперника неизвестны до конца партии:
+// на enemy_board только наши выстрелы и потопленные корабли, о которых он сообщил
+pub struct NetGame {
+    pub rules: Rules,
+    pub own_board: Board,
+    pub enemy_board: Board,
+    pub phase: Phase,
+    pub outcome: Option<Outcome>,
+    host: bool,
+    salt: String,
+    their_commit: Option<String>,
+    pending: Vec<(usize, usize)>,
+}
+
+impl NetGame {
+    // Партия с уже расставленными кораблями. Хозяин ходит первым
+    pub fn new(rules: Rules, own_board: Board, host: bool, rng: &mut impl Rng) -> Self {
+        NetGame {
+            enemy_board: Board::with_rules(rules.clone()),
+            rules,
+            own_board,
+            phase: Phase::WaitingCommit,
+            outcome: None,
+            host,
+            salt: random_salt(rng),
+            their_commit: None,
+            pending: Vec::new(),
+        }
+    }
+
+    // правила из приветствия хозяина, которое первым получает подключившийся
+    pub fn rules_from_hello(message: &NetMessage) -> Result<Rules, String> {
+        match message {
+            NetMessage::Hello { version, rules } if *version == NET_PROTOCOL_VERSION => {
+                rules.validate()?;
+                Ok(rules.clone())
+            }
+            NetMessage::Hello { version, .. } => Err(format!("Неподдерживаемая версия протокола соперника: {}", version)),
+            _ => Err("Ожидалось приветствие хозяина игры".to_string()),
+        }
+    }
+
+    // первые сообщения после подключения: хозяин отправляет правила и хеш, второй игрок - только хеш
+    pub fn opening_messages(&self) -> Vec<NetMessage> {
+        let commit = NetMessage::Commit { hash: layout_hash(&self.own_board.ship_coords(), &self.salt) };
+        if self.host {
+            vec![NetMessage::Hello { version: NET_PROTOCOL_VERSION, rules: self.rules.clone() }, commit]
+        } else {
+            vec![commit]
+        }
+    }
+
+    // сколько выстрелов в нашем залпе
+    pub fn volley_size(&self) -> usize {
+        if !self.rules.salvo {
+            return 1;
+        }
+        self.own_board.surviving_ships().min(self.enemy_board.unshot_cells().len())
+    }
+
+    // сколько выстрелов должно быть в залпе соперника
+    fn their_volley_size(&self) -> usize {
+        if !self.rules.salvo {
+            return 1;
+        }
+        let surviving = self.rules.ship_count() - self.enemy_board.sunk_ship_sizes().len();
+        surviving.min(self.own_board.unshot_cells().len())
+    }
+
+    // Наш залп: клетки проверяются так же, как в партии против компьютера
+    pub fn fire(&mut self, cells: &[(usize, usize)]) -> Result<NetMessage, String> {
+        if self.phase != Phase::MyTurn {
+            return Err("Сейчас не ваш ход".to_string());
+        }
+        if cells.len() != self.volley_size() {
+            return Err(format!("В залпе должно быть выстрелов: {}", self.volley_size()));
+        }
+        check_cells(&self.enemy_board, cells)?;
+        self.pending = cells.to_vec();
+        self.phase = Phase::WaitingResults;
+        Ok(NetMessage::Shots { cells: cells.to_vec() })
+    }
+
+    // Обработка сообщения соперника. Возвращает сообщения, которые нужно отправить в ответ;
+    // ошибка означает нарушение протокола, и партию продолжать нельзя
+    pub fn handle(&mut self, message: NetMessage) -> Result<Vec<NetMessage>, String> {
+        match (self.phase, message) {
+            (Phase::WaitingCommit, NetMessage::Commit { hash }) => {
+                self.their_commit = Some(hash);
+                self.phase = if self.host { Phase::MyTurn } else { Phase::TheirTurn };
+                Ok(Vec::new())
+            }
+            (Phase::TheirTurn, NetMessage::Shots { cells }) => self.receive_shots(&cells),
+            (Phase::WaitingResults, NetMessage::Results { results, sunk }) => self.receive_results(results, sunk),
+            (Phase::WaitingReveal, NetMessage::Reveal { ships, salt }) => {
+                let won = self.own_board.surviving_ships() > 0;
+                self.outcome = Some(Outcome { won, verification: self.verify_reveal(&ships, &salt) });
+                self.phase = Phase::Over;
+                Ok(Vec::new())
+            }
+            (phase, message) => Err(format!("Неожиданное сообщение {:?} на этапе {:?}", message, phase)),
+        }
+    }
+
+    fn receive_shots(&mut self, cells: &[(usize, usize)]) -> Result<Vec<NetMessage>, String> {
+        if cells.len() != self.their_volley_size() {
+            return Err(format!("Соперник прислал неверный залп из {} выстрелов", cells.len()));
+        }
+        check_cells(&self.own_board, cells)?;
+
+        let results: Vec<ShootResult> = cells.iter().map(|&(row, col)| self.own_board.shoot(row, col)).collect();
+        let sunk = cells
+            .iter()
+            .zip(&results)
+            .filter(|&(_, &result)| result == ShootResult::Destroy)
+            .map(|(&(row, col), _)| self.own_board.ship_at(row, col).unwrap().coords.clone())
+            .collect();
+
+        let mut replies = vec![NetMessage::Results { results: results.clone(), sunk }];
+        if self.own_board.all_ships_destroyed() {
+            replies.push(self.reveal());
+            self.phase = Phase::WaitingReveal;
+        } else if self.rules.passes_turn(&results) {
+            self.phase = Phase::MyTurn;
+        }
+        Ok(replies)
+    }
+
+    fn receive_results(
+        &mut self,
+        results: Vec<ShootResult>,
+        sunk: Vec<Vec<(usize, usize)>>,
+    ) -> Result<Vec<NetMessage>, String> {
+        let destroyed = results.iter().filter(|&&result| result == ShootResult::Destroy).count();
+        if results.len() != self.pending.len() || sunk.len() != destroyed {
+            return Err("Число результатов не совпадает с залпом".to_string());
+        }
+        for (&(row, col), &result) in self.pending.iter().zip(&results) {
+            self.enemy_board.shots[row][col] = Some(result != ShootResult::Miss);
+        }
+        for coords in sunk {
+            // потопленный корабль должен состоять из клеток, где мы уже попали
+            if coords.iter().any(|&(row, col)| self.enemy_board.shots.get(row).and_then(|r| r.get(col)) != Some(&Some(true))) {
+                return Err("Соперник сообщил о потоплении корабля, в который не попадали".to_string());
+            }
+            self.enemy_board.mark_sunk(coords)?;
+        }
+        self.pending.clear();
+
+        if self.enemy_board.sunk_ship_sizes().len() == self.rules.ship_count() {
+            self.phase = Phase::WaitingReveal;
+            return Ok(vec![self.reveal()]);
+        }
+        self.phase = if self.rules.passes_turn(&results) { Phase::TheirTurn } else { Phase::MyTurn };
+        Ok(Vec::new())
+    }
+
+    fn reveal(&self) -> NetMessage {
+        NetMessage::Reveal { ships: self.own_board.ship_coords(), salt: self.salt.clone() }
+    }
+
+    // Проверка раскрытой расстановки: хеш, состав флота, правила размещения
+    // и совпадение со всеми ответами на наши выстрелы
+    fn verify_reveal(&self, ships: &[Vec<(usize, usize)>], salt: &str) -> Result<(), String> {
+        if self.their_commit.as_deref() != Some(layout_hash(ships, salt).as_str()) {
+            return Err("Расстановка не совпадает с хешем из начала партии".to_string());
+        }
+
+        let mut sizes: Vec<usize> = ships.iter().map(|coords| coords.len()).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        if sizes != self.rules.ship_sizes() {
+            return Err("Флот соперника не соответствует правилам".to_string());
+        }
+        let mut board = Board::with_rules(self.rules.clone());
+        for coords in ships {
+            validate_ship_coordinates(coords)?;
+            board.place_ship(Ship::new(coords.clone(), coords.len()))?;
+        }
+
+        for row in 0..board.height {
+            for col in 0..board.width {
+                if let Some(hit) = self.enemy_board.shots[row][col]
+                    && hit != board.cells[row][col].is_some()
+                {
+                    let letter = (b'A' + row as u8) as char;
+                    return Err(format!("Соперник неверно ответил на выстрел {}{}", letter, col));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// клетки залпа разные, внутри поля и еще не обстреляны
+fn check_cells(board: &Board, cells: &[(usize, usize)]) -> Result<(), String> {
+    for (index, &(row, col)) in cells.iter().enumerate() {
+        if row >= board.height || col >= board.width {
+            return Err("Выстрел за пределы поля".to_string());
+        }
+        if board.shots[row][col].is_some() || cells[..index].contains(&(row, col)) {
+            return Err("В эту клетку уже стреляли".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{Difficulty, HuntTargetStrategy};
+    use crate::game::ShotStrategy;
+    use crate::net::{listen, Peer};
+    use crate::game::GameRng;
+    use rand::SeedableRng;
+    use std::thread;
+
+    fn player(rules: &Rules, host: bool, seed: u64) -> NetGame {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut board = Board::with_rules(rules.clone());
+        board.place_ships_randomly(&mut rng).unwrap();
+        NetGame::new(rules.clone(), board, host, &mut rng)
+    }
+
+    // доставляет сообщения между двумя игроками в памяти, пока оба не закончат
+    fn play_in_memory(host: &mut NetGame, guest: &mut NetGame, seed: u64) {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut to_guest = host.opening_messages();
+        let mut to_host = guest.opening_messages();
+        assert_eq!(NetGame::rules_from_hello(&to_guest[0]).unwrap(), guest.rules);
+        to_guest.remove(0);
+
+        for _ in 0..1000 {
+            for message in std::mem::take(&mut to_guest) {
+                to_host.extend(guest.handle(message).unwrap());
+            }
+            for message in std::mem::take(&mut to_host) {
+                to_guest.extend(host.handle(message).unwrap());
+            }
+            for (me, outbox) in [(&mut *host, &mut to_guest), (&mut *guest, &mut to_host)] {
+                if me.phase == Phase::MyTurn {
+                    let cells = HuntTargetStrategy.choose_volley(&me.enemy_board, me.volley_size(), &mut rng);
+                    outbox.push(me.fire(&cells).unwrap());
+                }
+            }
+            if host.phase == Phase::Over && guest.phase == Phase::Over {
+                return;
+            }
+        }
+        panic!("Партия не закончилась");
+    }
+
+    #[test]
+    fn test_honest_game_is_verified() {
+        for rules in Rules::presets() {
+            let mut host = player(&rules, true, 1);
+            let mut guest = player(&rules, false, 2);
+            play_in_memory(&mut host, &mut guest, 3);
+
+            let (host_outcome, guest_outcome) = (host.outcome.unwrap(), guest.outcome.unwrap());
+            assert_ne!(host_outcome.won, guest_outcome.won);
+            assert_eq!(host_outcome.verification, Ok(()));
+            assert_eq!(guest_outcome.verification, Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_changed_layout_is_detected() {
+        let rules = Rules::classic();
+        let mut host = player(&rules, true, 4);
+        let mut guest = player(&rules, false, 5);
+        host.handle(guest.opening_messages().remove(0)).unwrap();
+
+        // соперник раскрывает другую расстановку, чем та, под которой подписался
+        let other = player(&rules, false, 6);
+        host.phase = Phase::WaitingReveal;
+        host.handle(NetMessage::Reveal { ships: other.own_board.ship_coords(), salt: guest.salt.clone() }).unwrap();
+        assert!(host.outcome.unwrap().verification.is_err());
+
+        // та же расстановка, но ответ на выстрел был ложным
+        let mut host = player(&rules, true, 4);
+        host.handle(guest.opening_messages().remove(0)).unwrap();
+        let water = guest.own_board.unshot_cells().into_iter().find(|&(r, c)| guest.own_board.cells[r][c].is_none()).unwrap();
+        host.fire(&[water]).unwrap();
+        host.handle(NetMessage::Results { results: vec![ShootResult::Hit], sunk: Vec::new() }).unwrap();
+        host.phase = Phase::WaitingReveal;
+        guest.phase = Phase::WaitingReveal;
+        host.handle(guest.reveal()).unwrap();
+        assert!(host.outcome.unwrap().verification.unwrap_err().contains("неверно ответил"));
+    }
+
+    #[test]
+    fn test_out_of_turn_shots_are_rejected() {
+        let rules = Rules::classic();
+        let mut host = player(&rules, true, 7);
+        let mut guest = player(&rules, false, 8);
+        assert!(guest.fire(&[(0, 0)]).is_err());
+        guest.handle(host.opening_messages().remove(1)).unwrap();
+        assert_eq!(guest.phase, Phase::TheirTurn);
+        assert!(guest.handle(NetMessage::Shots { cells: vec![(0, 0), (0, 1)] }).is_err());
+        host.handle(guest.opening_messages().remove(0)).unwrap();
+        assert!(host.fire(&[(0, 0)]).is_ok());
+    }
+
+    #[test]
+    fn test_game_over_tcp() {
+        let addr = "127.0.0.1:0";
+        let listener = std::net::TcpListener::bind(addr).unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+        let accepted = listen(&addr).unwrap();
+
+        let guest = thread::spawn({
+            let addr = addr.clone();
+            move || {
+                let mut peer = Peer::connect(&addr).unwrap();
+                let rules = NetGame::rules_from_hello(&peer.receive().unwrap()).unwrap();
+                let mut game = player(&rules, false, 10);
+                run_ai(&mut game, &mut peer, 11);
+                game.outcome.unwrap()
+            }
+        });
+
+        let mut peer = accepted.recv().unwrap().unwrap();
+        let mut game = player(&Rules::salvo(), true, 12);
+        run_ai(&mut game, &mut peer, 13);
+        let host_outcome = game.outcome.unwrap();
+        let guest_outcome = guest.join().unwrap();
+        assert_ne!(host_outcome.won, guest_outcome.won);
+        assert_eq!(host_outcome.verification, Ok(()));
+        assert_eq!(guest_outcome.verification, Ok(()));
+    }
+
+    fn run_ai(game: &mut NetGame, peer: &mut Peer, seed: u64) {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let strategy = Difficulty::Hard.strategy();
+        for message in game.opening_messages() {
+            peer.send(&message).unwrap();
+        }
+        while game.phase != Phase::Over {
+            if game.phase == Phase::MyTurn {
+                let cells = strategy.choose_volley(&game.enemy_board, game.volley_size(), &mut rng);
+                peer.send(&game.fire(&cells).unwrap()).unwrap();
+                continue;
+            }
+            for reply in game.handle(peer.receive().unwrap()).unwrap() {
+                peer.send(&reply).unwrap();
+            }
+        }
+    }
+}
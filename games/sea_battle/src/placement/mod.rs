@@ -0,0 +1,185 @@
+use crate::board::Board;
+use crate::rules::Rules;
+use crate::ship::Ship;
+use rand::Rng;
+
+// сколько раз "Расставить остальные" начинает заново, если оставшиеся корабли не поместились
+const AUTO_FILL_ATTEMPTS: usize = 100;
+
+// Редактор расстановки: палитра оставшихся кораблей, ориентация и отмена.
+// В окне его показывает render::gui::placement_editor
+pub struct PlacementEditor {
+    pub board: Board,
+    pub palette: Vec<usize>, // размеры еще не поставленных кораблей, от больших к маленьким
+    pub selected: Option<usize>,
+    pub vertical: bool,
+    pub error: Option<String>, // последняя ошибка, видна до следующего удачного действия
+    placed: Vec<Vec<(usize, usize)>>, // порядок нужен для отмены
+}
+
+impl PlacementEditor {
+    pub fn new(rules: Rules) -> Self {
+        let palette = rules.ship_sizes();
+        PlacementEditor {
+            selected: palette.first().copied(),
+            board: Board::with_rules(rules),
+            palette,
+            vertical: false,
+            error: None,
+            placed: Vec::new(),
+        }
+    }
+
+    // клетки корабля от (row, col) вправо или вниз, могут выходить за поле
+    pub fn ship_cells(&self, size: usize, row: usize, col: usize) -> Vec<(usize, usize)> {
+        (0..size)
+            .map(|i| if self.vertical { (row + i, col) } else { (row, col + i) })
+            .collect()
+    }
+
+    pub fn can_place(&self, size: usize, row: usize, col: usize) -> bool {
+        self.palette.contains(&size) && self.board.can_place_ship(&self.ship_cells(size, row, col))
+    }
+
+    pub fn place(&mut self, size: usize, row: usize, col: usize) -> Result<(), String> {
+        if !self.can_place(size, row, col) {
+            return Err("Сюда корабль поставить нельзя".to_string());
+        }
+        let coords = self.ship_cells(size, row, col);
+        self.board.place_ship(Ship::new(coords.clone(), size))?;
+        self.placed.push(coords);
+        let index = self.palette.iter().position(|&s| s == size).unwrap();
+        self.palette.remove(index);
+        if !self.selected.is_some_and(|s| self.palette.contains(&s)) {
+            self.selected = self.palette.first().copied();
+        }
+        Ok(())
+    }
+
+    pub fn rotate(&mut self) {
+        self.vertical = !self.vertical;
+    }
+
+    // убирает последний поставленный корабль обратно в палитру
+    pub fn undo(&mut self) -> bool {
+        let Some(coords) = self.placed.pop() else {
+            return false;
+        };
+        self.palette.push(coords.len());
+        self.palette.sort_unstable_by(|a, b| b.cmp(a));
+        self.selected = Some(coords.len());
+        self.rebuild_board();
+        true
+    }
+
+    // Ставит оставшиеся корабли случайно, не трогая уже поставленные.
+    // Если они не помещаются, расстановка остается как была
+    pub fn auto_fill(&mut self, rng: &mut impl Rng) -> Result<(), String> {
+        let placed = self.placed.len();
+        for _ in 0..AUTO_FILL_ATTEMPTS {
+            while let Some(&size) = self.palette.first() {
+                let vertical = self.vertical;
+                let mut positions = Vec::new();
+                for orientation in [false, true] {
+                    self.vertical = orientation;
+                    for row in 0..self.board.height {
+                        for col in 0..self.board.width {
+                            if self.can_place(size, row, col) {
+                                positions.push((orientation, row, col));
+                            }
+                        }
+                    }
+                }
+                let choice = (!positions.is_empty()).then(|| positions[rng.random_range(0..positions.len())]);
+                self.vertical = vertical;
+                let Some((orientation, row, col)) = choice else {
+                    break;
+                };
+                self.vertical = orientation;
+                self.place(size, row, col)?;
+                self.vertical = vertical;
+            }
+            if self.is_complete() {
+                return Ok(());
+            }
+            while self.placed.len() > placed {
+                self.undo();
+            }
+        }
+        Err("Оставшиеся корабли не помещаются, уберите часть кораблей".to_string())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.placed.is_empty()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.palette.is_empty()
+    }
+
+    pub fn ships(&self) -> Vec<Vec<(usize, usize)>> {
+        self.placed.clone()
+    }
+
+    fn rebuild_board(&mut self) {
+        self.board = Board::with_rules(self.board.rules.clone());
+        for coords in &self.placed {
+            let ship = Ship::new(coords.clone(), coords.len());
+            self.board.place_ship(ship).expect("Поставленный корабль должен помещаться");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::GameRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_place_rotate_and_undo() {
+        let mut editor = PlacementEditor::new(Rules::classic());
+        assert_eq!(editor.selected, Some(4));
+
+        editor.place(4, 0, 0).unwrap();
+        assert_eq!(editor.ships(), vec![vec![(0, 0), (0, 1), (0, 2), (0, 3)]]);
+        assert!(!editor.palette.contains(&4));
+        assert_eq!(editor.selected, Some(3));
+
+        // рядом с кораблем нельзя, а вертикально ниже через клетку можно
+        assert!(!editor.can_place(3, 1, 0));
+        editor.rotate();
+        assert!(editor.place(3, 2, 0).is_ok());
+        assert_eq!(editor.ships()[1], vec![(2, 0), (3, 0), (4, 0)]);
+        assert!(editor.place(4, 6, 6).is_err(), "Четырехпалубный корабль уже поставлен");
+
+        assert!(editor.undo());
+        assert!(editor.board.cells[2][0].is_none());
+        assert_eq!(editor.palette, vec![3, 3, 2, 2, 2, 1, 1, 1, 1]);
+        assert!(editor.undo());
+        assert!(!editor.undo());
+        assert_eq!(editor.palette, Rules::classic().ship_sizes());
+    }
+
+    #[test]
+    fn test_auto_fill_keeps_placed_ships() {
+        let mut editor = PlacementEditor::new(Rules::milton_bradley());
+        editor.place(5, 9, 5).unwrap();
+        editor.auto_fill(&mut GameRng::seed_from_u64(1)).unwrap();
+
+        assert!(editor.is_complete());
+        assert_eq!(editor.ships()[0], vec![(9, 5), (9, 6), (9, 7), (9, 8), (9, 9)]);
+        assert_eq!(editor.ships().len(), 5);
+        assert!(!editor.vertical, "Автозаполнение не меняет ориентацию игрока");
+    }
+
+    #[test]
+    fn test_auto_fill_rolls_back_when_fleet_does_not_fit() {
+        let rules = Rules { fleet: vec![(3, 2)], ..Rules::classic().with_size(3, 3) };
+        let mut editor = PlacementEditor::new(rules);
+        editor.place(3, 1, 0).unwrap();
+        assert!(editor.auto_fill(&mut GameRng::seed_from_u64(2)).is_err());
+        assert_eq!(editor.ships().len(), 1);
+        assert_eq!(editor.palette, vec![3]);
+    }
+}
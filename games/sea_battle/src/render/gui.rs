@@ -0,0 +1,167 @@
+use super::{BoardRenderer, BoardView, CellView};
+use crate::board::Board;
+use crate::placement::PlacementEditor;
+use eframe::egui;
+use rand::Rng;
+
+pub fn color(cell: CellView) -> egui::Color32 {
+    match cell {
+        CellView::Water => egui::Color32::DARK_BLUE,
+        CellView::Ship => egui::Color32::GREEN,
+        CellView::Selected => egui::Color32::YELLOW,
+        CellView::Miss => egui::Color32::ORANGE,
+        CellView::Hit => egui::Color32::RED,
+        CellView::Sunk => egui::Color32::DARK_RED,
+    }
+}
+
+// Поле в окне egui. Возвращает клетку, по которой кликнули
+pub struct EguiRenderer<'u> {
+    pub ui: &'u mut egui::Ui,
+}
+
+impl BoardRenderer for EguiRenderer<'_> {
+    type Output = Option<(usize, usize)>;
+
+    fn render(&mut self, board: &Board, view: &BoardView) -> Option<(usize, usize)> {
+        let cell_size = 30.0;
+        let mut clicked_cell = None;
+
+        egui::Grid::new(format!("board_grid_{}", if view.hide_ships { "computer" } else { "player" }))
+            .min_col_width(cell_size)
+            .min_row_height(cell_size)
+            .show(self.ui, |ui| {
+                ui.label("");
+                for col in 0..board.width {
+                    ui.label(format!("{}", col));
+                }
+                ui.end_row();
+
+                for row in 0..board.height {
+                    let letter = (b'A' + row as u8) as char;
+                    ui.label(format!("{}", letter));
+                    for col in 0..board.width {
+                        ui.push_id(format!("cell_{}_{}_{}", row, col, view.hide_ships), |ui| {
+                            let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(25.0), egui::Sense::click());
+                            ui.painter().rect_filled(rect, 0.0, color(CellView::of(board, row, col, view)));
+                            if response.clicked() {
+                                clicked_cell = Some((row, col));
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+        clicked_cell
+    }
+}
+
+pub fn draw_board(ui: &mut egui::Ui, board: &Board, view: &BoardView) -> Option<(usize, usize)> {
+    EguiRenderer { ui }.render(board, view)
+}
+
+// Палитра, поле с подсветкой и кнопки редактора расстановки.
+// Возвращает true, когда игрок закончил расстановку
+pub fn placement_editor(ui: &mut egui::Ui, editor: &mut PlacementEditor, rng: &mut impl Rng) -> bool {
+    let cell_size = 28.0;
+    let mut done = false;
+
+    ui.input(|input| {
+        if input.key_pressed(egui::Key::R) {
+            editor.rotate();
+            editor.error = None;
+        }
+    });
+    if ui.input(|input| input.modifiers.command && input.key_pressed(egui::Key::Z)) && editor.undo() {
+        editor.error = None;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Корабли:");
+        for (index, &size) in editor.palette.iter().enumerate() {
+            let id = egui::Id::new(("palette", index));
+            let response = ui
+                .dnd_drag_source(id, size, |ui| {
+                    let text = "■".repeat(size);
+                    let selected = editor.selected == Some(size);
+                    ui.add(egui::Button::new(text).selected(selected))
+                })
+                .inner;
+            if response.clicked() {
+                editor.selected = Some(size);
+            }
+        }
+    });
+    ui.label(format!(
+        "Ориентация: {} (R - повернуть, Ctrl+Z - отменить)",
+        if editor.vertical { "вертикально" } else { "горизонтально" }
+    ));
+
+    let (width, height) = (editor.board.width, editor.board.height);
+    let size = egui::vec2((width + 1) as f32 * cell_size, (height + 1) as f32 * cell_size);
+    let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
+    let origin = rect.min + egui::vec2(cell_size, cell_size);
+
+    // клетка под указателем; во время перетаскивания hovered() ложно, поэтому берем позицию указателя
+    let hovered_cell = ui.input(|input| input.pointer.hover_pos()).and_then(|pos| {
+        let offset = pos - origin;
+        let (col, row) = ((offset.x / cell_size).floor(), (offset.y / cell_size).floor());
+        (col >= 0.0 && row >= 0.0 && (col as usize) < width && (row as usize) < height)
+            .then_some((row as usize, col as usize))
+    });
+    let dragged = response.dnd_hover_payload::<usize>().map(|size| *size);
+    let preview_size = dragged.or(editor.selected);
+
+    if let Some((row, col)) = hovered_cell {
+        let dropped = response.dnd_release_payload::<usize>().map(|size| *size);
+        let clicked = response.clicked().then_some(editor.selected).flatten();
+        if let Some(size) = dropped.or(clicked) {
+            editor.error = editor.place(size, row, col).err();
+        }
+    }
+
+    let preview = match (hovered_cell, preview_size) {
+        (Some((row, col)), Some(size)) => Some((editor.ship_cells(size, row, col), editor.can_place(size, row, col))),
+        _ => None,
+    };
+
+    let painter = ui.painter_at(rect);
+    for col in 0..width {
+        let center = rect.min + egui::vec2((col + 1) as f32 * cell_size + cell_size / 2.0, cell_size / 2.0);
+        painter.text(center, egui::Align2::CENTER_CENTER, col, egui::FontId::default(), ui.visuals().text_color());
+    }
+    for row in 0..height {
+        let letter = (b'A' + row as u8) as char;
+        let center = rect.min + egui::vec2(cell_size / 2.0, (row + 1) as f32 * cell_size + cell_size / 2.0);
+        painter.text(center, egui::Align2::CENTER_CENTER, letter, egui::FontId::default(), ui.visuals().text_color());
+        for col in 0..width {
+            let color = match &preview {
+                Some((cells, true)) if cells.contains(&(row, col)) => egui::Color32::LIGHT_GREEN, // Корабль встанет сюда
+                Some((cells, false)) if cells.contains(&(row, col)) => egui::Color32::RED, // Сюда нельзя
+                _ if editor.board.cells[row][col].is_some() => egui::Color32::GREEN,
+                _ => egui::Color32::DARK_BLUE,
+            };
+            let min = origin + egui::vec2(col as f32 * cell_size, row as f32 * cell_size);
+            let cell = egui::Rect::from_min_size(min, egui::vec2(cell_size - 3.0, cell_size - 3.0));
+            painter.rect_filled(cell, 0.0, color);
+        }
+    }
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(editor.can_undo(), egui::Button::new("Отменить")).clicked() && editor.undo() {
+            editor.error = None;
+        }
+        if ui.button("Повернуть").clicked() {
+            editor.rotate();
+            editor.error = None;
+        }
+        if ui.add_enabled(!editor.is_complete(), egui::Button::new("Расставить остальные")).clicked() {
+            editor.error = editor.auto_fill(rng).err();
+        }
+        done = ui.add_enabled(editor.is_complete(), egui::Button::new("В бой!")).clicked();
+    });
+    if let Some(error) = &editor.error {
+        ui.colored_label(egui::Color32::RED, error);
+    }
+    done
+}
@@ -0,0 +1,107 @@
+use crate::board::Board;
+
+#[cfg(feature = "gui")]
+pub mod gui;
+
+// Что показывает клетка поля. Все способы вывода рисуют одно и то же,
+// отличаются только цвета и символы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellView {
+    Water,
+    Ship,     // живой корабль, виден только на своем поле
+    Selected, // клетка, выбранная в залп
+    Miss,
+    Hit,
+    Sunk,
+}
+
+impl CellView {
+    pub fn of(board: &Board, row: usize, col: usize, view: &BoardView) -> Self {
+        match board.shots[row][col] {
+            Some(false) => CellView::Miss,
+            Some(true) if board.is_sunk(row, col) => CellView::Sunk,
+            // попадание по кораблю соперника в сетевой игре тоже сюда: сам корабль не виден
+            Some(true) => CellView::Hit,
+            None if view.selected.contains(&(row, col)) => CellView::Selected,
+            None if board.cells[row][col].is_some() && !view.hide_ships => CellView::Ship,
+            None => CellView::Water,
+        }
+    }
+
+    pub fn symbol(self) -> char {
+        match self {
+            CellView::Water => '~',
+            CellView::Ship => '■',
+            CellView::Selected => '+',
+            CellView::Miss => '·',
+            CellView::Hit => 'X',
+            CellView::Sunk => '#',
+        }
+    }
+}
+
+// как показывать поле: скрывать ли корабли, выбранные в залп клетки и курсор
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BoardView<'a> {
+    pub hide_ships: bool,
+    pub selected: &'a [(usize, usize)],
+    pub cursor: Option<(usize, usize)>,
+}
+
+impl BoardView<'_> {
+    pub fn own() -> Self {
+        BoardView::default()
+    }
+
+    pub fn enemy() -> Self {
+        BoardView { hide_ships: true, ..BoardView::default() }
+    }
+}
+
+// Способ вывода поля: окно egui, терминал или текст.
+// Output - то, что вывод возвращает игре, например клетку под кликом
+pub trait BoardRenderer {
+    type Output;
+
+    fn render(&mut self, board: &Board, view: &BoardView) -> Self::Output;
+}
+
+// поле строками символов, по пробелу между клетками
+pub struct TextRenderer;
+
+impl BoardRenderer for TextRenderer {
+    type Output = String;
+
+    fn render(&mut self, board: &Board, view: &BoardView) -> String {
+        let mut text = String::new();
+        for row in 0..board.height {
+            for col in 0..board.width {
+                text.push(CellView::of(board, row, col, view).symbol());
+                text.push(' ');
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ship::Ship;
+
+    #[test]
+    fn test_cell_views() {
+        let mut board = Board::new(4, 2);
+        board.place_ship(Ship::new(vec![(0, 0)], 1)).unwrap();
+        board.place_ship(Ship::new(vec![(0, 2), (0, 3)], 2)).unwrap();
+        board.shoot(0, 0);
+        board.shoot(0, 2);
+        board.shoot(1, 0);
+
+        assert_eq!(TextRenderer.render(&board, &BoardView::own()), "# ~ X ■ \n· ~ ~ ~ \n");
+        let selected = [(1, 3)];
+        let view = BoardView { selected: &selected, ..BoardView::enemy() };
+        assert_eq!(TextRenderer.render(&board, &view), "# ~ X ~ \n· ~ ~ + \n");
+    }
+}
@@ -0,0 +1,131 @@
+use crate::board::Board;
+use crate::rules::Rules;
+use crate::ship::{Ship, ShootResult};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+// версия формата файла повтора
+pub const REPLAY_VERSION: u32 = 2;
+
+// кто стреляет: игрок стреляет по доске компьютера, компьютер - по доске игрока
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Side {
+    Player,
+    Computer,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShotRecord {
+    pub shooter: Side,
+    pub row: usize,
+    pub col: usize,
+    pub result: ShootResult,
+}
+
+// запись партии: зерно, правила, расстановка обеих сторон и все выстрелы по порядку
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub rules: Rules,
+    pub player_ships: Vec<Vec<(usize, usize)>>,
+    pub computer_ships: Vec<Vec<(usize, usize)>>,
+    pub shots: Vec<ShotRecord>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, rules: &Rules, player_board: &Board, computer_board: &Board) -> Self {
+        Replay {
+            version: REPLAY_VERSION,
+            seed,
+            rules: rules.clone(),
+            player_ships: player_board.ship_coords(),
+            computer_ships: computer_board.ship_coords(),
+            shots: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, shooter: Side, row: usize, col: usize, result: ShootResult) {
+        self.shots.push(ShotRecord { shooter, row, col, result });
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Не удалось сохранить повтор: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Не удалось прочитать повтор: {}", e))?;
+        let replay: Replay = serde_json::from_str(&json).map_err(|e| format!("Неверный файл повтора: {}", e))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!("Неподдерживаемая версия повтора: {}", replay.version));
+        }
+        Ok(replay)
+    }
+
+    fn board(&self, ships: &[Vec<(usize, usize)>]) -> Result<Board, String> {
+        let mut board = Board::with_rules(self.rules.clone());
+        for coords in ships {
+            let ship = Ship::new(coords.clone(), coords.len());
+            board.place_ship(ship)?;
+        }
+        Ok(board)
+    }
+
+    // доски игрока и компьютера после первых step выстрелов
+    pub fn boards_at(&self, step: usize) -> Result<(Board, Board), String> {
+        let mut player_board = self.board(&self.player_ships)?;
+        let mut computer_board = self.board(&self.computer_ships)?;
+        for shot in self.shots.iter().take(step) {
+            let board = match shot.shooter {
+                Side::Player => &mut computer_board,
+                Side::Computer => &mut player_board,
+            };
+            if shot.row >= board.height || shot.col >= board.width {
+                return Err("Выстрел за пределы поля в повторе".to_string());
+            }
+            board.shoot(shot.row, shot.col);
+        }
+        Ok((player_board, computer_board))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ProbabilityDensityStrategy;
+    use crate::board::AutoShipPlacer;
+    use crate::game::Game;
+
+    #[test]
+    fn test_replay_rebuilds_boards() {
+        let mut game = Game::with_seed(7, &AutoShipPlacer, &AutoShipPlacer);
+        game.player_shoot(0, 0);
+        game.player_shoot(5, 5);
+        while !game.check_game_over() {
+            game.computer_shoot(&ProbabilityDensityStrategy);
+        }
+
+        let replay = &game.replay;
+        let (player_board, computer_board) = replay.boards_at(replay.shots.len()).unwrap();
+        assert_eq!(player_board.shots, game.player_board.shots);
+        assert_eq!(computer_board.shots, game.computer_board.shots);
+        assert!(player_board.all_ships_destroyed());
+
+        let (player_board, _) = replay.boards_at(0).unwrap();
+        assert!(player_board.unshot_cells().len() == player_board.width * player_board.height);
+    }
+
+    #[test]
+    fn test_replay_file_round_trip() {
+        let mut game = Game::with_seed(3, &AutoShipPlacer, &AutoShipPlacer);
+        game.player_shoot(1, 2);
+
+        let path = std::env::temp_dir().join(format!("sea_battle_replay_{}.json", std::process::id()));
+        game.replay.save(&path).unwrap();
+        let loaded = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded, game.replay);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::board::Board;
+use crate::game::GameRng;
+use crate::ship::ShootResult;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+
+// самое большое поле, которое можно подписать буквами A-Z
+pub const MAX_BOARD_SIZE: usize = 26;
+
+// сколько пробных расстановок делает проверка правил, прежде чем признать флот неразмещаемым
+const TRIAL_PLACEMENTS: u64 = 50;
+
+// Правила партии: размер поля, состав флота, можно ли кораблям касаться,
+// дается ли повторный выстрел после попадания и играется ли партия залпами
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub fleet: Vec<(usize, usize)>, // (размер корабля, количество)
+    pub ships_may_touch: bool,
+    pub shoot_again_on_hit: bool,
+    // за ход столько выстрелов, сколько у стреляющего осталось кораблей
+    #[serde(default)]
+    pub salvo: bool,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::classic()
+    }
+}
+
+impl Rules {
+    // классический "Морской бой": 10 кораблей, касаться нельзя, после попадания ход повторяется
+    pub fn classic() -> Self {
+        Rules {
+            name: "Классика".to_string(),
+            width: 10,
+            height: 10,
+            fleet: vec![
+                (4, 1), // 1 четырёхпалубный корабль
+                (3, 2), // 2 трёхпалубных корабля
+                (2, 3), // 3 двухпалубных корабля
+                (1, 4), // 4 однопалубных корабля
+            ],
+            ships_may_touch: false,
+            shoot_again_on_hit: true,
+            salvo: false,
+        }
+    }
+
+    // Milton Bradley: 5 кораблей от авианосца до эсминца, корабли могут касаться, ходы по очереди
+    pub fn milton_bradley() -> Self {
+        Rules {
+            name: "Milton Bradley".to_string(),
+            width: 10,
+            height: 10,
+            fleet: vec![(5, 1), (4, 1), (3, 2), (2, 1)],
+            ships_may_touch: true,
+            shoot_again_on_hit: false,
+            salvo: false,
+        }
+    }
+
+    // Залп: флот Milton Bradley, за ход по выстрелу на каждый уцелевший корабль
+    pub fn salvo() -> Self {
+        Rules {
+            name: "Залп".to_string(),
+            salvo: true,
+            ..Rules::milton_bradley()
+        }
+    }
+
+    pub fn presets() -> Vec<Rules> {
+        vec![Rules::classic(), Rules::milton_bradley(), Rules::salvo()]
+    }
+
+    // пресет по названию без учета регистра
+    pub fn preset(name: &str) -> Option<Rules> {
+        Rules::presets().into_iter().find(|rules| rules.name.to_lowercase() == name.to_lowercase())
+    }
+
+    // правила с другим размером поля
+    pub fn with_size(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    // размеры всех кораблей флота, от больших к маленьким
+    pub fn ship_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self
+            .fleet
+            .iter()
+            .flat_map(|&(size, count)| std::iter::repeat_n(size, count))
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    // Переходит ли ход к сопернику после выстрела или залпа: залп всегда передает ход,
+    // одиночный выстрел - после промаха или если повторного выстрела нет
+    pub fn passes_turn(&self, results: &[ShootResult]) -> bool {
+        self.salvo || !self.shoot_again_on_hit || results.iter().all(|&result| result == ShootResult::Miss)
+    }
+
+    pub fn ship_count(&self) -> usize {
+        self.fleet.iter().map(|&(_, count)| count).sum()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.width == 0 || self.height == 0 || self.width > MAX_BOARD_SIZE || self.height > MAX_BOARD_SIZE {
+            return Err(format!("Размер поля должен быть от 1 до {}", MAX_BOARD_SIZE));
+        }
+        if self.ship_count() == 0 {
+            return Err("Во флоте должен быть хотя бы один корабль".to_string());
+        }
+        if self.ship_sizes().iter().any(|&size| size == 0 || size > self.width.max(self.height)) {
+            return Err("Корабль не помещается на поле".to_string());
+        }
+        let cells: usize = self.fleet.iter().map(|&(size, count)| size * count).sum();
+        if cells > self.width * self.height / 2 {
+            return Err("Флот занимает слишком большую часть поля".to_string());
+        }
+        // Если касаться нельзя, корабль вместе с полосой справа и снизу от него занимает
+        // (размер + 1) x 2 клеток поля, расширенного на клетку, и эти области не пересекаются
+        let padded: usize = self.ship_sizes().iter().map(|&size| (size + 1) * 2).sum();
+        if !self.ships_may_touch && padded > (self.width + 1) * (self.height + 1) {
+            return Err("Флот не помещается на поле: кораблям нельзя касаться".to_string());
+        }
+        // оценки выше не гарантируют, что расстановка существует, поэтому флот пробуем расставить
+        if !self.fleet_fits() {
+            return Err("Не удалось расставить флот на поле такого размера".to_string());
+        }
+        Ok(())
+    }
+
+    // пробные расстановки с фиксированными зернами, чтобы проверка не зависела от случая
+    fn fleet_fits(&self) -> bool {
+        (0..TRIAL_PLACEMENTS).any(|seed| {
+            let mut board = Board::with_rules(self.clone());
+            board.place_ships_in_free_positions(&mut GameRng::seed_from_u64(seed)).is_ok()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{parse_coordinates, AutoShipPlacer, ShipPlacer};
+    use crate::ship::Ship;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for rules in Rules::presets() {
+            assert!(rules.validate().is_ok(), "Пресет '{}' некорректен", rules.name);
+        }
+        assert_eq!(Rules::classic().ship_count(), 10);
+        assert_eq!(Rules::milton_bradley().ship_sizes(), vec![5, 4, 3, 3, 2]);
+        assert!(Rules::classic().with_size(3, 3).validate().is_err());
+    }
+
+    #[test]
+    fn test_unplaceable_fleet_is_rejected() {
+        // половины поля хватает по клеткам, но без касаний классический флот не встает
+        for (width, height) in [(7, 6), (6, 7), (8, 5)] {
+            assert!(Rules::classic().with_size(width, height).validate().is_err(), "{}x{}", width, height);
+        }
+        for (width, height) in [(10, 10), (8, 8), (5, MAX_BOARD_SIZE)] {
+            assert!(Rules::classic().with_size(width, height).validate().is_ok(), "{}x{}", width, height);
+        }
+        // корабли, которым можно касаться, ставятся плотнее
+        assert!(Rules::milton_bradley().with_size(6, 6).validate().is_ok());
+    }
+
+    #[test]
+    fn test_auto_placer_gives_up() {
+        let mut board = Board::with_rules(Rules::classic().with_size(7, 6));
+        assert!(AutoShipPlacer.place_ships(&mut board, &mut GameRng::seed_from_u64(1)).is_err());
+
+        for seed in 0..20 {
+            let mut board = Board::with_rules(Rules::classic().with_size(8, 8));
+            AutoShipPlacer.place_ships(&mut board, &mut GameRng::seed_from_u64(seed)).unwrap();
+            assert_eq!(board.ships.len(), Rules::classic().ship_count());
+        }
+    }
+
+    #[test]
+    fn test_random_placement_follows_fleet() {
+        let rules = Rules::milton_bradley().with_size(12, 8);
+        let mut board = Board::with_rules(rules.clone());
+        board.place_ships_randomly(&mut GameRng::seed_from_u64(5)).unwrap();
+
+        let mut sizes: Vec<usize> = board.ship_coords().iter().map(|coords| coords.len()).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(sizes, rules.ship_sizes());
+        assert_eq!((board.width, board.height), (12, 8));
+    }
+
+    #[test]
+    fn test_ships_may_touch() {
+        let touching = [vec![(0, 0), (0, 1)], vec![(1, 0), (1, 1)]];
+        for (rules, allowed) in [(Rules::classic(), false), (Rules::milton_bradley(), true)] {
+            let mut board = Board::with_rules(rules);
+            board.place_ship(Ship::new(touching[0].clone(), 2)).unwrap();
+            assert_eq!(board.can_place_ship(&touching[1]), allowed);
+        }
+    }
+
+    #[test]
+    fn test_parse_coordinates_on_wide_board() {
+        assert_eq!(parse_coordinates("C12", 15, 15), Ok((2, 12)));
+        assert_eq!(parse_coordinates("A0", 10, 10), Ok((0, 0)));
+        assert!(parse_coordinates("A10", 10, 10).is_err());
+        assert!(parse_coordinates("K1", 10, 10).is_err());
+        assert!(parse_coordinates("5", 10, 10).is_err());
+    }
+}
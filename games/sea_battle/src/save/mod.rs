@@ -0,0 +1,190 @@
+use crate::board::Board;
+use crate::game::Game;
+use crate::rules::Rules;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+// файл сохраненной партии по умолчанию
+pub const DEFAULT_SAVE_PATH: &str = "sea_battle_save.json";
+
+// версия формата файла сохранения
+pub const SAVE_VERSION: u32 = 1;
+
+// незаконченная партия вместе с уровнем компьютера, против которого она шла
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub opponent: String, // имя уровня сложности
+    pub game: Game,
+}
+
+impl SavedGame {
+    pub fn new(game: Game, opponent: &str) -> Self {
+        SavedGame { version: SAVE_VERSION, opponent: opponent.to_string(), game }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Не удалось сохранить партию: {}", e))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Не удалось прочитать сохранение: {}", e))?;
+        let saved: SavedGame = serde_json::from_str(&json).map_err(|e| format!("Неверный файл сохранения: {}", e))?;
+        if saved.version != SAVE_VERSION {
+            return Err(format!("Неподдерживаемая версия сохранения: {}", saved.version));
+        }
+        saved.game.rules.validate()?;
+        check_board(&saved.game.player_board, &saved.game.rules)?;
+        check_board(&saved.game.computer_board, &saved.game.rules)?;
+        Ok(saved)
+    }
+}
+
+// испорченный или исправленный вручную файл не должен ронять игру на первом выстреле
+fn check_board(board: &Board, rules: &Rules) -> Result<(), String> {
+    let (width, height) = (rules.width, rules.height);
+    let grid_fits = board.shots.len() == height
+        && board.cells.len() == height
+        && board.shots.iter().all(|row| row.len() == width)
+        && board.cells.iter().all(|row| row.len() == width);
+    if board.width != width || board.height != height || !grid_fits {
+        return Err("Размер поля в сохранении не совпадает с правилами".to_string());
+    }
+    // корабль без клеток считался бы уже потопленным
+    let ships_fit = board.ships.iter().all(|ship| {
+        !ship.coords.is_empty()
+            && ship.size == ship.coords.len()
+            && ship.hits.len() == ship.coords.len()
+            && ship.coords.iter().all(|&(row, col)| row < height && col < width)
+    });
+    if !ships_fit || board.cells.iter().flatten().flatten().any(|&id| id >= board.ships.len()) {
+        return Err("Неверные корабли в сохранении".to_string());
+    }
+    // клетки корабля указывают на него, а номер корабля стоит только в его клетках
+    let ships_match_cells = board.ships.iter().enumerate().all(|(id, ship)| {
+        ship.coords.iter().all(|&(row, col)| board.cells[row][col] == Some(id))
+    }) && board.cells.iter().enumerate().all(|(row, cells)| {
+        cells.iter().enumerate().all(|(col, cell)| cell.is_none_or(|id| board.ships[id].coords.contains(&(row, col))))
+    });
+    if !ships_match_cells {
+        return Err("Клетки поля не совпадают с кораблями в сохранении".to_string());
+    }
+    let mut sizes: Vec<usize> = board.ships.iter().map(|ship| ship.coords.len()).collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    if sizes != rules.ship_sizes() {
+        return Err("Флот в сохранении не соответствует правилам".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ProbabilityDensityStrategy;
+    use crate::board::AutoShipPlacer;
+    use crate::game::Turn;
+    use crate::replay::Side;
+    use crate::ship::Ship;
+
+    // игрок стреляет по первым свободным клеткам, компьютер - по своей стратегии
+    fn play(game: &mut Game, volleys: usize) {
+        for _ in 0..volleys {
+            match game.turn {
+                Turn::Player => {
+                    let count = game.volley_size(Side::Player);
+                    let cells = game.computer_board.unshot_cells()[..count].to_vec();
+                    game.player_volley(&cells).unwrap();
+                }
+                Turn::Computer => {
+                    game.computer_volley(&ProbabilityDensityStrategy);
+                }
+                Turn::Over => return,
+            }
+        }
+    }
+
+    #[test]
+    fn test_saved_game_continues_identically() {
+        for rules in [Rules::classic(), Rules::salvo()] {
+            let mut game = Game::with_rules(rules, 21, &AutoShipPlacer, &AutoShipPlacer);
+            play(&mut game, 9);
+
+            let path = std::env::temp_dir().join(format!("sea_battle_save_{}_{}.json", std::process::id(), game.rules.salvo));
+            SavedGame::new(game.clone(), "Сложный").save(&path).unwrap();
+            let loaded = SavedGame::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(loaded.opponent, "Сложный");
+            let mut resumed = loaded.game;
+            assert_eq!(resumed.player_board, game.player_board);
+            assert_eq!(resumed.computer_board, game.computer_board);
+            assert_eq!(resumed.turn, game.turn);
+
+            // генератор продолжается с того же места, поэтому компьютер стреляет так же
+            play(&mut game, usize::MAX);
+            play(&mut resumed, usize::MAX);
+            assert_eq!(resumed.turn, Turn::Over);
+            assert_eq!(resumed.replay, game.replay);
+        }
+    }
+
+    #[test]
+    fn test_load_rejects_broken_board() {
+        let mut game = Game::with_seed(3, &AutoShipPlacer, &AutoShipPlacer);
+        game.computer_board.cells[0][0] = Some(99);
+        let path = std::env::temp_dir().join(format!("sea_battle_broken_save_{}.json", std::process::id()));
+        SavedGame::new(game, "Легкий").save(&path).unwrap();
+        let result = SavedGame::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_check_board_matches_cells_and_ships() {
+        let game = Game::with_seed(5, &AutoShipPlacer, &AutoShipPlacer);
+        assert!(check_board(&game.player_board, &game.rules).is_ok());
+
+        // клетка корабля отдана другому кораблю
+        let mut board = game.player_board.clone();
+        let (row, col) = board.ships[0].coords[0];
+        board.cells[row][col] = Some(1);
+        assert!(check_board(&board, &game.rules).is_err());
+
+        // номер корабля стоит в пустой клетке
+        let mut board = game.player_board.clone();
+        let col = board.cells[0].iter().position(Option::is_none).unwrap();
+        board.cells[0][col] = Some(0);
+        assert!(check_board(&board, &game.rules).is_err());
+
+        // клетка корабля пуста
+        let mut board = game.player_board.clone();
+        let (row, col) = board.ships[2].coords[0];
+        board.cells[row][col] = None;
+        assert!(check_board(&board, &game.rules).is_err());
+    }
+
+    #[test]
+    fn test_check_board_rejects_wrong_fleet() {
+        let game = Game::with_seed(6, &AutoShipPlacer, &AutoShipPlacer);
+
+        // корабль без клеток
+        let mut board = game.player_board.clone();
+        board.ships.push(Ship { coords: Vec::new(), size: 0, hits: Vec::new() });
+        assert!(check_board(&board, &game.rules).is_err());
+
+        // лишний корабль, которого нет во флоте правил
+        let mut board = game.player_board.clone();
+        let col = board.cells[0].iter().position(Option::is_none).unwrap();
+        board.ships.push(Ship::new(vec![(0, col)], 1));
+        board.cells[0][col] = Some(board.ships.len() - 1);
+        assert!(check_board(&board, &game.rules).is_err());
+
+        // пропавший корабль
+        let mut board = game.player_board.clone();
+        let last = board.ships.pop().unwrap();
+        for &(row, col) in &last.coords {
+            board.cells[row][col] = None;
+        }
+        assert!(check_board(&board, &game.rules).is_err());
+    }
+}
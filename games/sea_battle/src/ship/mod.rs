@@ -1,11 +1,13 @@
-#[derive(Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ShootResult {
     Miss,
     Hit,
     Destroy,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Ship {
     pub coords: Vec<(usize, usize)>,
     pub size: usize,
@@ -36,9 +38,9 @@ impl Ship {
 mod tests {
     use super::*;
     use crate::board::Board;
-    use std::{rc::Rc, cell::RefCell};
 
     #[test]
+    #[allow(clippy::bool_assert_comparison)]
     fn test_ship_creation() {
         let ship = Ship::new(vec![(0, 0), (0, 1)], 2);
         assert_eq!(ship.size, 2);
@@ -49,7 +51,7 @@ mod tests {
     #[test]
     fn test_board_place_ship() {
         let mut board = Board::new(10, 10);
-        let ship = Rc::new(RefCell::new(Ship::new(vec![(0, 0), (0, 1)], 2)));
+        let ship = Ship::new(vec![(0, 0), (0, 1)], 2);
 
         assert!(board.place_ship(ship).is_ok());
         assert!(board.cells[0][0].is_some());
@@ -59,7 +61,7 @@ mod tests {
     #[test]
     fn test_ship_destroyed() {
         let mut board = Board::new(10, 10);
-        let ship = Rc::new(RefCell::new(Ship::new(vec![(0, 0), (0, 1)], 2)));
+        let ship = Ship::new(vec![(0, 0), (0, 1)], 2);
 
         board.place_ship(ship).unwrap();
         assert_eq!(board.shoot(0, 0), ShootResult::Hit);
@@ -70,7 +72,7 @@ mod tests {
     #[test]
     fn test_all_ships_destroyed_with_alive_ships() {
         let mut board = Board::new(10, 10);
-        let ship = Rc::new(RefCell::new(Ship::new(vec![(0, 0), (0, 1)], 2)));
+        let ship = Ship::new(vec![(0, 0), (0, 1)], 2);
 
         board.place_ship(ship).unwrap();
         assert!(!board.all_ships_destroyed(), "Корабль должен быть еще жив");
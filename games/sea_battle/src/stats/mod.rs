@@ -0,0 +1,244 @@
+use crate::game::Game;
+use crate::replay::Side;
+use crate::ship::ShootResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+// файл профиля игрока по умолчанию
+pub const DEFAULT_STATS_PATH: &str = "sea_battle_stats.json";
+
+// версия формата файла профиля
+pub const STATS_VERSION: u32 = 1;
+
+// итоги одной партии с точки зрения игрока
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub finished_at: DateTime<Utc>,
+    pub won: bool,
+    pub opponent: String, // стратегия компьютера
+    pub rules: String,
+    pub seed: u64,
+    pub shots: usize,
+    pub hits: usize,
+    pub turns: usize, // ходы игрока; в классике серия попаданий - один ход
+    pub ships_lost: usize,
+}
+
+impl GameRecord {
+    // итоги законченной партии по ее записи
+    pub fn from_game(game: &Game, opponent: &str) -> Self {
+        let mut turns = 0;
+        let mut previous = None;
+        for shot in &game.replay.shots {
+            if shot.shooter == Side::Player && previous != Some(Side::Player) {
+                turns += 1;
+            }
+            previous = Some(shot.shooter);
+        }
+        let player_shots: Vec<_> = game.replay.shots.iter().filter(|shot| shot.shooter == Side::Player).collect();
+
+        GameRecord {
+            finished_at: Utc::now(),
+            won: game.computer_board.all_ships_destroyed(),
+            opponent: opponent.to_string(),
+            rules: game.rules.name.clone(),
+            seed: game.seed,
+            shots: player_shots.len(),
+            hits: player_shots.iter().filter(|shot| shot.result != ShootResult::Miss).count(),
+            turns,
+            ships_lost: game.player_board.sunk_ship_sizes().len(),
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.shots == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / self.shots as f64
+    }
+}
+
+// сводка по всем партиям профиля
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Summary {
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub accuracy: f64,
+    pub average_turns_to_win: Option<f64>,
+    pub current_streak: isize, // больше нуля - победы подряд, меньше нуля - поражения
+    pub best_win_streak: usize,
+    pub worst_loss_streak: usize,
+}
+
+impl Summary {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+}
+
+// Профиль игрока: история партий, которая сохраняется между запусками
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub version: u32,
+    pub games: Vec<GameRecord>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile { version: STATS_VERSION, games: Vec::new() }
+    }
+}
+
+impl Profile {
+    // профиль из файла; если файла еще нет, профиль пустой
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Profile::default());
+        }
+        let json = fs::read_to_string(path).map_err(|e| format!("Не удалось прочитать статистику: {}", e))?;
+        let profile: Profile = serde_json::from_str(&json).map_err(|e| format!("Неверный файл статистики: {}", e))?;
+        if profile.version != STATS_VERSION {
+            return Err(format!("Неподдерживаемая версия статистики: {}", profile.version));
+        }
+        Ok(profile)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| format!("Не удалось сохранить статистику: {}", e))
+    }
+
+    // добавляет партию и сразу сохраняет профиль
+    pub fn record(&mut self, record: GameRecord, path: &Path) -> Result<(), String> {
+        self.games.push(record);
+        self.save(path)
+    }
+
+    // Дописывает партию в профиль на диске и возвращает его. Файл перечитывается перед записью,
+    // чтобы не затереть партии, записанные другим окном, а непрочитанный файл не перезаписывается
+    pub fn append(path: &Path, record: GameRecord) -> Result<Profile, String> {
+        let mut profile = Profile::load(path)?;
+        profile.record(record, path)?;
+        Ok(profile)
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary { games: self.games.len(), ..Summary::default() };
+        let (mut shots, mut hits, mut win_turns) = (0, 0, 0);
+        let (mut wins_in_row, mut losses_in_row) = (0, 0);
+
+        for game in &self.games {
+            shots += game.shots;
+            hits += game.hits;
+            if game.won {
+                summary.wins += 1;
+                win_turns += game.turns;
+                wins_in_row += 1;
+                losses_in_row = 0;
+            } else {
+                summary.losses += 1;
+                losses_in_row += 1;
+                wins_in_row = 0;
+            }
+            summary.best_win_streak = summary.best_win_streak.max(wins_in_row);
+            summary.worst_loss_streak = summary.worst_loss_streak.max(losses_in_row);
+        }
+
+        summary.current_streak = wins_in_row as isize - losses_in_row as isize;
+        if shots > 0 {
+            summary.accuracy = hits as f64 / shots as f64;
+        }
+        if summary.wins > 0 {
+            summary.average_turns_to_win = Some(win_turns as f64 / summary.wins as f64);
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::ProbabilityDensityStrategy;
+    use crate::board::AutoShipPlacer;
+
+    fn record(won: bool, shots: usize, hits: usize, turns: usize) -> GameRecord {
+        GameRecord {
+            finished_at: Utc::now(),
+            won,
+            opponent: "Сложный".to_string(),
+            rules: "Классика".to_string(),
+            seed: 0,
+            shots,
+            hits,
+            turns,
+            ships_lost: 0,
+        }
+    }
+
+    #[test]
+    fn test_summary_streaks() {
+        let mut profile = Profile::default();
+        assert_eq!(profile.summary(), Summary::default());
+
+        for won in [true, true, false, true, true, true, false, false] {
+            profile.games.push(record(won, 60, 20, if won { 40 } else { 0 }));
+        }
+        let summary = profile.summary();
+        assert_eq!((summary.wins, summary.losses), (5, 3));
+        assert_eq!(summary.best_win_streak, 3);
+        assert_eq!(summary.worst_loss_streak, 2);
+        assert_eq!(summary.current_streak, -2);
+        assert_eq!(summary.average_turns_to_win, Some(40.0));
+        assert!((summary.accuracy - 1.0 / 3.0).abs() < 1e-9);
+        assert!((summary.win_rate() - 0.625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_from_game() {
+        let mut game = Game::with_seed(11, &AutoShipPlacer, &AutoShipPlacer);
+        game.player_shoot(0, 0);
+        while !game.check_game_over() {
+            game.computer_shoot(&ProbabilityDensityStrategy);
+        }
+
+        let record = GameRecord::from_game(&game, "Сложный");
+        assert!(!record.won);
+        assert_eq!((record.shots, record.turns), (1, 1));
+        assert_eq!(record.ships_lost, 10);
+        assert_eq!(record.hits, game.computer_board.shots[0][0].unwrap() as usize);
+    }
+
+    #[test]
+    fn test_profile_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("sea_battle_stats_{}.json", std::process::id()));
+        assert_eq!(Profile::load(&path).unwrap(), Profile::default());
+
+        let mut profile = Profile::default();
+        profile.record(record(true, 30, 20, 12), &path).unwrap();
+        let loaded = Profile::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, profile);
+    }
+
+    #[test]
+    fn test_append_keeps_other_games_and_broken_file() {
+        let path = std::env::temp_dir().join(format!("sea_battle_stats_append_{}.json", std::process::id()));
+        // профиль, загруженный до партий другого окна, не затирает их
+        let stale = Profile::load(&path).unwrap();
+        Profile::append(&path, record(true, 30, 20, 12)).unwrap();
+        let profile = Profile::append(&path, record(false, 40, 10, 0)).unwrap();
+        assert_eq!(profile.games.len(), 2);
+        assert!(stale.games.is_empty());
+
+        std::fs::write(&path, "{ испорчен").unwrap();
+        assert!(Profile::append(&path, record(true, 30, 20, 12)).is_err());
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(contents, "{ испорчен");
+    }
+}
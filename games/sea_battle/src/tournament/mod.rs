@@ -0,0 +1,187 @@
+use crate::ai::{Difficulty, Layout};
+use crate::game::{Game, GameRng, Turn};
+use crate::replay::Side;
+use crate::rules::Rules;
+use rand::SeedableRng;
+use std::{fs, path::Path, thread};
+
+// Участник турнира: расстановка кораблей и стратегия выстрелов
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contender {
+    pub difficulty: Difficulty,
+    pub layout: Layout,
+}
+
+impl Contender {
+    // имя вида "Сложный/Края"
+    pub fn name(&self) -> String {
+        format!("{}/{}", self.difficulty.name(), self.layout.name())
+    }
+
+    // "Сложный" или "Сложный/Края"; без расстановки - случайная
+    pub fn from_name(name: &str) -> Option<Contender> {
+        let (difficulty, layout) = name.split_once('/').unwrap_or((name, Layout::Random.name()));
+        Some(Contender { difficulty: Difficulty::from_name(difficulty)?, layout: Layout::from_name(layout)? })
+    }
+}
+
+// итог одной партии турнира; участники - 0 и 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchResult {
+    pub game: usize,
+    pub seed: u64,
+    pub first: usize, // кто стрелял первым
+    pub winner: usize,
+    pub shots: [usize; 2], // выстрелы каждого участника
+}
+
+// Партия двух компьютеров. Участник first играет за игрока, другой - за компьютера.
+// Его выстрелы берут случайность из своего генератора, поэтому партия повторяется по зерну
+pub fn play_match(contenders: [Contender; 2], rules: &Rules, game: usize, seed: u64, first: usize) -> MatchResult {
+    let (player, computer) = (contenders[first], contenders[1 - first]);
+    let mut state = Game::with_rules(rules.clone(), seed, &*player.layout.placer(), &*computer.layout.placer());
+    let mut player_rng = GameRng::seed_from_u64(seed.rotate_left(32));
+    let (player_strategy, computer_strategy) = (player.difficulty.strategy(), computer.difficulty.strategy());
+
+    while state.turn != Turn::Over {
+        if state.turn == Turn::Player {
+            let count = state.volley_size(Side::Player);
+            let cells = player_strategy.choose_volley(&state.computer_board, count, &mut player_rng);
+            state.player_volley(&cells).expect("Стратегия выбрала недопустимый залп");
+        } else {
+            state.computer_volley(&*computer_strategy);
+        }
+    }
+
+    let count = |side: Side| state.replay.shots.iter().filter(|shot| shot.shooter == side).count();
+    let (player_shots, computer_shots) = (count(Side::Player), count(Side::Computer));
+    let player_won = state.computer_board.all_ships_destroyed();
+    MatchResult {
+        game,
+        seed,
+        first,
+        winner: if player_won { first } else { 1 - first },
+        shots: if first == 0 { [player_shots, computer_shots] } else { [computer_shots, player_shots] },
+    }
+}
+
+// Серия партий на нескольких потоках. Партия i играется с зерном seed + i,
+// а первым в ней стреляет участник i % 2, поэтому итог не зависит от числа потоков
+pub fn run(contenders: [Contender; 2], rules: &Rules, games: usize, seed: u64, threads: usize) -> Vec<MatchResult> {
+    let threads = threads.clamp(1, games.max(1));
+    let mut results: Vec<MatchResult> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                scope.spawn(move || {
+                    (worker..games)
+                        .step_by(threads)
+                        .map(|game| play_match(contenders, rules, game, seed.wrapping_add(game as u64), game % 2))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers.into_iter().flat_map(|worker| worker.join().expect("Поток турнира упал")).collect()
+    });
+    results.sort_by_key(|result| result.game);
+    results
+}
+
+// итоги одного участника
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContenderReport {
+    pub wins: usize,
+    pub win_rate: f64,
+    pub mean_shots: f64,
+    pub median_shots: usize,
+    pub p90_shots: usize,
+    pub p99_shots: usize,
+    pub mean_shots_to_win: Option<f64>, // только по выигранным партиям
+}
+
+pub fn report(results: &[MatchResult], contender: usize) -> ContenderReport {
+    let mut shots: Vec<usize> = results.iter().map(|result| result.shots[contender]).collect();
+    shots.sort_unstable();
+    let won: Vec<usize> = results.iter().filter(|result| result.winner == contender).map(|result| result.shots[contender]).collect();
+    let mean = |values: &[usize]| values.iter().sum::<usize>() as f64 / values.len().max(1) as f64;
+
+    ContenderReport {
+        wins: won.len(),
+        win_rate: won.len() as f64 / results.len().max(1) as f64,
+        mean_shots: mean(&shots),
+        median_shots: percentile(&shots, 50.0),
+        p90_shots: percentile(&shots, 90.0),
+        p99_shots: percentile(&shots, 99.0),
+        mean_shots_to_win: (!won.is_empty()).then(|| mean(&won)),
+    }
+}
+
+// процентиль по ближайшему рангу из отсортированных значений
+pub fn percentile(sorted: &[usize], percent: f64) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+// по строке на партию, чтобы результаты можно было разобрать в таблице
+pub fn write_csv(results: &[MatchResult], contenders: [Contender; 2], path: &Path) -> Result<(), String> {
+    let mut csv = String::from("game,seed,first,winner,shots_a,shots_b\n");
+    for result in results {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            result.game,
+            result.seed,
+            contenders[result.first].name(),
+            contenders[result.winner].name(),
+            result.shots[0],
+            result.shots[1],
+        ));
+    }
+    fs::write(path, csv).map_err(|e| format!("Не удалось сохранить CSV: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contender(name: &str) -> Contender {
+        Contender::from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_contender_names() {
+        assert_eq!(contender("сложный/края"), Contender { difficulty: Difficulty::Hard, layout: Layout::Edge });
+        assert_eq!(contender("Легкий").layout, Layout::Random);
+        assert_eq!(contender("Средний/Вразброс").name(), "Средний/Вразброс");
+        assert_eq!(Contender::from_name("Сложный/Угол"), None);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&values, 50.0), 5);
+        assert_eq!(percentile(&values, 90.0), 9);
+        assert_eq!(percentile(&values, 99.0), 10);
+        assert_eq!(percentile(&values, 0.0), 1);
+        assert_eq!(percentile(&[], 50.0), 0);
+    }
+
+    #[test]
+    fn test_results_do_not_depend_on_threads() {
+        let contenders = [contender("Средний/Края"), contender("Легкий/Вразброс")];
+        let rules = Rules::salvo();
+        assert_eq!(run(contenders, &rules, 6, 9, 1), run(contenders, &rules, 6, 9, 4));
+    }
+
+    #[test]
+    fn test_stronger_strategy_wins_more() {
+        let contenders = [contender("Средний"), contender("Легкий")];
+        let results = run(contenders, &Rules::classic(), 20, 1, 4);
+        let (strong, weak) = (report(&results, 0), report(&results, 1));
+        assert_eq!(strong.wins + weak.wins, 20);
+        assert!(strong.wins >= 15, "побед: {}", strong.wins);
+        assert!(strong.mean_shots_to_win.unwrap() < 80.0);
+        assert!(results.iter().all(|result| result.shots[result.winner] >= Rules::classic().ship_sizes().iter().sum()));
+    }
+}
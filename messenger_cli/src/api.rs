@@ -0,0 +1,59 @@
+use messenger_protocol::structs::{ChatExport, ExportedMessage, SessionTokens, UserChat};
+use serde_json::json;
+use crate::CliResult;
+
+// клиент REST API сервера
+pub struct ApiClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl ApiClient {
+    pub fn new(base_url: &str) -> Self {
+        ApiClient {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    // ответ с ошибкой превращается в текст сервера
+    async fn check(response: reqwest::Response) -> CliResult<reqwest::Response> {
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("{}: {}", status, body).into())
+    }
+
+    pub async fn login(&self, username: &str, password: &str) -> CliResult<SessionTokens> {
+        let response = self.http
+            .post(format!("{}/login", self.base_url))
+            .json(&json!({ "username": username, "password": password }))
+            .send()
+            .await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    // чаты пользователя со счетчиками непрочитанных
+    pub async fn my_chats(&self, token: &str) -> CliResult<Vec<UserChat>> {
+        let response = self.http
+            .get(format!("{}/users/me/chats", self.base_url))
+            .bearer_auth(token)
+            .send()
+            .await?;
+        Ok(Self::check(response).await?.json().await?)
+    }
+
+    // история группового чата через выгрузку, сервер отдает ChatExport
+    pub async fn chat_history(&self, chat_id: i32, token: &str) -> CliResult<Vec<ExportedMessage>> {
+        let response = self.http
+            .get(format!("{}/chats/{}/export", self.base_url, chat_id))
+            .query(&[("format", "json")])
+            .bearer_auth(token)
+            .send()
+            .await?;
+        let export: ChatExport = Self::check(response).await?.json().await?;
+        Ok(export.messages)
+    }
+}
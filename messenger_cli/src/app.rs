@@ -0,0 +1,364 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use messenger_protocol::structs::{ExportedMessage, SendOptions, UserChat};
+use messenger_protocol::Message;
+use crate::history::ChatLine;
+
+// префикс локальных команд клиента; команды с "/" уходят на сервер
+pub const LOCAL_COMMAND_PREFIX: char = ':';
+
+// сколько строк прокручивают PageUp/PageDown
+pub const PAGE_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversationKind {
+    General,
+    Group(i32),
+    Private(String),
+}
+
+impl ConversationKind {
+    // имя файла локальной истории
+    pub fn history_key(&self) -> String {
+        match self {
+            ConversationKind::General => "general".to_string(),
+            ConversationKind::Group(chat_id) => format!("chat-{}", chat_id),
+            ConversationKind::Private(peer) => format!("dm-{}", peer),
+        }
+    }
+
+    // идентификатор чата в кадрах протокола, у приватной переписки его нет
+    fn chat_id(&self) -> Option<Option<i32>> {
+        match self {
+            ConversationKind::General => Some(None),
+            ConversationKind::Group(chat_id) => Some(Some(*chat_id)),
+            ConversationKind::Private(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Conversation {
+    pub kind: ConversationKind,
+    pub title: String,
+    pub lines: Vec<ChatLine>,
+    pub unread: i64,
+    pub scroll: usize, // на сколько строк прокручено вверх от конца
+    pub history_requested: bool,
+}
+
+impl Conversation {
+    pub fn new(kind: ConversationKind, title: String) -> Self {
+        Conversation { kind, title, lines: Vec::new(), unread: 0, scroll: 0, history_requested: false }
+    }
+
+    fn last_id(&self) -> Option<i32> {
+        self.lines.iter().rev().find_map(|line| line.id)
+    }
+}
+
+// действия, которые состояние просит выполнить снаружи
+#[derive(Debug)]
+pub enum Effect {
+    Send(Message),
+    Persist { key: String, line: ChatLine },
+    LoadGroupHistory(i32),
+    Quit,
+}
+
+pub struct App {
+    pub username: String,
+    pub conversations: Vec<Conversation>,
+    pub selected: usize,
+    pub input: String,
+    pub status: String,
+}
+
+impl App {
+    // список чатов с сервера и собеседники из локальной истории
+    pub fn new(username: &str, chats: Vec<UserChat>, private_peers: Vec<String>) -> Self {
+        let mut conversations: Vec<Conversation> = chats
+            .into_iter()
+            .map(|chat| {
+                let kind = match chat.chat_id {
+                    Some(chat_id) => ConversationKind::Group(chat_id),
+                    None => ConversationKind::General,
+                };
+                let mut conversation = Conversation::new(kind, chat.name);
+                conversation.unread = chat.unread;
+                conversation
+            })
+            .collect();
+        if !conversations.iter().any(|c| c.kind == ConversationKind::General) {
+            conversations.insert(0, Conversation::new(ConversationKind::General, "Общий чат".to_string()));
+        }
+        for peer in private_peers {
+            conversations.push(Conversation::new(ConversationKind::Private(peer.clone()), format!("@{}", peer)));
+        }
+
+        App {
+            username: username.to_string(),
+            conversations,
+            selected: 0,
+            input: String::new(),
+            status: "Tab - следующий чат, :pm <имя> - личные сообщения, Esc - выход".to_string(),
+        }
+    }
+
+    pub fn active(&self) -> &Conversation {
+        &self.conversations[self.selected]
+    }
+
+    fn find(&self, kind: &ConversationKind) -> Option<usize> {
+        self.conversations.iter().position(|c| c.kind == *kind)
+    }
+
+    // разговор находится или создается, например при первом личном сообщении
+    fn find_or_create(&mut self, kind: ConversationKind) -> usize {
+        if let Some(index) = self.find(&kind) {
+            return index;
+        }
+        let title = match &kind {
+            ConversationKind::General => "Общий чат".to_string(),
+            ConversationKind::Group(chat_id) => format!("Чат #{}", chat_id),
+            ConversationKind::Private(peer) => format!("@{}", peer),
+        };
+        self.conversations.push(Conversation::new(kind, title));
+        self.conversations.len() - 1
+    }
+
+    // история из локального кеша
+    pub fn load_cached(&mut self, kind: &ConversationKind, lines: Vec<ChatLine>) {
+        if let Some(index) = self.find(kind) {
+            self.conversations[index].lines = lines;
+        }
+    }
+
+    // история группы, загруженная с сервера, заменяет кеш
+    pub fn load_group_history(&mut self, chat_id: i32, messages: Vec<ExportedMessage>) -> Vec<Effect> {
+        let Some(index) = self.find(&ConversationKind::Group(chat_id)) else {
+            return Vec::new();
+        };
+        self.conversations[index].lines = messages
+            .into_iter()
+            .map(|m| ChatLine { id: Some(m.id), sender: m.sender, content: m.content })
+            .collect();
+        if index == self.selected {
+            self.mark_read()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn select(&mut self, index: usize) -> Vec<Effect> {
+        if index >= self.conversations.len() {
+            return Vec::new();
+        }
+        self.selected = index;
+        let conversation = &mut self.conversations[index];
+        conversation.scroll = 0;
+
+        let mut effects = Vec::new();
+        if let ConversationKind::Group(chat_id) = conversation.kind {
+            if conversation.lines.is_empty() && !conversation.history_requested {
+                conversation.history_requested = true;
+                effects.push(Effect::LoadGroupHistory(chat_id));
+            }
+        }
+        effects.extend(self.mark_read());
+        effects
+    }
+
+    // отметка о прочтении для открытого чата, если в нем есть непрочитанные
+    fn mark_read(&mut self) -> Vec<Effect> {
+        let conversation = &mut self.conversations[self.selected];
+        let (Some(chat_id), Some(message_id)) = (conversation.kind.chat_id(), conversation.last_id()) else {
+            return Vec::new();
+        };
+        if conversation.unread == 0 {
+            return Vec::new();
+        }
+        conversation.unread = 0;
+        vec![Effect::Send(Message::MarkRead { chat_id, message_id })]
+    }
+
+    pub fn scroll_up(&mut self, lines: usize) {
+        let conversation = &mut self.conversations[self.selected];
+        conversation.scroll = (conversation.scroll + lines).min(conversation.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        let conversation = &mut self.conversations[self.selected];
+        conversation.scroll = conversation.scroll.saturating_sub(lines);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Vec<Effect> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return vec![Effect::Quit];
+        }
+        match key.code {
+            KeyCode::Esc => vec![Effect::Quit],
+            KeyCode::Tab => self.select((self.selected + 1) % self.conversations.len()),
+            KeyCode::BackTab => {
+                let count = self.conversations.len();
+                self.select((self.selected + count - 1) % count)
+            }
+            KeyCode::PageUp => {
+                self.scroll_up(PAGE_SIZE);
+                Vec::new()
+            }
+            KeyCode::PageDown => {
+                self.scroll_down(PAGE_SIZE);
+                Vec::new()
+            }
+            KeyCode::Up => {
+                self.scroll_up(1);
+                Vec::new()
+            }
+            KeyCode::Down => {
+                self.scroll_down(1);
+                Vec::new()
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                Vec::new()
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    // отправка строки ввода: локальная команда или сообщение в открытый чат
+    pub fn submit(&mut self) -> Vec<Effect> {
+        let input = std::mem::take(&mut self.input);
+        let text = input.trim();
+        if text.is_empty() {
+            return Vec::new();
+        }
+        if let Some(command) = text.strip_prefix(LOCAL_COMMAND_PREFIX) {
+            return self.local_command(command);
+        }
+
+        let content = text.to_string();
+        let message = match &self.active().kind {
+            ConversationKind::General => Message::SendMessage { content, options: SendOptions::default() },
+            ConversationKind::Group(chat_id) => Message::SendMessageToGroupChat {
+                chat_id: *chat_id,
+                content,
+                options: SendOptions::default(),
+            },
+            ConversationKind::Private(peer) => Message::SendPrivateMessage { recipient: peer.clone(), content },
+        };
+        self.conversations[self.selected].scroll = 0;
+        vec![Effect::Send(message)]
+    }
+
+    fn local_command(&mut self, command: &str) -> Vec<Effect> {
+        let mut parts = command.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("quit" | "q"), _) => vec![Effect::Quit],
+            (Some("pm"), Some(peer)) => {
+                let index = self.find_or_create(ConversationKind::Private(peer.to_string()));
+                self.select(index)
+            }
+            (Some("chat"), Some(id)) => match id.parse::<i32>() {
+                Ok(chat_id) => {
+                    let index = self.find_or_create(ConversationKind::Group(chat_id));
+                    self.select(index)
+                }
+                Err(_) => {
+                    self.status = format!("Неверный номер чата: {}", id);
+                    Vec::new()
+                }
+            },
+            _ => {
+                self.status = "Команды: :pm <имя>, :chat <номер>, :quit".to_string();
+                Vec::new()
+            }
+        }
+    }
+
+    // строка попадает в разговор и в локальный кеш
+    fn push_line(&mut self, kind: ConversationKind, line: ChatLine) -> Vec<Effect> {
+        let index = self.find_or_create(kind.clone());
+        let conversation = &mut self.conversations[index];
+        conversation.lines.push(line.clone());
+        // прокрученная вверх история остается на месте
+        if conversation.scroll > 0 {
+            conversation.scroll += 1;
+        }
+
+        let mut effects = vec![Effect::Persist { key: kind.history_key(), line: line.clone() }];
+        if index == self.selected && line.id.is_some() && line.sender != self.username {
+            conversation.unread += 1;
+            effects.extend(self.mark_read());
+        }
+        effects
+    }
+
+    pub fn handle_server(&mut self, message: Message) -> Vec<Effect> {
+        match message {
+            // служебные сообщения сервера не сохраняются в истории
+            Message::ReceiveMessage { id: None, content, .. } => {
+                self.status = content;
+                Vec::new()
+            }
+            Message::ReceiveMessage { id, sender, content, .. } => {
+                self.push_line(ConversationKind::General, ChatLine { id, sender, content })
+            }
+            Message::ReceiveGroupChatMessage { id, chat_id, sender, content, .. } => {
+                self.push_line(ConversationKind::Group(chat_id), ChatLine { id, sender, content })
+            }
+            Message::ReceivePrivateMessage { sender, recipient, content } => {
+                // копия собственного сообщения с другого устройства относится к переписке с получателем
+                let peer = if sender == self.username { recipient } else { sender.clone() };
+                self.push_line(ConversationKind::Private(peer), ChatLine { id: None, sender, content })
+            }
+            Message::ErrorMessage { error } => {
+                self.status = format!("Ошибка: {}", error);
+                Vec::new()
+            }
+            Message::TopicChanged { chat_id, topic, changed_by } => {
+                self.status = match topic {
+                    Some(topic) => format!("{} сменил тему чата #{}: {}", changed_by, chat_id, topic),
+                    None => format!("{} убрал тему чата #{}", changed_by, chat_id),
+                };
+                Vec::new()
+            }
+            Message::UnreadCounts { counts } => {
+                for count in counts {
+                    self.set_unread(count.chat_id, count.unread);
+                }
+                self.mark_read()
+            }
+            Message::UnreadCountUpdated { chat_id, unread } => {
+                self.set_unread(chat_id, unread);
+                // в открытом чате новые сообщения сразу считаются прочитанными
+                self.mark_read()
+            }
+            Message::MessageDeleted { message_id, chat_id } => {
+                let kind = match chat_id {
+                    Some(chat_id) => ConversationKind::Group(chat_id),
+                    None => ConversationKind::General,
+                };
+                if let Some(index) = self.find(&kind) {
+                    self.conversations[index].lines.retain(|line| line.id != Some(message_id));
+                }
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn set_unread(&mut self, chat_id: Option<i32>, unread: i64) {
+        let kind = match chat_id {
+            Some(chat_id) => ConversationKind::Group(chat_id),
+            None => ConversationKind::General,
+        };
+        let index = self.find_or_create(kind);
+        self.conversations[index].unread = unread;
+    }
+}
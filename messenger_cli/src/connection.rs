@@ -0,0 +1,48 @@
+use futures_util::{SinkExt, StreamExt};
+use messenger_protocol::Message;
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use crate::CliResult;
+
+// Подключение к WebSocket серверу. Возвращает канал для отправки кадров
+// и канал входящих кадров; входящий канал закрывается вместе с соединением
+pub async fn connect(
+    ws_url: &str,
+    username: &str,
+    token: &str,
+) -> CliResult<(mpsc::UnboundedSender<Message>, mpsc::UnboundedReceiver<Message>)> {
+    let (ws, _) = connect_async(ws_url).await?;
+    let (mut writer, mut reader) = ws.split();
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<Message>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Message>();
+
+    // отправка кадров серверу
+    tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            let text = serde_json::to_string(&message).unwrap();
+            if writer.send(WsMessage::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+        let _ = writer.close().await;
+    });
+
+    // чтение кадров сервера, неизвестные кадры пропускаются
+    tokio::spawn(async move {
+        while let Some(Ok(frame)) = reader.next().await {
+            let WsMessage::Text(text) = frame else {
+                continue;
+            };
+            if let Ok(message) = serde_json::from_str::<Message>(&text) {
+                if incoming_tx.send(message).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    outgoing_tx.send(Message::Join { username: username.to_string(), token: token.to_string() })?;
+    Ok((outgoing_tx, incoming_rx))
+}
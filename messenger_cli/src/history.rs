@@ -0,0 +1,85 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use crate::CliResult;
+
+// строка переписки в локальном кеше
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatLine {
+    #[serde(default)]
+    pub id: Option<i32>,
+    pub sender: String,
+    pub content: String,
+}
+
+// Локальная история: по файлу JSON Lines на разговор. Клиент сначала показывает
+// кеш и только потом дополняет его тем, что пришло с сервера
+pub struct History {
+    dir: PathBuf,
+}
+
+impl History {
+    pub fn open(dir: PathBuf) -> CliResult<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(History { dir })
+    }
+
+    // каталог по умолчанию: MESSENGER_CLI_DATA или ~/.messenger_cli/<пользователь>
+    pub fn default_dir(username: &str) -> PathBuf {
+        let base = std::env::var("MESSENGER_CLI_DATA")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+                PathBuf::from(home).join(".messenger_cli")
+            });
+        base.join(username)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", key))
+    }
+
+    // поврежденные строки пропускаются, чтобы не терять остальную историю
+    pub fn load(&self, key: &str) -> Vec<ChatLine> {
+        let Ok(file) = File::open(self.path(key)) else {
+            return Vec::new();
+        };
+        BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+
+    pub fn append(&self, key: &str, line: &ChatLine) -> CliResult<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(self.path(key))?;
+        writeln!(file, "{}", serde_json::to_string(line)?)?;
+        Ok(())
+    }
+
+    // полная перезапись, когда историю группы загрузили с сервера
+    pub fn replace(&self, key: &str, lines: &[ChatLine]) -> CliResult<()> {
+        let mut file = File::create(self.path(key))?;
+        for line in lines {
+            writeln!(file, "{}", serde_json::to_string(line)?)?;
+        }
+        Ok(())
+    }
+
+    // собеседники, с которыми уже есть локальная переписка
+    pub fn private_peers(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut peers: Vec<String> = entries
+            .map_while(Result::ok)
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_prefix("dm-")?.strip_suffix(".jsonl").map(str::to_string)
+            })
+            .collect();
+        peers.sort();
+        peers
+    }
+}
@@ -0,0 +1,8 @@
+// терминальный клиент мессенджера: REST для входа и списка чатов, WebSocket для сообщений
+pub mod api;
+pub mod app;
+pub mod connection;
+pub mod history;
+pub mod ui;
+
+pub type CliResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
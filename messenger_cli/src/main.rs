@@ -0,0 +1,132 @@
+use std::io::{self, Write};
+use crossterm::event::{Event, EventStream, KeyEventKind};
+use crossterm::execute;
+use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use futures_util::StreamExt;
+use messenger_cli::api::ApiClient;
+use messenger_cli::app::{App, ConversationKind, Effect};
+use messenger_cli::history::{ChatLine, History};
+use messenger_cli::{connection, ui, CliResult};
+use messenger_protocol::Message;
+use tokio::sync::mpsc::UnboundedSender;
+
+fn prompt(label: &str) -> io::Result<String> {
+    print!("{}", label);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+// Выполнение действий, которые запросило состояние клиента.
+// Возвращает false, если пользователь вышел
+async fn apply(
+    effects: Vec<Effect>,
+    app: &mut App,
+    api: &ApiClient,
+    history: &History,
+    token: &str,
+    outgoing: &UnboundedSender<Message>,
+) -> bool {
+    for effect in effects {
+        match effect {
+            Effect::Send(message) => {
+                if outgoing.send(message).is_err() {
+                    app.status = "Соединение с сервером потеряно".to_string();
+                }
+            }
+            Effect::Persist { key, line } => {
+                if let Err(e) = history.append(&key, &line) {
+                    app.status = format!("Не удалось сохранить историю: {}", e);
+                }
+            }
+            Effect::LoadGroupHistory(chat_id) => match api.chat_history(chat_id, token).await {
+                Ok(messages) => {
+                    let key = ConversationKind::Group(chat_id).history_key();
+                    let lines: Vec<ChatLine> = messages
+                        .iter()
+                        .map(|m| ChatLine { id: Some(m.id), sender: m.sender.clone(), content: m.content.clone() })
+                        .collect();
+                    let _ = history.replace(&key, &lines);
+                    let effects = app.load_group_history(chat_id, messages);
+                    // отметки о прочтении не порождают новых загрузок
+                    for effect in effects {
+                        if let Effect::Send(message) = effect {
+                            let _ = outgoing.send(message);
+                        }
+                    }
+                }
+                Err(e) => app.status = format!("Не удалось загрузить историю чата: {}", e),
+            },
+            Effect::Quit => return false,
+        }
+    }
+    true
+}
+
+async fn run(mut app: App, api: ApiClient, history: History, token: &str, ws_url: &str) -> CliResult<()> {
+    let (outgoing, mut incoming) = connection::connect(ws_url, &app.username, token).await?;
+    let mut events = EventStream::new();
+
+    let effects = app.select(0);
+    apply(effects, &mut app, &api, &history, token, &outgoing).await;
+    ui::draw(&app)?;
+
+    loop {
+        let running = tokio::select! {
+            event = events.next() => match event {
+                Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                    let effects = app.handle_key(key);
+                    apply(effects, &mut app, &api, &history, token, &outgoing).await
+                }
+                Some(Ok(_)) => true,
+                _ => false,
+            },
+            message = incoming.recv() => match message {
+                Some(message) => {
+                    let effects = app.handle_server(message);
+                    apply(effects, &mut app, &api, &history, token, &outgoing).await
+                }
+                None => {
+                    app.status = "Сервер закрыл соединение".to_string();
+                    false
+                }
+            },
+        };
+        if !running {
+            break;
+        }
+        ui::draw(&app)?;
+    }
+
+    let _ = outgoing.send(Message::Leave);
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> CliResult<()> {
+    let http_url = std::env::var("MESSENGER_HTTP").unwrap_or_else(|_| "http://127.0.0.1:8081".to_string());
+    let ws_url = std::env::var("MESSENGER_WS").unwrap_or_else(|_| "ws://127.0.0.1:8080".to_string());
+
+    let api = ApiClient::new(&http_url);
+    let username = prompt("Имя пользователя: ")?;
+    let password = prompt("Пароль: ")?;
+    let tokens = api.login(&username, &password).await?;
+    let chats = api.my_chats(&tokens.token).await?;
+
+    // сначала показываем локальную историю, сервер ее дополнит
+    let history = History::open(History::default_dir(&username))?;
+    let mut app = App::new(&username, chats, history.private_peers());
+    let kinds: Vec<ConversationKind> = app.conversations.iter().map(|c| c.kind.clone()).collect();
+    for kind in kinds {
+        let lines = history.load(&kind.history_key());
+        app.load_cached(&kind, lines);
+    }
+
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let result = run(app, api, history, &tokens.token, &ws_url).await;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
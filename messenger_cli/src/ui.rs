@@ -0,0 +1,100 @@
+use std::io::{self, Write};
+use crossterm::cursor::MoveTo;
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::queue;
+use crate::app::App;
+use crate::history::ChatLine;
+
+// ширина списка чатов слева
+const SIDEBAR_WIDTH: u16 = 24;
+
+// обрезка строки по ширине в символах
+fn fit(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+// перенос строки переписки по ширине окна
+pub fn wrap(line: &ChatLine, width: usize) -> Vec<String> {
+    let text = format!("{}: {}", line.sender, line.content);
+    if width == 0 {
+        return Vec::new();
+    }
+    let chars: Vec<char> = text.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    chars.chunks(width).map(|chunk| chunk.iter().collect()).collect()
+}
+
+// Видимые строки открытого разговора с учетом прокрутки.
+// Прокрутка считается в строках переписки, а не в строках экрана
+pub fn visible_lines(app: &App, width: usize, height: usize) -> Vec<String> {
+    let conversation = app.active();
+    let end = conversation.lines.len().saturating_sub(conversation.scroll);
+    let mut rows: Vec<String> = conversation.lines[..end]
+        .iter()
+        .flat_map(|line| wrap(line, width))
+        .collect();
+    if rows.len() > height {
+        rows.drain(..rows.len() - height);
+    }
+    rows
+}
+
+pub fn draw(app: &App) -> io::Result<()> {
+    let mut out = io::stdout();
+    let (width, height) = terminal::size()?;
+    queue!(out, Clear(ClearType::All))?;
+
+    // список чатов
+    for (index, conversation) in app.conversations.iter().enumerate() {
+        if index as u16 >= height.saturating_sub(2) {
+            break;
+        }
+        let mut title = conversation.title.clone();
+        if conversation.unread > 0 {
+            title = format!("{} ({})", title, conversation.unread);
+        }
+        queue!(out, MoveTo(0, index as u16))?;
+        if index == app.selected {
+            queue!(out, SetAttribute(Attribute::Reverse))?;
+        }
+        queue!(out, Print(fit(&title, SIDEBAR_WIDTH as usize - 1)), SetAttribute(Attribute::Reset))?;
+    }
+    for row in 0..height.saturating_sub(2) {
+        queue!(out, MoveTo(SIDEBAR_WIDTH - 1, row), Print("│"))?;
+    }
+
+    // переписка открытого чата
+    let chat_width = width.saturating_sub(SIDEBAR_WIDTH) as usize;
+    let chat_height = height.saturating_sub(2) as usize;
+    for (row, text) in visible_lines(app, chat_width, chat_height).iter().enumerate() {
+        queue!(out, MoveTo(SIDEBAR_WIDTH, row as u16), Print(text))?;
+    }
+
+    // строка состояния и поле ввода
+    let status = if app.active().scroll > 0 {
+        format!("[прокрутка: {}] {}", app.active().scroll, app.status)
+    } else {
+        app.status.clone()
+    };
+    queue!(
+        out,
+        MoveTo(0, height.saturating_sub(2)),
+        SetAttribute(Attribute::Dim),
+        Print(fit(&status, width as usize)),
+        SetAttribute(Attribute::Reset),
+    )?;
+    let prompt = format!("{}> {}", app.active().title, app.input);
+    // при длинном вводе виден его конец
+    let skip = prompt.chars().count().saturating_sub(width.saturating_sub(1) as usize);
+    let prompt: String = prompt.chars().skip(skip).collect();
+    queue!(
+        out,
+        MoveTo(0, height.saturating_sub(1)),
+        Print(&prompt),
+        MoveTo(prompt.chars().count() as u16, height.saturating_sub(1)),
+    )?;
+    out.flush()
+}
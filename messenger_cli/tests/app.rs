@@ -0,0 +1,156 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use messenger_cli::app::{App, ConversationKind, Effect};
+use messenger_cli::history::{ChatLine, History};
+use messenger_protocol::structs::UserChat;
+use messenger_protocol::Message;
+
+fn chat(chat_id: Option<i32>, name: &str, unread: i64) -> UserChat {
+    UserChat { chat_id, name: name.to_string(), creator: None, last_read: None, unread }
+}
+
+fn app() -> App {
+    App::new("alice", vec![chat(None, "Общий чат", 0), chat(Some(7), "rust", 2)], vec!["bob".to_string()])
+}
+
+fn type_text(app: &mut App, text: &str) -> Vec<Effect> {
+    for c in text.chars() {
+        app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+    app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+}
+
+fn line(id: i32, sender: &str) -> ChatLine {
+    ChatLine { id: Some(id), sender: sender.to_string(), content: format!("сообщение {}", id) }
+}
+
+#[test]
+fn conversations_come_from_server_and_local_history() {
+    let app = app();
+    let kinds: Vec<ConversationKind> = app.conversations.iter().map(|c| c.kind.clone()).collect();
+    assert_eq!(kinds, vec![
+        ConversationKind::General,
+        ConversationKind::Group(7),
+        ConversationKind::Private("bob".to_string()),
+    ]);
+    assert_eq!(app.conversations[1].unread, 2);
+}
+
+#[test]
+fn input_is_sent_to_active_conversation() {
+    let mut app = app();
+    let effects = type_text(&mut app, "привет");
+    assert!(matches!(&effects[..], [Effect::Send(Message::SendMessage { content, .. })] if content == "привет"));
+    assert!(app.input.is_empty());
+
+    app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+    let effects = type_text(&mut app, "/topic Rust");
+    assert!(matches!(
+        &effects[..],
+        [Effect::Send(Message::SendMessageToGroupChat { chat_id: 7, content, .. })] if content == "/topic Rust"
+    ));
+}
+
+#[test]
+fn opening_group_without_history_loads_it() {
+    let mut app = app();
+    let effects = app.select(1);
+    assert!(matches!(&effects[..], [Effect::LoadGroupHistory(7)]));
+    // повторно история не запрашивается
+    app.select(0);
+    assert!(app.select(1).is_empty());
+}
+
+#[test]
+fn opening_chat_with_unread_marks_it_read() {
+    let mut app = app();
+    app.load_cached(&ConversationKind::Group(7), vec![line(1, "bob"), line(5, "bob")]);
+    let effects = app.select(1);
+    assert!(matches!(
+        &effects[..],
+        [Effect::Send(Message::MarkRead { chat_id: Some(7), message_id: 5 })]
+    ));
+    assert_eq!(app.conversations[1].unread, 0);
+}
+
+#[test]
+fn incoming_messages_are_routed_and_persisted() {
+    let mut app = app();
+    let effects = app.handle_server(Message::ReceiveGroupChatMessage {
+        id: Some(10),
+        chat_id: 7,
+        sender: "bob".to_string(),
+        content: "hi".to_string(),
+        reply_to: None,
+        reactions: Vec::new(),
+    });
+    assert!(matches!(&effects[..], [Effect::Persist { key, .. }] if key == "chat-7"));
+    assert_eq!(app.conversations[1].lines.len(), 1);
+
+    // собственное личное сообщение с другого устройства попадает в переписку с получателем
+    app.handle_server(Message::ReceivePrivateMessage {
+        sender: "alice".to_string(),
+        recipient: "carol".to_string(),
+        content: "[Приватно] hi".to_string(),
+    });
+    let carol = app.conversations.iter().find(|c| c.kind == ConversationKind::Private("carol".to_string()));
+    assert_eq!(carol.unwrap().lines.len(), 1);
+}
+
+#[test]
+fn message_in_active_chat_is_read_immediately() {
+    let mut app = app();
+    let effects = app.handle_server(Message::ReceiveMessage {
+        id: Some(3),
+        sender: "bob".to_string(),
+        content: "hi".to_string(),
+        reply_to: None,
+        reactions: Vec::new(),
+    });
+    assert!(matches!(
+        &effects[..],
+        [Effect::Persist { .. }, Effect::Send(Message::MarkRead { chat_id: None, message_id: 3 })]
+    ));
+}
+
+#[test]
+fn local_commands_open_conversations() {
+    let mut app = app();
+    type_text(&mut app, ":pm dave");
+    assert_eq!(app.active().kind, ConversationKind::Private("dave".to_string()));
+
+    let effects = type_text(&mut app, ":chat 12");
+    assert_eq!(app.active().kind, ConversationKind::Group(12));
+    assert!(matches!(&effects[..], [Effect::LoadGroupHistory(12)]));
+
+    assert!(matches!(&type_text(&mut app, ":quit")[..], [Effect::Quit]));
+}
+
+#[test]
+fn scrollback_is_bounded() {
+    let mut app = app();
+    app.load_cached(&ConversationKind::General, (1..=30).map(|id| line(id, "bob")).collect());
+    app.handle_key(KeyEvent::new(KeyCode::PageUp, KeyModifiers::NONE));
+    assert_eq!(app.active().scroll, 10);
+    for _ in 0..5 {
+        app.scroll_up(10);
+    }
+    assert_eq!(app.active().scroll, 29);
+    app.scroll_down(100);
+    assert_eq!(app.active().scroll, 0);
+}
+
+#[test]
+fn history_round_trip() {
+    let dir = std::env::temp_dir().join(format!("messenger_cli_history_{}", std::process::id()));
+    let history = History::open(dir.clone()).unwrap();
+    history.append("dm-bob", &line(1, "bob")).unwrap();
+    history.append("dm-bob", &line(2, "alice")).unwrap();
+    history.replace("chat-7", &[line(3, "bob")]).unwrap();
+
+    assert_eq!(history.load("dm-bob"), vec![line(1, "bob"), line(2, "alice")]);
+    assert_eq!(history.load("chat-7"), vec![line(3, "bob")]);
+    assert!(history.load("general").is_empty());
+    assert_eq!(history.private_peers(), vec!["bob".to_string()]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,14 @@
+// Схема генерируется из крейта messenger_protocol:
+// cd messenger_protocol && cargo run --bin protocol-schema
+import schema from './schema.json';
+
+export const PROTOCOL_VERSION = schema['x-protocol-version'];
+
+// типы кадров WebSocket, известные серверу
+export const FRAME_TYPES = schema.$defs.Message.oneOf.map((variant) => variant.properties.type.const);
+
+export function isKnownFrame(message) {
+  return FRAME_TYPES.includes(message.type);
+}
+
+export default schema;
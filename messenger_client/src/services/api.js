@@ -16,10 +16,15 @@ export default {
   getGroupChats() {
     return instance.get(`/chats`);
   },
-  createGroupChat(name, creator) {
-    return instance.post(`/chats`, { name, creator });
+  // чаты пользователя со счетчиками непрочитанных
+  getMyChats(token) {
+    return instance.get(`/users/me/chats`, { headers: { Authorization: `Bearer ${token}` } });
   },
-  deleteGroupChat(chatId) {
-    return instance.delete(`/chats`, { chatId });
+  // создатель и удаляющий определяются сервером по токену
+  createGroupChat(name, token) {
+    return instance.post(`/chats`, { name }, { headers: { Authorization: `Bearer ${token}` } });
+  },
+  deleteGroupChat(chatId, token) {
+    return instance.delete(`/chats`, { data: { chat_id: chatId }, headers: { Authorization: `Bearer ${token}` } });
   },
 }
@@ -1,3 +1,5 @@
+import { isKnownFrame } from '../protocol';
+
 const SOCKET_URL = 'ws://127.0.0.1:8080';
 
 class WebSocketManager {
@@ -6,13 +8,13 @@ class WebSocketManager {
     this.store = store;
   }
 
-  connect(username) {
+  connect(username, token) {
     this.socket = new WebSocket(SOCKET_URL);
 
     this.socket.onopen = () => {
       console.log('Соединение установлено');
       // Отправляем сообщение о присоединении
-      this.socket.send(JSON.stringify({ type: 'Join', username }));
+      this.socket.send(JSON.stringify({ type: 'Join', username, token }));
     };
 
     this.socket.onmessage = (event) => {
@@ -37,6 +39,10 @@ class WebSocketManager {
   }
 
   sendMessage(message) {
+    if (!isKnownFrame(message)) {
+      console.error('Кадр не описан в протоколе:', message.type);
+      return;
+    }
     if (this.socket && this.socket.readyState === WebSocket.OPEN) {
       this.socket.send(JSON.stringify(message));
     } else {
@@ -49,11 +55,21 @@ class WebSocketManager {
       case 'ReceiveMessage':
         this.store.dispatch('addMessage', { chatId: 'general', message });
         break;
+      case 'UnreadCounts':
+        this.store.commit('setUnreadCounts', message.counts);
+        break;
+      case 'UnreadCountUpdated':
+        this.store.commit('setUnreadCount', { chatId: message.chat_id, unread: message.unread });
+        break;
       case 'ErrorMessage':
         console.error('Ошибка: ', message.error);
         break;
       default:
-        console.warn("Неизвестный тип сообщения:", message.type);
+        if (isKnownFrame(message)) {
+          console.warn("Необработанный тип сообщения:", message.type);
+        } else {
+          console.warn("Неизвестный тип сообщения:", message.type);
+        }
     }
   }
 }
@@ -5,12 +5,15 @@ export default createStore({
   state: {
     user: "",
     token: null,
+    refreshToken: null,
     chatName: null,
     chats: [],
     messanges: {
       general: [],
     },
     isConnected: false,
+    // непрочитанные по чатам, общий чат - под ключом general
+    unread: {},
   },
   mutations: {
     setUser(state, user) {
@@ -19,6 +22,9 @@ export default createStore({
     setToken(state, token) {
       state.token = token;
     },
+    setRefreshToken(state, refreshToken) {
+      state.refreshToken = refreshToken;
+    },
     setChats(state,chats) {
       state.chats = chats;
     },
@@ -31,6 +37,15 @@ export default createStore({
     setConnectionStatus(state, status) {
       state.isConnected = status;
     },
+    setUnreadCounts(state, counts) {
+      state.unread = {};
+      counts.forEach(({ chat_id, unread }) => {
+        state.unread[chat_id ?? 'general'] = unread;
+      });
+    },
+    setUnreadCount(state, { chatId, unread }) {
+      state.unread[chatId ?? 'general'] = unread;
+    },
   },
   actions: {
     async register(_, { username, password }) {
@@ -40,14 +55,19 @@ export default createStore({
       const response = await api.login(username, password);
       commit('setUser', username);
       commit('setToken', response.data.token);
+      commit('setRefreshToken', response.data.refresh_token);
     },
     async fetchChats({ commit }) {
       const response = await api.getGroupChats();
       commit('setChats', response.data);
+      if (this.state.token) {
+        const myChats = await api.getMyChats(this.state.token);
+        commit('setUnreadCounts', myChats.data);
+      }
     },
     async createGroupChat({ commit }, name) {
-      if (this.state.user) {
-        const response = await api.createGroupChat(name, this.state.user);
+      if (this.state.token) {
+        const response = await api.createGroupChat(name, this.state.token);
         commit('setChats', [ ...this.state.chats, response.data ]);
       } else {
         alert("Вы не авторизованы");
@@ -60,7 +80,7 @@ export default createStore({
       await api.removeMemberFroumGroupChat(chatId, username);
     },
     async deleteGroupChat({ commit }, chatId) {
-      await api.deleteGroupChat(chatId);
+      await api.deleteGroupChat(chatId, this.state.token);
       commit('setChats', this.state.chats.filter((chat) => chat.id !== chatId));
     },
     async addMessage({ commit }, message) {
@@ -0,0 +1,18 @@
+use std::fs;
+use messenger_protocol::schema::{json_schema, CLIENT_SCHEMA_PATH};
+
+// Генерация JSON Schema протокола для веб-клиента.
+// Без аргументов схема записывается в messenger_client, с аргументом "-" выводится в консоль
+fn main() -> std::io::Result<()> {
+    let schema = serde_json::to_string_pretty(&json_schema())? + "\n";
+    match std::env::args().nth(1).as_deref() {
+        Some("-") => print!("{}", schema),
+        Some(path) => fs::write(path, schema)?,
+        None => {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/").to_string() + CLIENT_SCHEMA_PATH;
+            fs::write(&path, schema)?;
+            println!("Схема записана в {}", path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,11 @@
+// типы протокола мессенджера, общие для сервера и клиентов
+pub mod message;
+pub mod requests;
+pub mod schema;
+pub mod structs;
+pub mod validation;
+pub mod version;
+
+pub use message::Message;
+pub use validation::ValidationError;
+pub use version::PROTOCOL_VERSION;
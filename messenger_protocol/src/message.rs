@@ -0,0 +1,100 @@
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use crate::structs::{ChatMessage, ReactionCount, ReadMarker, SendOptions, UnreadCount};
+use crate::validation::{validate_content, validate_emoji, ValidationError};
+
+// Сообщения протокола WebSocket между клиентом и сервером
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
+#[serde(tag = "type")] // Указываем поле `type` для различения типов сообщений
+pub enum Message {
+    Join { username: String, token: String }, // Клиент присоединяется к чату с токеном доступа из /login
+    SendMessage { content: String, #[serde(flatten)] options: SendOptions }, // Клиент отправляет сообщение
+    ReceiveMessage {
+        #[serde(default)] id: Option<i32>,
+        sender: String,
+        content: String,
+        #[serde(default)] reply_to: Option<i32>,
+        #[serde(default)] reactions: Vec<ReactionCount>,
+    }, // Сообщение для клиента,
+    SendPrivateMessage { recipient: String, content: String }, // Отправка приватных сообщений
+    ReceivePrivateMessage { sender: String, #[serde(default)] recipient: String, content: String }, // Получение приватных сообщений
+    Leave, // выход пользователя
+    ErrorMessage { error: String }, // Ответ об ошибке
+    AddMemberToGroupChat { chat_id: i32, username: String }, // добавить пользователя в групповой чат
+    SendMessageToGroupChat { chat_id: i32, content: String, #[serde(flatten)] options: SendOptions }, // отправить сообщение в группвой чат
+    ReceiveGroupChatMessage {
+        #[serde(default)] id: Option<i32>,
+        chat_id: i32,
+        sender: String,
+        content: String,
+        #[serde(default)] reply_to: Option<i32>,
+        #[serde(default)] reactions: Vec<ReactionCount>,
+    }, // получение соощения из группового чата
+    RemoveMemberFromGroupChat { chat_id: i32, username: String }, // удалить пользователя из чата, удаляет вошедший клиент
+    React { message_id: i32, emoji: String }, // поставить реакцию на сообщение
+    Unreact { message_id: i32, emoji: String }, // снять реакцию с сообщения
+    ReactionsUpdated { message_id: i32, reactions: Vec<ReactionCount> }, // новые количества реакций на сообщение
+    LoadThread { root_id: i32 }, // запрос ветки ответов на сообщение
+    Thread { root_id: i32, messages: Vec<ChatMessage> }, // ветка ответов на сообщение
+    MuteUser { username: String, #[serde(default)] chat_id: Option<i32> }, // запретить писать (без chat_id - везде)
+    UnmuteUser { username: String, #[serde(default)] chat_id: Option<i32> }, // снять запрет писать
+    BanUser { username: String, #[serde(default)] chat_id: Option<i32> }, // заблокировать пользователя
+    UnbanUser { username: String, #[serde(default)] chat_id: Option<i32> }, // разблокировать пользователя
+    MarkRead { #[serde(default)] chat_id: Option<i32>, message_id: i32 }, // отметить сообщения чата прочитанными
+    ReadMarkerUpdated { chat_id: Option<i32>, message_id: i32 }, // отметка о прочтении с другого устройства
+    ReadMarkers { markers: Vec<ReadMarker> }, // все отметки о прочтении пользователя после входа
+    MessageDeleted { message_id: i32, chat_id: Option<i32> }, // сообщение удалено (истек срок жизни)
+    TopicChanged { chat_id: i32, topic: Option<String>, changed_by: String }, // новая тема группового чата
+    UnreadCounts { counts: Vec<UnreadCount> }, // непрочитанные во всех чатах пользователя после входа
+    UnreadCountUpdated { chat_id: Option<i32>, unread: i64 }, // изменился счетчик непрочитанных в чате
+}
+
+impl Message {
+    // служебное сообщение от сервера
+    pub fn server(content: String) -> Self {
+        Message::ReceiveMessage {
+            id: None,
+            sender: "Server".to_string(),
+            content,
+            reply_to: None,
+            reactions: Vec::new(),
+        }
+    }
+
+    // Проверка кадра от клиента до обработки. Имена пользователей здесь не проверяются:
+    // правила регистрации могли измениться после создания старых учетных записей
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match self {
+            Message::SendMessage { content, options }
+            | Message::SendMessageToGroupChat { content, options, .. } => {
+                validate_content(content)?;
+                options.validate()
+            }
+            Message::SendPrivateMessage { content, .. } => validate_content(content),
+            Message::React { emoji, .. } | Message::Unreact { emoji, .. } => validate_emoji(emoji),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<ChatMessage> for Message {
+    fn from(message: ChatMessage) -> Self {
+        match message.chat_id {
+            Some(chat_id) => Message::ReceiveGroupChatMessage {
+                id: Some(message.id),
+                chat_id,
+                sender: message.sender,
+                content: message.content,
+                reply_to: message.reply_to,
+                reactions: message.reactions,
+            },
+            None => Message::ReceiveMessage {
+                id: Some(message.id),
+                sender: message.sender,
+                content: message.content,
+                reply_to: message.reply_to,
+                reactions: message.reactions,
+            },
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use crate::structs::SendOptions;
+
+// тела запросов REST API
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RegisterUser {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct LoginUser {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RefreshSession {
+    pub refresh_token: String,
+}
+
+// создатель и удаляющий чат определяются по токену сессии
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CreateChat {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DeleteChat {
+    pub chat_id: i32,
+}
+
+// параметры выгрузки чата (строка запроса); кто выгружает, сервер узнает по токену сессии
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ExportChat {
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CreateBot {
+    pub name: String,
+}
+
+// сообщение бота в групповой чат
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PostMessage {
+    pub content: String,
+    #[serde(flatten)]
+    pub options: SendOptions,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct CreateWebhook {
+    pub url: String,
+}
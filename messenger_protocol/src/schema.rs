@@ -0,0 +1,50 @@
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Value};
+use crate::message::Message;
+use crate::requests::{
+    CreateBot, CreateChat, CreateWebhook, DeleteChat, ExportChat, LoginUser, PostMessage,
+    RefreshSession, RegisterUser,
+};
+use crate::structs::{
+    AuditEvent, Chat, ChatExport, ChatMessage, DirectChatExport, Session, SessionTokens, UserChat,
+    Webhook, WebhookPayload,
+};
+use crate::version::{ProtocolInfo, PROTOCOL_VERSION};
+
+// путь к схеме в веб-клиенте относительно крейта протокола
+pub const CLIENT_SCHEMA_PATH: &str = "../messenger_client/src/protocol/schema.json";
+
+// JSON Schema всех кадров и тел запросов. Веб-клиент хранит ее у себя,
+// тест крейта следит, чтобы сохраненная схема совпадала с типами
+pub fn json_schema() -> Value {
+    let mut generator = SchemaSettings::draft2020_12().into_generator();
+    let message = generator.subschema_for::<Message>();
+    generator.subschema_for::<RegisterUser>();
+    generator.subschema_for::<LoginUser>();
+    generator.subschema_for::<RefreshSession>();
+    generator.subschema_for::<CreateChat>();
+    generator.subschema_for::<DeleteChat>();
+    generator.subschema_for::<ExportChat>();
+    generator.subschema_for::<CreateBot>();
+    generator.subschema_for::<PostMessage>();
+    generator.subschema_for::<CreateWebhook>();
+    generator.subschema_for::<Chat>();
+    generator.subschema_for::<ChatExport>();
+    generator.subschema_for::<ChatMessage>();
+    generator.subschema_for::<DirectChatExport>();
+    generator.subschema_for::<SessionTokens>();
+    generator.subschema_for::<Session>();
+    generator.subschema_for::<UserChat>();
+    generator.subschema_for::<Webhook>();
+    generator.subschema_for::<WebhookPayload>();
+    generator.subschema_for::<AuditEvent>();
+    generator.subschema_for::<ProtocolInfo>();
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Messenger protocol",
+        "x-protocol-version": PROTOCOL_VERSION,
+        "$ref": message.as_value()["$ref"],
+        "$defs": generator.take_definitions(true),
+    })
+}
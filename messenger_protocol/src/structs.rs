@@ -0,0 +1,157 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+use crate::validation::ValidationError;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Chat {
+    pub id: i32,
+    pub name: String,
+    pub creator: String,
+}
+
+// количество одинаковых реакций на сообщение
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReactionCount {
+    pub emoji: String,
+    pub count: i64,
+}
+
+// дополнительные параметры отправки сообщения
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SendOptions {
+    #[serde(default)]
+    pub reply_to: Option<i32>, // ответ на сообщение
+    #[serde(default)]
+    pub send_at: Option<DateTime<Utc>>, // отложенная отправка
+    #[serde(default)]
+    pub expires_after: Option<i64>, // удалить через указанное количество секунд после отправки
+}
+
+impl SendOptions {
+    // через сколько секунд отправить сообщение, None - отправить сразу
+    pub fn delay_secs(&self) -> Option<f64> {
+        self.send_at
+            .map(|send_at| (send_at - Utc::now()).num_milliseconds() as f64 / 1000.0)
+            .filter(|delay| *delay > 0.0)
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.expires_after.is_some_and(|secs| secs <= 0) {
+            return Err(ValidationError::InvalidExpiry);
+        }
+        Ok(())
+    }
+}
+
+// сохраненное сообщение общего (chat_id = None) или группового чата
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChatMessage {
+    pub id: i32,
+    pub chat_id: Option<i32>,
+    pub sender: String,
+    pub content: String,
+    pub reply_to: Option<i32>,
+    #[serde(default)]
+    pub reactions: Vec<ReactionCount>,
+}
+
+// сообщение в выгрузке чата
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportedMessage {
+    pub id: i32,
+    pub sender: String,
+    pub content: String,
+    pub reply_to: Option<i32>,
+    pub sent_at: NaiveDateTime,
+}
+
+// полная выгрузка группового чата
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ChatExport {
+    pub chat: Chat,
+    pub members: Vec<String>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+// выгрузка личной переписки двух пользователей, reply_to у сообщений всегда пустой
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DirectChatExport {
+    pub participants: Vec<String>,
+    pub messages: Vec<ExportedMessage>,
+}
+
+// токены, выдаваемые при входе и обновлении сессии
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct SessionTokens {
+    pub session_id: i32,
+    pub token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
+}
+
+// последнее прочитанное сообщение в чате
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReadMarker {
+    pub chat_id: Option<i32>,
+    pub message_id: i32,
+}
+
+// количество непрочитанных сообщений в чате (chat_id = None - общий чат)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UnreadCount {
+    pub chat_id: Option<i32>,
+    pub unread: i64,
+}
+
+// чат пользователя для списка чатов с отметкой о прочтении и счетчиком
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UserChat {
+    pub chat_id: Option<i32>,
+    pub name: String,
+    pub creator: Option<String>,
+    pub last_read: Option<i32>,
+    pub unread: i64,
+}
+
+// активная сессия пользователя
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Session {
+    pub id: i32,
+    pub username: String,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: NaiveDateTime,
+}
+
+// зарегистрированный вебхук группового чата (секрет выдается только при создании)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Webhook {
+    pub id: i32,
+    pub chat_id: i32,
+    pub url: String,
+    pub created_by: String,
+    pub created_at: NaiveDateTime,
+}
+
+// тело запроса вебхука о новом сообщении
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WebhookPayload {
+    pub event: String,
+    pub chat_id: i32,
+    pub message: ChatMessage,
+}
+
+// запись журнала аудита
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub event_type: String,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub chat_id: Option<i32>,
+    pub ip: Option<String>,
+    pub details: Option<String>,
+    pub created_at: NaiveDateTime,
+}
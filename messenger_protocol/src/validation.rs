@@ -0,0 +1,67 @@
+use thiserror::Error;
+
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 64;
+pub const MIN_PASSWORD_LENGTH: usize = 6;
+pub const MAX_CHAT_NAME_LENGTH: usize = 100;
+pub const MAX_EMOJI_LENGTH: usize = 16;
+
+// ошибки проверки данных, одинаковые для сервера и клиентов
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    #[error("Имя пользователя должно содержать от {MIN_USERNAME_LENGTH} до {MAX_USERNAME_LENGTH} символов")]
+    UsernameLength,
+    #[error("Имя пользователя может содержать только буквы, цифры, _, - и .")]
+    UsernameCharacters,
+    #[error("Пароль должен содержать не меньше {MIN_PASSWORD_LENGTH} символов")]
+    PasswordTooShort,
+    #[error("Название чата должно содержать от 1 до {MAX_CHAT_NAME_LENGTH} символов")]
+    ChatNameLength,
+    #[error("Пустое сообщение")]
+    EmptyContent,
+    #[error("Реакция должна содержать от 1 до {MAX_EMOJI_LENGTH} символов")]
+    EmojiLength,
+    #[error("Время жизни сообщения должно быть положительным")]
+    InvalidExpiry,
+}
+
+pub fn validate_username(username: &str) -> Result<(), ValidationError> {
+    let length = username.chars().count();
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&length) {
+        return Err(ValidationError::UsernameLength);
+    }
+    if !username.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        return Err(ValidationError::UsernameCharacters);
+    }
+    Ok(())
+}
+
+pub fn validate_password(password: &str) -> Result<(), ValidationError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(ValidationError::PasswordTooShort);
+    }
+    Ok(())
+}
+
+pub fn validate_chat_name(name: &str) -> Result<(), ValidationError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_CHAT_NAME_LENGTH {
+        return Err(ValidationError::ChatNameLength);
+    }
+    Ok(())
+}
+
+// длину сообщения ограничивают фильтры сервера, здесь только пустые сообщения
+pub fn validate_content(content: &str) -> Result<(), ValidationError> {
+    if content.trim().is_empty() {
+        return Err(ValidationError::EmptyContent);
+    }
+    Ok(())
+}
+
+pub fn validate_emoji(emoji: &str) -> Result<(), ValidationError> {
+    if emoji.is_empty() || emoji.chars().count() > MAX_EMOJI_LENGTH {
+        return Err(ValidationError::EmojiLength);
+    }
+    Ok(())
+}
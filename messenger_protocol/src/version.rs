@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+
+// Версия протокола. Увеличивается при несовместимых изменениях кадров
+// и тел запросов; добавление необязательных полей версию не меняет
+pub const PROTOCOL_VERSION: u32 = 1;
+
+// подпротоколы WebSocket, которыми клиент выбирает кодировку
+pub const JSON_PROTOCOL: &str = "messenger.json";
+pub const MSGPACK_PROTOCOL: &str = "messenger.msgpack";
+
+// описание протокола сервера для клиентов (GET /protocol)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProtocolInfo {
+    pub version: u32,
+    pub subprotocols: Vec<String>,
+}
+
+impl ProtocolInfo {
+    pub fn current() -> Self {
+        ProtocolInfo {
+            version: PROTOCOL_VERSION,
+            subprotocols: vec![JSON_PROTOCOL.to_string(), MSGPACK_PROTOCOL.to_string()],
+        }
+    }
+}
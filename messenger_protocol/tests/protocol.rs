@@ -0,0 +1,72 @@
+use messenger_protocol::schema::{json_schema, CLIENT_SCHEMA_PATH};
+use messenger_protocol::structs::SendOptions;
+use messenger_protocol::validation::{validate_chat_name, validate_password, validate_username};
+use messenger_protocol::version::ProtocolInfo;
+use messenger_protocol::{Message, ValidationError, PROTOCOL_VERSION};
+use serde_json::json;
+
+#[test]
+fn frames_keep_wire_format() {
+    let message: Message = serde_json::from_value(json!({
+        "type": "SendMessageToGroupChat",
+        "chat_id": 3,
+        "content": "привет",
+        "reply_to": 10,
+    }))
+    .unwrap();
+    assert!(matches!(
+        &message,
+        Message::SendMessageToGroupChat { chat_id: 3, options: SendOptions { reply_to: Some(10), .. }, .. }
+    ));
+
+    let json = serde_json::to_value(Message::MarkRead { chat_id: None, message_id: 5 }).unwrap();
+    assert_eq!(json, json!({ "type": "MarkRead", "chat_id": null, "message_id": 5 }));
+}
+
+#[test]
+fn client_frames_are_validated() {
+    let empty = Message::SendMessage { content: "   ".to_string(), options: SendOptions::default() };
+    assert_eq!(empty.validate(), Err(ValidationError::EmptyContent));
+
+    let expired = Message::SendMessage {
+        content: "hi".to_string(),
+        options: SendOptions { expires_after: Some(0), ..SendOptions::default() },
+    };
+    assert_eq!(expired.validate(), Err(ValidationError::InvalidExpiry));
+
+    let reaction = Message::React { message_id: 1, emoji: String::new() };
+    assert_eq!(reaction.validate(), Err(ValidationError::EmojiLength));
+
+    assert!(Message::React { message_id: 1, emoji: "👍".to_string() }.validate().is_ok());
+    assert!(Message::Leave.validate().is_ok());
+}
+
+#[test]
+fn registration_rules() {
+    assert!(validate_username("alice_01").is_ok());
+    assert!(validate_username("Мария").is_ok());
+    assert_eq!(validate_username("al"), Err(ValidationError::UsernameLength));
+    assert_eq!(validate_username("alice bob"), Err(ValidationError::UsernameCharacters));
+    assert_eq!(validate_password("12345"), Err(ValidationError::PasswordTooShort));
+    assert!(validate_password("secret").is_ok());
+    assert_eq!(validate_chat_name(" "), Err(ValidationError::ChatNameLength));
+}
+
+#[test]
+fn protocol_info_lists_subprotocols() {
+    let info = ProtocolInfo::current();
+    assert_eq!(info.version, PROTOCOL_VERSION);
+    assert_eq!(info.subprotocols, vec!["messenger.json", "messenger.msgpack"]);
+}
+
+// схема веб-клиента должна совпадать с типами крейта
+#[test]
+fn client_schema_is_up_to_date() {
+    let path = format!("{}/{}", env!("CARGO_MANIFEST_DIR"), CLIENT_SCHEMA_PATH);
+    let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(
+        saved == json_schema(),
+        "Схема {} устарела, обновите ее: cargo run --bin protocol-schema",
+        path
+    );
+}
@@ -0,0 +1,25 @@
+-- исходные таблицы, которые раньше создавались вручную; на существующей базе ничего не меняет
+CREATE TABLE IF NOT EXISTS users (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL UNIQUE,
+    password_hash VARCHAR NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS messages (
+    id SERIAL PRIMARY KEY,
+    sender VARCHAR NOT NULL,
+    content TEXT NOT NULL,
+    timestamp TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS group_chats (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    creator VARCHAR NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS group_chat_members (
+    chat_id INTEGER NOT NULL REFERENCES group_chats(id) ON DELETE CASCADE,
+    username VARCHAR NOT NULL,
+    PRIMARY KEY (chat_id, username)
+);
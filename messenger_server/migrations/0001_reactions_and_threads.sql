@@ -0,0 +1,16 @@
+-- сообщения групповых чатов и ответы на сообщения
+ALTER TABLE messages
+    ADD COLUMN IF NOT EXISTS chat_id INTEGER REFERENCES group_chats(id) ON DELETE CASCADE,
+    ADD COLUMN IF NOT EXISTS reply_to INTEGER REFERENCES messages(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS messages_chat_id_idx ON messages (chat_id);
+CREATE INDEX IF NOT EXISTS messages_reply_to_idx ON messages (reply_to);
+
+-- реакции на сообщения, один пользователь ставит каждую реакцию один раз
+CREATE TABLE IF NOT EXISTS message_reactions (
+    message_id INTEGER NOT NULL REFERENCES messages(id) ON DELETE CASCADE,
+    username VARCHAR NOT NULL,
+    emoji VARCHAR(32) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (message_id, username, emoji)
+);
@@ -0,0 +1,15 @@
+-- глобальные администраторы сервера
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_admin BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- блокировки пользователей: chat_id = NULL означает блокировку во всех чатах
+CREATE TABLE IF NOT EXISTS user_restrictions (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL,
+    chat_id INTEGER REFERENCES group_chats(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL CHECK (kind IN ('mute', 'ban')),
+    created_by VARCHAR NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS user_restrictions_unique_idx
+    ON user_restrictions (username, COALESCE(chat_id, 0), kind);
@@ -0,0 +1,17 @@
+-- сессии пользователей: короткоживущий токен доступа и токен обновления.
+-- В базе хранятся только sha256-хэши токенов
+CREATE TABLE IF NOT EXISTS sessions (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR NOT NULL REFERENCES users(username) ON DELETE CASCADE,
+    access_token_hash VARCHAR(64) NOT NULL UNIQUE,
+    refresh_token_hash VARCHAR(64) NOT NULL UNIQUE,
+    user_agent VARCHAR,
+    ip VARCHAR,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    access_expires_at TIMESTAMP NOT NULL,
+    refresh_expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS sessions_username_idx ON sessions (username);
@@ -0,0 +1,9 @@
+-- последнее прочитанное сообщение пользователя в каждом чате (chat_id = NULL - общий чат)
+CREATE TABLE IF NOT EXISTS read_markers (
+    username VARCHAR NOT NULL,
+    chat_id INTEGER REFERENCES group_chats(id) ON DELETE CASCADE,
+    message_id INTEGER NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS read_markers_unique_idx ON read_markers (username, COALESCE(chat_id, 0));
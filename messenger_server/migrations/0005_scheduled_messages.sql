@@ -0,0 +1,8 @@
+-- отложенная отправка и самоудаляющиеся сообщения
+ALTER TABLE messages
+    ADD COLUMN IF NOT EXISTS send_at TIMESTAMP,
+    ADD COLUMN IF NOT EXISTS delivered BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN IF NOT EXISTS expires_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS messages_scheduled_idx ON messages (send_at) WHERE NOT delivered;
+CREATE INDEX IF NOT EXISTS messages_expires_at_idx ON messages (expires_at) WHERE expires_at IS NOT NULL;
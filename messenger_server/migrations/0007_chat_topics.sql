@@ -0,0 +1,2 @@
+-- тема группового чата, задается командой /topic
+ALTER TABLE group_chats ADD COLUMN IF NOT EXISTS topic VARCHAR;
@@ -0,0 +1,2 @@
+-- подсчет непрочитанных: сообщения чата после отметки о прочтении
+CREATE INDEX IF NOT EXISTS messages_chat_id_id_idx ON messages (COALESCE(chat_id, 0), id) WHERE delivered;
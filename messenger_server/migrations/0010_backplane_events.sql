@@ -0,0 +1,9 @@
+-- события шины postgres: NOTIFY передает только ID события, потому что payload NOTIFY
+-- ограничен 8000 байт, а событие группового чата несет список участников и сообщение
+CREATE TABLE IF NOT EXISTS backplane_events (
+    id BIGSERIAL PRIMARY KEY,
+    event TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS backplane_events_created_at_idx ON backplane_events (created_at);
@@ -0,0 +1,13 @@
+-- личные сообщения, чтобы переписку двух пользователей можно было выгрузить;
+-- хранятся отдельно от messages, где chat_id IS NULL означает общий чат
+CREATE TABLE IF NOT EXISTS private_messages (
+    id SERIAL PRIMARY KEY,
+    sender VARCHAR NOT NULL,
+    recipient VARCHAR NOT NULL,
+    content TEXT NOT NULL,
+    timestamp TIMESTAMP NOT NULL DEFAULT NOW()
+);
+
+-- переписка ищется по паре пользователей независимо от направления
+CREATE INDEX IF NOT EXISTS private_messages_pair_idx
+    ON private_messages (LEAST(sender, recipient), GREATEST(sender, recipient), id);
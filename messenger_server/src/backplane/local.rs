@@ -0,0 +1,39 @@
+use futures_util::future::BoxFuture;
+use tokio::sync::broadcast;
+use tracing::debug;
+use crate::types::AppResult;
+use super::{Backplane, Event, CHANNEL_CAPACITY};
+
+// шина в рамках одного процесса
+pub struct LocalBackplane {
+    tx: broadcast::Sender<Event>,
+}
+
+impl LocalBackplane {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        LocalBackplane { tx }
+    }
+}
+
+impl Default for LocalBackplane {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backplane for LocalBackplane {
+    fn publish(&self, event: Event) -> BoxFuture<'_, AppResult<()>> {
+        Box::pin(async move {
+            // ошибка означает только отсутствие подписчиков
+            if self.tx.send(event).is_err() {
+                debug!("Нет подключенных клиентов для доставки события");
+            }
+            Ok(())
+        })
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.tx.subscribe()
+    }
+}
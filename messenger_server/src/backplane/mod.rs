@@ -0,0 +1,69 @@
+use std::env;
+use std::sync::Arc;
+use futures_util::future::BoxFuture;
+use serde::{Serialize, Deserialize};
+use tokio::sync::broadcast;
+use tracing::info;
+use crate::types::{AppResult, DbPool};
+
+pub mod local;
+pub mod postgres;
+
+// размер буфера канала, из которого подключения получают события
+pub const CHANNEL_CAPACITY: usize = 32;
+
+// Событие, которое рассылается между всеми экземплярами сервера.
+// payload - уже сериализованное сообщение для клиента
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "target")]
+pub enum Event {
+    Global { payload: String }, // всем подключенным клиентам
+    Private { recipient: String, payload: String }, // одному пользователю
+    Group { chat_id: i32, members: Vec<String>, payload: String }, // участникам группового чата
+    SessionsRevoked { session_ids: Vec<i32> }, // отключить подключения отозванных сессий
+}
+
+impl Event {
+    // нужно ли доставлять событие клиенту с данным именем
+    pub fn is_for(&self, username: Option<&str>) -> bool {
+        match self {
+            Event::Global { .. } => true,
+            Event::Private { recipient, .. } => username == Some(recipient.as_str()),
+            Event::Group { members, .. } => {
+                username.is_some_and(|name| members.iter().any(|member| member == name))
+            }
+            Event::SessionsRevoked { .. } => false,
+        }
+    }
+
+    // сообщение для клиента, у служебных событий его нет
+    pub fn payload(&self) -> Option<&str> {
+        match self {
+            Event::Global { payload }
+            | Event::Private { payload, .. }
+            | Event::Group { payload, .. } => Some(payload),
+            Event::SessionsRevoked { .. } => None,
+        }
+    }
+}
+
+// Шина для доставки сообщений клиентам, подключенным к любому экземпляру сервера.
+// Каждый экземпляр получает все опубликованные события (в том числе свои)
+// и сам решает, каким из своих клиентов их отдать
+pub trait Backplane: Send + Sync {
+    fn publish(&self, event: Event) -> BoxFuture<'_, AppResult<()>>;
+    fn subscribe(&self) -> broadcast::Receiver<Event>;
+}
+
+pub type SharedBackplane = Arc<dyn Backplane>;
+
+// выбор реализации по переменной окружения BACKPLANE (local | postgres)
+pub async fn from_env(pool: &DbPool) -> AppResult<SharedBackplane> {
+    let kind = env::var("BACKPLANE").unwrap_or_else(|_| "local".to_string());
+    info!("Используется шина сообщений: {}", kind);
+
+    match kind.as_str() {
+        "postgres" => Ok(Arc::new(postgres::PgBackplane::new(pool.clone()).await?)),
+        _ => Ok(Arc::new(local::LocalBackplane::new())),
+    }
+}
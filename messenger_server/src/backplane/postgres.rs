@@ -0,0 +1,125 @@
+use std::time::Duration;
+use futures_util::future::BoxFuture;
+use sqlx::postgres::PgListener;
+use tokio::sync::broadcast;
+use tracing::{debug, error, info};
+use crate::types::{AppResult, DbPool, ServerError};
+use super::{Backplane, Event, CHANNEL_CAPACITY};
+
+// канал postgres, через который обмениваются экземпляры сервера
+const NOTIFY_CHANNEL: &str = "messenger_events";
+
+// как часто удаляются старые события и сколько они хранятся
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+const EVENT_RETENTION_SECS: f64 = 600.0;
+
+// Шина через LISTEN/NOTIFY общей базы данных.
+// Размер payload в NOTIFY ограничен 8000 байт, поэтому событие записывается
+// в таблицу backplane_events, а в уведомлении передается только его ID
+pub struct PgBackplane {
+    pool: DbPool,
+    tx: broadcast::Sender<Event>,
+}
+
+impl PgBackplane {
+    pub async fn new(pool: DbPool) -> AppResult<Self> {
+        let mut listener = PgListener::connect_with(&pool)
+            .await
+            .map_err(|e| {
+                error!("Ошибка подключения слушателя postgres: {}", e);
+                ServerError::DatabaseError { context: "Ошибка подключения слушателя postgres".to_string(), source: e }
+            })?;
+
+        listener.listen(NOTIFY_CHANNEL)
+            .await
+            .map_err(|e| {
+                error!("Ошибка подписки на канал {}: {}", NOTIFY_CHANNEL, e);
+                ServerError::DatabaseError { context: "Ошибка подписки на канал postgres".to_string(), source: e }
+            })?;
+        info!("Подписка на канал postgres {} успешно создана", NOTIFY_CHANNEL);
+
+        let (tx, _) = broadcast::channel(CHANNEL_CAPACITY);
+        let listener_tx = tx.clone();
+        let listener_pool = pool.clone();
+
+        // пересылаем уведомления из базы локальным подписчикам
+        tokio::spawn(async move {
+            loop {
+                // при обрыве соединения PgListener переподключается сам
+                match listener.recv().await {
+                    Ok(notification) => {
+                        match load_event(&listener_pool, notification.payload()).await {
+                            Ok(Some(event)) => {
+                                if listener_tx.send(event).is_err() {
+                                    debug!("Нет подключенных клиентов для доставки события");
+                                }
+                            }
+                            Ok(None) => error!("Событие шины {} не найдено", notification.payload()),
+                            Err(e) => error!("Ошибка чтения события шины: {}", e),
+                        }
+                    }
+                    Err(e) => error!("Ошибка получения уведомления postgres: {}", e),
+                }
+            }
+        });
+
+        // доставленные события больше не нужны
+        let cleanup_pool = pool.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let result = sqlx::query("DELETE FROM backplane_events WHERE created_at < NOW() - make_interval(secs => $1)")
+                    .bind(EVENT_RETENTION_SECS)
+                    .execute(&cleanup_pool)
+                    .await;
+                if let Err(e) = result {
+                    error!("Ошибка удаления старых событий шины: {}", e);
+                }
+            }
+        });
+
+        Ok(PgBackplane { pool, tx })
+    }
+}
+
+// событие по ID из уведомления
+async fn load_event(pool: &DbPool, payload: &str) -> Result<Option<Event>, String> {
+    let id: i64 = payload.parse().map_err(|_| format!("неверный ID события '{}'", payload))?;
+    let event: Option<String> = sqlx::query_scalar("SELECT event FROM backplane_events WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    event
+        .map(|event| serde_json::from_str(&event).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+impl Backplane for PgBackplane {
+    fn publish(&self, event: Event) -> BoxFuture<'_, AppResult<()>> {
+        Box::pin(async move {
+            let payload = serde_json::to_string(&event)
+                .map_err(|e| ServerError::BackplaneError(e.to_string()))?;
+
+            // запись и уведомление в одной команде: слушатели получат ID после фиксации строки
+            sqlx::query(
+                "WITH inserted AS (INSERT INTO backplane_events (event) VALUES ($2) RETURNING id)
+                SELECT pg_notify($1, id::text) FROM inserted",
+            )
+            .bind(NOTIFY_CHANNEL)
+            .bind(payload)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("Ошибка публикации события в postgres: {}", e);
+                ServerError::DatabaseError { context: "Ошибка публикации события в postgres".to_string(), source: e }
+            })?;
+            Ok(())
+        })
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.tx.subscribe()
+    }
+}
@@ -0,0 +1,180 @@
+// Служебная утилита для обслуживания сервера сообщений.
+// Использует ту же базу данных (DATABASE_URL из .env), что и сервер
+use std::{env, fs, process};
+use serde::Deserialize;
+use messenger_server::backplane::{self, Event};
+use messenger_server::db::{db_main, group_chat, messages, private_messages, user};
+use messenger_server::services::{auth_service, chat_service, session_service};
+use messenger_server::structs::{ChatExport, DirectChatExport};
+use messenger_server::types::{AppResult, DbPool, ServerError};
+
+const USAGE: &str = "Использование: messenger-admin <команда> [аргументы]
+
+Команды:
+  users list                              список пользователей
+  users create <имя> <пароль>             создать пользователя
+  users delete <имя>                      удалить пользователя
+  users reset-password <имя> <пароль>     сменить пароль
+  chats list                              список групповых чатов
+  chats members <chat_id>                 участники чата
+  chats transfer <chat_id> <имя>          передать чат другому участнику
+  chats export <chat_id> [файл]           выгрузить историю чата в JSON
+  chats import <файл>                     загрузить групповой чат или личную переписку из JSON-выгрузки
+  messages purge <дней>                   удалить сообщения, в том числе личные, старше N дней";
+
+// файл выгрузки: групповой чат (chats export или GET /chats/{id}/export)
+// или личная переписка (GET /chats/direct/{username}/export)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImportFile {
+    Group(ChatExport),
+    Direct(DirectChatExport),
+}
+
+#[tokio::main]
+async fn main() {
+    // в консоль выводим только предупреждения, чтобы не мешать выводу команд
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .init();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.is_empty() {
+        eprintln!("{}", USAGE);
+        process::exit(1);
+    }
+
+    let pool = match db_main::create_db_pool().await {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("Не удалось подключиться к базе данных: {}", e);
+            process::exit(1);
+        }
+    };
+
+    if let Err(e) = run(&pool, &args).await {
+        eprintln!("Ошибка: {}", e);
+        process::exit(1);
+    }
+}
+
+async fn run(pool: &DbPool, args: &[String]) -> AppResult<()> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["users", "list"] => {
+            for username in user::list_all(pool).await? {
+                println!("{}", username);
+            }
+        }
+        ["users", "create", username, password] => {
+            auth_service::register_user(pool, username, password).await?;
+            println!("Пользователь {} создан", username);
+        }
+        ["users", "delete", username] => {
+            user::delete(pool, username).await?;
+            println!("Пользователь {} удален", username);
+        }
+        ["users", "reset-password", username, password] => {
+            user::update_password(pool, username, password).await?;
+            // старые токены, возможно украденные, после смены пароля не действуют
+            let session_ids = session_service::revoke_all_sessions(pool, username).await?;
+            if !session_ids.is_empty() {
+                // запущенные серверы закрывают подключения отозванных сессий, если шина общая
+                backplane::from_env(pool).await?.publish(Event::SessionsRevoked { session_ids }).await?;
+            }
+            println!("Пароль пользователя {} изменен, сессии отозваны", username);
+        }
+        ["chats", "list"] => {
+            for chat in chat_service::get_all_group_chats(pool).await? {
+                println!("{}\t{}\t{}", chat.id, chat.name, chat.creator);
+            }
+        }
+        ["chats", "members", chat_id] => {
+            for member in group_chat::get_members(pool, parse_number(chat_id)?).await? {
+                println!("{}", member);
+            }
+        }
+        ["chats", "transfer", chat_id, new_owner] => {
+            let chat_id = parse_number(chat_id)?;
+            group_chat::transfer_ownership(pool, chat_id, new_owner).await?;
+            println!("Чат ID: {} передан пользователю {}", chat_id, new_owner);
+        }
+        ["chats", "export", chat_id, rest @ ..] if rest.len() <= 1 => {
+            let export = chat_service::export_group_chat(pool, parse_number(chat_id)?).await?;
+            let json = serde_json::to_string_pretty(&export)
+                .map_err(|e| ServerError::InvalidArgument(e.to_string()))?;
+            match rest.first() {
+                Some(path) => {
+                    fs::write(path, json).map_err(|e| {
+                        ServerError::InvalidArgument(format!("Не удалось записать файл {}: {}", path, e))
+                    })?;
+                    println!("Выгружено {} сообщений в {}", export.messages.len(), path);
+                }
+                None => println!("{}", json),
+            }
+        }
+        ["chats", "import", path] => {
+            let json = fs::read_to_string(path).map_err(|e| {
+                ServerError::InvalidArgument(format!("Не удалось прочитать файл {}: {}", path, e))
+            })?;
+            let export: ImportFile = serde_json::from_str(&json)
+                .map_err(|e| ServerError::InvalidArgument(format!("Неверный формат выгрузки: {}", e)))?;
+            match export {
+                ImportFile::Group(export) => {
+                    let chat_id = chat_service::import_group_chat(pool, &export).await?;
+                    println!("Чат {} загружен с ID: {} ({} сообщений)", export.chat.name, chat_id, export.messages.len());
+                }
+                ImportFile::Direct(export) => {
+                    let count = chat_service::import_direct_chat(pool, &export).await?;
+                    println!("Личная переписка {} загружена ({} сообщений)", export.participants.join(" и "), count);
+                }
+            }
+        }
+        ["messages", "purge", days] => {
+            let days = parse_days(days)?;
+            let removed = messages::purge_older_than(pool, days).await?
+                + private_messages::purge_older_than(pool, days).await?;
+            println!("Удалено сообщений: {}", removed);
+        }
+        _ => return Err(ServerError::InvalidArgument(USAGE.to_string())),
+    }
+    Ok(())
+}
+
+// при нуле дней и меньше под удаление попали бы все сообщения
+fn parse_days(value: &str) -> AppResult<i32> {
+    let days = parse_number(value)?;
+    if days < 1 {
+        return Err(ServerError::InvalidArgument(format!("Количество дней должно быть больше нуля\n{}", USAGE)));
+    }
+    Ok(days)
+}
+
+fn parse_number(value: &str) -> AppResult<i32> {
+    value
+        .parse()
+        .map_err(|_| ServerError::InvalidArgument(format!("'{}' не является числом", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn purge_needs_positive_days() {
+        assert_eq!(parse_days("30").unwrap(), 30);
+        for days in ["0", "-7", "неделя"] {
+            assert!(matches!(parse_days(days), Err(ServerError::InvalidArgument(_))), "{}", days);
+        }
+    }
+
+    #[test]
+    fn import_reads_both_export_kinds() {
+        let group = r#"{"chat":{"id":1,"name":"team","creator":"alice"},"members":["alice"],"messages":[]}"#;
+        assert!(matches!(serde_json::from_str(group), Ok(ImportFile::Group(_))));
+        let direct = r#"{"participants":["alice","bob"],"messages":[]}"#;
+        assert!(matches!(serde_json::from_str(direct), Ok(ImportFile::Direct(_))));
+        assert!(serde_json::from_str::<ImportFile>("[]").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{de::DeserializeOwned, Serialize};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+// подпротоколы WebSocket, которыми клиент выбирает кодировку
+pub use messenger_protocol::version::{JSON_PROTOCOL, MSGPACK_PROTOCOL};
+
+// Кодировка сообщений для клиента. JSON передается текстовыми кадрами,
+// MessagePack - бинарными, структура сообщений одинаковая
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Encoding {
+    #[default]
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    // выбор кодировки по заголовку Sec-WebSocket-Protocol (список через запятую)
+    pub fn negotiate(protocols: &str) -> Option<Self> {
+        protocols
+            .split(',')
+            .map(str::trim)
+            .find_map(|protocol| match protocol {
+                MSGPACK_PROTOCOL => Some(Encoding::MessagePack),
+                JSON_PROTOCOL => Some(Encoding::Json),
+                _ => None,
+            })
+    }
+
+    pub fn protocol(&self) -> &'static str {
+        match self {
+            Encoding::Json => JSON_PROTOCOL,
+            Encoding::MessagePack => MSGPACK_PROTOCOL,
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, value: &T) -> Result<WsMessage, String> {
+        match self {
+            Encoding::Json => serde_json::to_string(value)
+                .map(|json| WsMessage::Text(json.into()))
+                .map_err(|e| e.to_string()),
+            // именованные поля, чтобы сообщения остались картами, как в JSON
+            Encoding::MessagePack => rmp_serde::to_vec_named(value)
+                .map(|bytes| WsMessage::Binary(bytes.into()))
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    // перекодирование уже сериализованного в JSON сообщения (например, из шины)
+    pub fn encode_json(&self, json: &str) -> Result<WsMessage, String> {
+        match self {
+            Encoding::Json => Ok(WsMessage::Text(json.to_string().into())),
+            Encoding::MessagePack => {
+                let value: serde_json::Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+                self.encode(&value)
+            }
+        }
+    }
+}
+
+// Разбор кадра от клиента: текстовые кадры - JSON, бинарные - MessagePack.
+// None для служебных кадров (ping, close и т.д.)
+pub fn decode<T: DeserializeOwned>(frame: &WsMessage) -> Option<Result<T, String>> {
+    match frame {
+        WsMessage::Text(text) => Some(serde_json::from_str(text).map_err(|e| e.to_string())),
+        WsMessage::Binary(bytes) => Some(rmp_serde::from_slice(bytes).map_err(|e| e.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::Message;
+    use serde_json::{json, Value};
+
+    fn sample() -> Message {
+        Message::SendMessage { content: "привет".to_string(), options: Default::default() }
+    }
+
+    #[test]
+    fn negotiate_picks_first_known_protocol() {
+        assert_eq!(Encoding::negotiate(MSGPACK_PROTOCOL), Some(Encoding::MessagePack));
+        assert_eq!(Encoding::negotiate(&format!("chat.v2, {}, {}", JSON_PROTOCOL, MSGPACK_PROTOCOL)), Some(Encoding::Json));
+        assert_eq!(Encoding::negotiate(&format!("{} ,{}", MSGPACK_PROTOCOL, JSON_PROTOCOL)), Some(Encoding::MessagePack));
+        assert_eq!(Encoding::MessagePack.protocol(), MSGPACK_PROTOCOL);
+    }
+
+    #[test]
+    fn unknown_protocol_falls_back_to_json() {
+        assert_eq!(Encoding::negotiate(""), None);
+        assert_eq!(Encoding::negotiate("chat.v2, binary"), None);
+        assert_eq!(Encoding::negotiate("chat.v2").unwrap_or_default(), Encoding::Json);
+    }
+
+    #[test]
+    fn json_uses_text_frames() {
+        let frame = Encoding::Json.encode(&sample()).unwrap();
+        assert!(matches!(frame, WsMessage::Text(_)));
+        let decoded: Message = decode(&frame).unwrap().unwrap();
+        assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(sample()).unwrap());
+    }
+
+    #[test]
+    fn message_pack_round_trips_through_binary_frames() {
+        let frame = Encoding::MessagePack.encode(&sample()).unwrap();
+        assert!(matches!(frame, WsMessage::Binary(_)));
+        let decoded: Message = decode(&frame).unwrap().unwrap();
+        assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(sample()).unwrap());
+
+        // сообщение из шины приходит в JSON и перекодируется для клиента
+        let json = json!({ "type": "ErrorMessage", "error": "нет доступа" }).to_string();
+        let frame = Encoding::MessagePack.encode_json(&json).unwrap();
+        let decoded: Value = decode(&frame).unwrap().unwrap();
+        assert_eq!(decoded.to_string(), json);
+    }
+
+    #[test]
+    fn control_frames_and_garbage() {
+        assert!(decode::<Message>(&WsMessage::Ping(Vec::new().into())).is_none());
+        assert!(decode::<Message>(&WsMessage::Binary(vec![0xc1].into())).unwrap().is_err());
+        assert!(decode::<Message>(&WsMessage::Text("{".into())).unwrap().is_err());
+    }
+}
@@ -0,0 +1,198 @@
+use futures_util::future::BoxFuture;
+use crate::backplane::Event;
+use crate::commands::{usage_error, CommandContext, CommandHandler};
+use crate::db::{group_chat, user};
+use crate::moderation;
+use crate::presence;
+use crate::protocol::Message;
+use crate::services::{audit_service, chat_service, message_service};
+use crate::structs::{AuditEventKind, AuditRecord, RestrictionKind, SendOptions};
+use crate::types::{AppResult, ServerError};
+
+// единственный аргумент - имя пользователя
+fn single_username<'a>(handler: &dyn CommandHandler, args: &'a str) -> AppResult<&'a str> {
+    let mut parts = args.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some(username), None) => Ok(username),
+        _ => Err(usage_error(handler)),
+    }
+}
+
+// /me - сообщение от третьего лица
+pub struct MeCommand;
+
+impl CommandHandler for MeCommand {
+    fn name(&self) -> &'static str { "me" }
+    fn usage(&self) -> &'static str { "/me <действие>" }
+    fn description(&self) -> &'static str { "сообщение от третьего лица" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            if args.is_empty() {
+                return Err(usage_error(self));
+            }
+            let content = format!("* {} {}", context.sender, args);
+            let options = SendOptions::default();
+            match context.chat_id {
+                Some(chat_id) => message_service::send_message_to_group_chat(
+                    context.backplane, context.db_pool, context.filters, chat_id, context.sender, &content, &options,
+                ).await?,
+                None => message_service::send_message_to_general_chat(
+                    context.backplane, context.db_pool, context.filters, context.sender, &content, &options,
+                ).await?,
+            };
+            Ok(None)
+        })
+    }
+}
+
+// /topic - просмотр или смена темы группового чата
+pub struct TopicCommand;
+
+impl CommandHandler for TopicCommand {
+    fn name(&self) -> &'static str { "topic" }
+    fn usage(&self) -> &'static str { "/topic [тема | -]" }
+    fn description(&self) -> &'static str { "показать тему чата, задать новую или сбросить (только создатель)" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            let chat_id = context.require_group_chat()?;
+            if args.is_empty() {
+                let topic = group_chat::get_topic(context.db_pool, chat_id).await?;
+                return Ok(Some(Message::server(match topic {
+                    Some(topic) => format!("Тема чата: {}", topic),
+                    None => "Тема чата не задана".to_string(),
+                })));
+            }
+
+            if !group_chat::check_if_creator(context.db_pool, chat_id, context.sender).await? {
+                return Err(ServerError::PermissionDenied);
+            }
+            let topic = match args {
+                "-" => None,
+                topic => Some(moderation::moderate(context.db_pool, context.filters, context.sender, Some(chat_id), topic).await?),
+            };
+            group_chat::set_topic(context.db_pool, chat_id, topic.as_deref()).await?;
+
+            let update = Message::TopicChanged { chat_id, topic, changed_by: context.sender.to_string() };
+            message_service::publish_for_chat(context.backplane, context.db_pool, Some(chat_id), &update).await?;
+            Ok(None)
+        })
+    }
+}
+
+// /invite - добавление участника в групповой чат
+pub struct InviteCommand;
+
+impl CommandHandler for InviteCommand {
+    fn name(&self) -> &'static str { "invite" }
+    fn usage(&self) -> &'static str { "/invite <пользователь>" }
+    fn description(&self) -> &'static str { "добавить пользователя в чат" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            let chat_id = context.require_group_chat()?;
+            let username = single_username(self, args)?;
+            if user::find_user_by_username(context.db_pool, username).await?.is_none() {
+                return Err(ServerError::MemberNotFound);
+            }
+            if group_chat::is_member(context.db_pool, chat_id, username).await? {
+                return Err(ServerError::InvalidArgument(format!("{} уже состоит в чате", username)));
+            }
+            chat_service::add_member(context.db_pool, chat_id, username).await?;
+            let record = AuditRecord::new(AuditEventKind::MemberAdded)
+                .actor(context.sender)
+                .target(username)
+                .chat(chat_id)
+                .ip(context.ip);
+            audit_service::record(context.db_pool, record).await;
+
+            let notification = Message::server(format!("{} добавил в чат {}", context.sender, username));
+            message_service::publish_for_chat(context.backplane, context.db_pool, Some(chat_id), &notification).await?;
+            Ok(None)
+        })
+    }
+}
+
+// /kick - удаление участника из группового чата (только создатель)
+pub struct KickCommand;
+
+impl CommandHandler for KickCommand {
+    fn name(&self) -> &'static str { "kick" }
+    fn usage(&self) -> &'static str { "/kick <пользователь>" }
+    fn description(&self) -> &'static str { "удалить пользователя из чата (только создатель)" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            let chat_id = context.require_group_chat()?;
+            let username = single_username(self, args)?;
+            group_chat::remove_member(context.db_pool, chat_id, username, context.sender).await?;
+            let record = AuditRecord::new(AuditEventKind::MemberRemoved)
+                .actor(context.sender)
+                .target(username)
+                .chat(chat_id)
+                .ip(context.ip);
+            audit_service::record(context.db_pool, record).await;
+
+            let notification = Message::server(format!("{} удалил из чата {}", context.sender, username));
+            message_service::publish_for_chat(context.backplane, context.db_pool, Some(chat_id), &notification).await?;
+            // удаленный участник уже не получит рассылку по чату
+            let removed = Message::server(format!("Вы удалены из группового чата ID: {}", chat_id));
+            context.backplane.publish(Event::Private {
+                recipient: username.to_string(),
+                payload: serde_json::to_string(&removed).unwrap(),
+            }).await?;
+            Ok(None)
+        })
+    }
+}
+
+// /mute - запрет писать в текущем чате; в общем чате - глобально (только администратор)
+pub struct MuteCommand;
+
+impl CommandHandler for MuteCommand {
+    fn name(&self) -> &'static str { "mute" }
+    fn usage(&self) -> &'static str { "/mute <пользователь>" }
+    fn description(&self) -> &'static str { "запретить пользователю писать в чат (создатель или администратор)" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            let username = single_username(self, args)?;
+            moderation::update_restriction(
+                context.db_pool, context.sender, username, context.chat_id, RestrictionKind::Mute, true,
+            ).await?;
+            Ok(Some(Message::server(format!("Пользователь {} не может писать в чат", username))))
+        })
+    }
+}
+
+// /who - кто в чате
+pub struct WhoCommand;
+
+impl CommandHandler for WhoCommand {
+    fn name(&self) -> &'static str { "who" }
+    fn usage(&self) -> &'static str { "/who" }
+    fn description(&self) -> &'static str { "участники чата и кто из них в сети" }
+
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>> {
+        Box::pin(async move {
+            if !args.is_empty() {
+                return Err(usage_error(self));
+            }
+            // в сети - подключенные к этому экземпляру сервера
+            let online = presence::online_users(context.clients).await;
+            let text = match context.chat_id {
+                Some(chat_id) => {
+                    let members: Vec<String> = group_chat::get_members(context.db_pool, chat_id)
+                        .await?
+                        .into_iter()
+                        .map(|member| if online.contains(&member) { format!("{} (в сети)", member) } else { member })
+                        .collect();
+                    format!("Участники чата: {}", members.join(", "))
+                }
+                None => format!("В сети: {}", online.join(", ")),
+            };
+            Ok(Some(Message::server(text)))
+        })
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use futures_util::future::BoxFuture;
+use tracing::info;
+use crate::backplane::SharedBackplane;
+use crate::moderation::FilterChain;
+use crate::presence::Clients;
+use crate::protocol::Message;
+use crate::services::{audit_service, message_service};
+use crate::types::{AppResult, DbPool, ServerError};
+
+pub mod builtin;
+
+// префикс команды; сообщение, начинающееся с двух префиксов, отправляется как обычный текст
+pub const COMMAND_PREFIX: char = '/';
+
+// окружение, в котором выполняется команда; chat_id = None для общего чата
+pub struct CommandContext<'a> {
+    pub db_pool: &'a DbPool,
+    pub backplane: &'a SharedBackplane,
+    pub filters: &'a FilterChain,
+    pub clients: &'a Clients,
+    pub sender: &'a str,
+    pub chat_id: Option<i32>,
+    pub ip: Option<&'a str>, // адрес клиента для журнала аудита
+}
+
+impl CommandContext<'_> {
+    // команды управления участниками и темой имеют смысл только в групповом чате
+    pub fn require_group_chat(&self) -> AppResult<i32> {
+        self.chat_id.ok_or_else(|| {
+            ServerError::InvalidArgument("Команда доступна только в групповом чате".to_string())
+        })
+    }
+}
+
+// Обработчик команды. Some(ответ) отправляется только автору команды,
+// остальным участникам обработчик рассылает события сам
+pub trait CommandHandler: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn usage(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn execute<'a>(&'a self, context: &'a CommandContext<'a>, args: &'a str) -> BoxFuture<'a, AppResult<Option<Message>>>;
+}
+
+// ошибка неверного использования команды
+pub fn usage_error(handler: &dyn CommandHandler) -> ServerError {
+    ServerError::InvalidArgument(format!("Использование: {}", handler.usage()))
+}
+
+// разбор "/name аргументы"; None - если это не команда
+pub fn parse(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix(COMMAND_PREFIX)?;
+    if rest.starts_with(COMMAND_PREFIX) {
+        return None;
+    }
+    let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    Some((name, args.trim()))
+}
+
+// "//текст" отправляется как "/текст"
+pub fn unescape(content: &str) -> &str {
+    match content.strip_prefix(COMMAND_PREFIX) {
+        Some(rest) if rest.starts_with(COMMAND_PREFIX) => rest,
+        _ => content,
+    }
+}
+
+// реестр команд; /help строится по зарегистрированным обработчикам
+pub struct CommandRegistry {
+    handlers: BTreeMap<&'static str, Box<dyn CommandHandler>>,
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        CommandRegistry { handlers: BTreeMap::new() }
+    }
+
+    // реестр со всеми встроенными командами
+    pub fn with_builtin() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(builtin::MeCommand));
+        registry.register(Box::new(builtin::TopicCommand));
+        registry.register(Box::new(builtin::InviteCommand));
+        registry.register(Box::new(builtin::KickCommand));
+        registry.register(Box::new(builtin::MuteCommand));
+        registry.register(Box::new(builtin::WhoCommand));
+        registry
+    }
+
+    pub fn register(&mut self, handler: Box<dyn CommandHandler>) {
+        self.handlers.insert(handler.name(), handler);
+    }
+
+    fn help(&self) -> Message {
+        let mut lines = vec![format!("{}help - список команд", COMMAND_PREFIX)];
+        for handler in self.handlers.values() {
+            lines.push(format!("{} - {}", handler.usage(), handler.description()));
+        }
+        Message::server(lines.join("\n"))
+    }
+
+    // Выполнение команды из текста сообщения. None - сообщение не является командой
+    // и отправляется как обычно
+    pub async fn execute(&self, context: &CommandContext<'_>, content: &str) -> Option<AppResult<Option<Message>>> {
+        let (name, args) = parse(content)?;
+        info!("Команда /{} от {} (chat_id={:?})", name, context.sender, context.chat_id);
+
+        let result = self.dispatch(context, name, args).await;
+        if let Err(e) = &result {
+            let action = format!("{}{}", COMMAND_PREFIX, name);
+            audit_service::record_denial(context.db_pool, e, Some(context.sender), context.chat_id, context.ip, &action).await;
+        }
+        Some(result)
+    }
+
+    async fn dispatch(&self, context: &CommandContext<'_>, name: &str, args: &str) -> AppResult<Option<Message>> {
+        // команды группового чата доступны только его участникам
+        message_service::check_chat_access(context.db_pool, context.chat_id, context.sender).await?;
+
+        if name == "help" {
+            return Ok(Some(self.help()));
+        }
+        let Some(handler) = self.handlers.get(name) else {
+            return Err(ServerError::InvalidArgument(format!(
+                "Неизвестная команда {}{}, список команд: {}help",
+                COMMAND_PREFIX, name, COMMAND_PREFIX,
+            )));
+        };
+        handler.execute(context, args).await
+    }
+}
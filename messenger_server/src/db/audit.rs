@@ -0,0 +1,57 @@
+use tracing::error;
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{AuditEvent, AuditFilter, AuditRecord}};
+
+// добавление записи в журнал аудита
+pub async fn insert(pool: &DbPool, record: &AuditRecord) -> AppResult<()> {
+    sqlx::query!(
+        "INSERT INTO audit_events (event_type, actor, target, chat_id, ip, details) VALUES ($1, $2, $3, $4, $5, $6)",
+        record.kind.as_str(),
+        record.actor,
+        record.target,
+        record.chat_id,
+        record.ip,
+        record.details,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка записи события аудита в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка записи события аудита в БД".to_string(), source: e }
+    })?;
+
+    Ok(())
+}
+
+// выборка журнала от новых записей к старым
+pub async fn list(pool: &DbPool, filter: &AuditFilter, limit: i64) -> AppResult<Vec<AuditEvent>> {
+    let events = sqlx::query_as!(
+        AuditEvent,
+        "SELECT id, event_type, actor, target, chat_id, ip, details, created_at FROM audit_events
+        WHERE ($1::varchar IS NULL OR event_type = $1)
+            AND ($2::varchar IS NULL OR actor = $2)
+            AND ($3::varchar IS NULL OR target = $3)
+            AND ($4::int4 IS NULL OR chat_id = $4)
+            AND ($5::timestamp IS NULL OR created_at >= $5)
+            AND ($6::timestamp IS NULL OR created_at < $6)
+            AND ($7::int8 IS NULL OR id < $7)
+        ORDER BY id DESC
+        LIMIT $8",
+        filter.event_type.map(|kind| kind.as_str()),
+        filter.actor,
+        filter.target,
+        filter.chat_id,
+        filter.since,
+        filter.until,
+        filter.before_id,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка загрузки журнала аудита из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка загрузки журнала аудита из БД".to_string(), source: e }
+    })?;
+
+    Ok(events)
+}
@@ -0,0 +1,57 @@
+use tracing::{info, error};
+
+use crate::types::{AppResult, DbPool, ServerError};
+
+fn db_error(context: &str, e: sqlx::Error) -> ServerError {
+    error!("{}: {}", context, e);
+    ServerError::DatabaseError { context: context.to_string(), source: e }
+}
+
+// Создание бота: пользователь с пометкой is_bot и хэшем API-токена.
+// Пароль бота случайный, войти через /login он не может
+pub async fn create(
+    pool: &DbPool,
+    username: &str,
+    owner: &str,
+    password_hash: &str,
+    token_hash: &str,
+) -> AppResult<()> {
+    let mut tx = pool.begin().await.map_err(|e| db_error("Ошибка начала транзакции", e))?;
+
+    sqlx::query!(
+        "INSERT INTO users (username, password_hash, is_bot) VALUES ($1, $2, TRUE)",
+        username,
+        password_hash,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_error("Ошибка записи бота в БД", e))?;
+
+    sqlx::query!(
+        "INSERT INTO bots (username, owner, token_hash) VALUES ($1, $2, $3)",
+        username,
+        owner,
+        token_hash,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| db_error("Ошибка записи токена бота в БД", e))?;
+
+    tx.commit().await.map_err(|e| db_error("Ошибка завершения транзакции", e))?;
+
+    info!("Бот {} создан (владелец: {})", username, owner);
+    Ok(())
+}
+
+// имя бота по хэшу API-токена
+pub async fn find_by_token(pool: &DbPool, token_hash: &str) -> AppResult<Option<String>> {
+    let row = sqlx::query!(
+        "SELECT username FROM bots WHERE token_hash = $1",
+        token_hash,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_error("Ошибка поиска бота по токену", e))?;
+
+    Ok(row.map(|row| row.username))
+}
@@ -11,7 +11,7 @@ pub async fn create_db_pool() -> AppResult<PgPool> {
     let database_url = env::var("DATABASE_URL")
         .map_err(|e| {
             error!("DATABASE_URL is not sen in .env file: {}", e);
-            ServerError::VarError(e.into())
+            ServerError::VarError(e)
         })?;
     info!("Подключение к базе данных: {}", database_url);
 
@@ -24,5 +24,22 @@ pub async fn create_db_pool() -> AppResult<PgPool> {
         })?;
 
     info!("Пул соединений успешно создан");
+    run_migrations(&pool).await?;
     Ok(pool)
 }
+
+// Применяет еще не примененные миграции из migrations/ при запуске сервера и messenger-admin.
+// Все они идемпотентны, поэтому на базе, подготовленной вручную, ничего не ломают.
+// Макросы query! сверяют запросы с базой уже при сборке, поэтому перед первой сборкой
+// схему нужно создать отдельно: sqlx migrate run (из sqlx-cli)
+pub async fn run_migrations(pool: &PgPool) -> AppResult<()> {
+    sqlx::migrate!()
+        .run(pool)
+        .await
+        .map_err(|e| {
+            error!("Ошибка применения миграций: {}", e);
+            ServerError::DatabaseError { context: "Ошибка применения миграций".to_string(), source: e.into() }
+        })?;
+    info!("Миграции базы данных применены");
+    Ok(())
+}
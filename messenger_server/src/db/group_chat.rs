@@ -1,6 +1,7 @@
+use std::collections::HashMap;
 use tracing::{warn, info, error};
 
-use crate::{types::{AppResult, DbPool, ServerError}, structs::Chat};
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{Chat, ChatExport}};
 
 
 pub async fn get_all(pool: &DbPool) -> AppResult<Vec<Chat>> {
@@ -18,6 +19,23 @@ pub async fn get_all(pool: &DbPool) -> AppResult<Vec<Chat>> {
     Ok(chats)
 }
 
+// поиск группового чата по ID
+pub async fn get_by_id(pool: &DbPool, chat_id: i32) -> AppResult<Option<Chat>> {
+    let chat = sqlx::query_as!(
+        Chat,
+        "SELECT id, name, creator FROM group_chats WHERE id = $1",
+        chat_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения чата из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения чата из БД".to_string(), source: e }
+    })?;
+
+    Ok(chat)
+}
+
 // создание нового группового чата
 pub async fn create(
     pool: &DbPool,
@@ -107,7 +125,28 @@ pub async fn get_members(
     Ok(members)
 }
 
-async fn check_if_creator(
+// проверка, состоит ли пользователь в групповом чате
+pub async fn is_member(
+    pool: &DbPool,
+    chat_id: i32,
+    username: &str,
+) -> AppResult<bool> {
+    let row = sqlx::query!(
+        "SELECT EXISTS(SELECT 1 FROM group_chat_members WHERE chat_id = $1 AND username = $2) as \"exists!\"",
+        chat_id,
+        username,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка проверки участника группового чата (chat_id={}) в БД: {}", chat_id, e);
+        ServerError::DatabaseError { context: "Ошибка проверки участника группового чата".to_string(), source: e }
+    })?;
+
+    Ok(row.exists)
+}
+
+pub async fn check_if_creator(
     pool: &DbPool,
     chat_id: i32,
     username: &str
@@ -196,3 +235,138 @@ pub async fn delete(
     info!("Групповой чат (ID = {}) успешно удален", chat_id);
     Ok(())
 }
+
+// передача группового чата другому участнику
+pub async fn transfer_ownership(
+    pool: &DbPool,
+    chat_id: i32,
+    new_owner: &str,
+) -> AppResult<()> {
+    info!("Передача группового чата ID: {} пользователю {}", chat_id, new_owner);
+
+    if !is_member(pool, chat_id, new_owner).await? {
+        warn!("Пользователь {} не является участником чата ID: {}", new_owner, chat_id);
+        return Err(ServerError::MemberNotFound);
+    }
+
+    sqlx::query!(
+        "UPDATE group_chats SET creator = $2 WHERE id = $1",
+        chat_id,
+        new_owner,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка (БД) передачи группового чата: {}", e);
+        ServerError::DatabaseError { context: "Ошибка (БД) передачи группового чата".to_string(), source: e }
+    })?;
+
+    info!("Групповой чат ID: {} передан пользователю {}", chat_id, new_owner);
+    Ok(())
+}
+
+// Загрузка выгруженного чата одной транзакцией.
+// ID сообщений назначаются заново, ответы переназначаются на новые ID
+pub async fn import(pool: &DbPool, export: &ChatExport) -> AppResult<i32> {
+    info!("Загрузка группового чата {} ({} сообщений)", export.chat.name, export.messages.len());
+
+    let db_error = |context: &str, e: sqlx::Error| {
+        error!("{}: {}", context, e);
+        ServerError::DatabaseError { context: context.to_string(), source: e }
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| db_error("Ошибка начала транзакции", e))?;
+
+    let exists = sqlx::query!(
+        "SELECT EXISTS(SELECT 1 FROM group_chats WHERE name = $1) as \"exists!\"",
+        export.chat.name,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_error("Ошибка получения списка чатов из БД", e))?
+    .exists;
+    if exists {
+        warn!("Групповой чат с именем {} уже существуют", export.chat.name);
+        return Err(ServerError::GroupChatExist);
+    }
+
+    let chat_id = sqlx::query!(
+        "INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id",
+        export.chat.name,
+        export.chat.creator,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_error("Ошибка записи группового чата в БД", e))?
+    .id;
+
+    for member in &export.members {
+        sqlx::query!(
+            "INSERT INTO group_chat_members (chat_id, username) VALUES ($1, $2)",
+            chat_id,
+            member,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("Ошибка добавления пользователя в групповой чат", e))?;
+    }
+
+    // старый ID -> новый ID
+    let mut ids: HashMap<i32, i32> = HashMap::new();
+    for message in &export.messages {
+        let reply_to = message.reply_to.and_then(|old_id| ids.get(&old_id).copied());
+        let id = sqlx::query!(
+            "INSERT INTO messages (sender, content, chat_id, reply_to, timestamp) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            message.sender,
+            message.content,
+            chat_id,
+            reply_to,
+            message.sent_at,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| db_error("Ошибка записи сообщения в БД", e))?
+        .id;
+        ids.insert(message.id, id);
+    }
+
+    tx.commit().await.map_err(|e| db_error("Ошибка завершения транзакции", e))?;
+
+    info!("Групповой чат {} загружен (ID: {})", export.chat.name, chat_id);
+    Ok(chat_id)
+}
+
+// тема группового чата
+pub async fn get_topic(pool: &DbPool, chat_id: i32) -> AppResult<Option<String>> {
+    let row = sqlx::query!(
+            "SELECT topic FROM group_chats WHERE id = $1",
+            chat_id
+        )
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| {
+            error!("Ошибка получения темы чата из БД: {}", e);
+            ServerError::DatabaseError { context: "Ошибка получения темы чата из БД".to_string(), source: e }
+        })?
+        .ok_or(ServerError::ChatNotFound)?;
+
+    Ok(row.topic)
+}
+
+// установка темы группового чата, None - сброс
+pub async fn set_topic(pool: &DbPool, chat_id: i32, topic: Option<&str>) -> AppResult<()> {
+    sqlx::query!(
+            "UPDATE group_chats SET topic = $2 WHERE id = $1",
+            chat_id,
+            topic,
+        )
+        .execute(pool)
+        .await
+        .map_err(|e| {
+            error!("Ошибка сохранения темы чата в БД: {}", e);
+            ServerError::DatabaseError { context: "Ошибка сохранения темы чата в БД".to_string(), source: e }
+        })?;
+
+    info!("Тема чата ID: {} изменена", chat_id);
+    Ok(())
+}
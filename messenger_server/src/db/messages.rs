@@ -1,39 +1,72 @@
-use tracing::{warn, info, error};
+use tracing::{info, error};
 
-use crate::types::{AppResult, DbPool, ServerError};
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{ChatMessage, ExportedMessage, SendOptions}};
+use crate::db::reactions;
 
-// сохранение сообщения
-pub async fn save_message(pool: &DbPool, sender: &str, content: &str) -> AppResult<()> {
-    info!("Сохранение в базу данных сообщения: sender={}, content={}", sender, content);
+// Сохранение сообщения, возвращает ID сообщения.
+// Отложенное сообщение сохраняется недоставленным и не попадает в историю до времени отправки
+pub async fn save_message(
+    pool: &DbPool,
+    sender: &str,
+    content: &str,
+    chat_id: Option<i32>,
+    options: &SendOptions,
+) -> AppResult<i32> {
+    info!("Сохранение в базу данных сообщения: sender={}, content={}, chat_id={:?}", sender, content, chat_id);
 
-    let rows_affected = sqlx::query!(
-        "INSERT INTO messages (sender, content) VALUES ($1, $2)",
+    let row = sqlx::query!(
+        r#"INSERT INTO messages (sender, content, chat_id, reply_to, send_at, delivered, expires_at)
+        SELECT $1, $2, $3, $4, t.send_at, t.send_at IS NULL,
+            CASE WHEN $6::float8 IS NULL THEN NULL ELSE COALESCE(t.send_at, NOW()) + make_interval(secs => $6) END
+        FROM (SELECT CASE WHEN $5::float8 IS NULL THEN NULL ELSE NOW() + make_interval(secs => $5) END AS send_at) t
+        RETURNING id"#,
         sender,
         content,
+        chat_id,
+        options.reply_to,
+        options.delay_secs(),
+        options.expires_after.map(|secs| secs as f64),
     )
-    .execute(pool)
+    .fetch_one(pool)
     .await
     .map_err(|e| {
         error!("Ошибка записи сообщения в БД: {}", e);
         ServerError::DatabaseError { context: "Ошибка записи сообщения в БД".to_string(), source: e }
-    })?
-    .rows_affected();
+    })?;
 
-    if rows_affected == 0 {
-        warn!("Сообщение не было сохранено в базе данных");
-    } else {
-        info!("Сообщение было сохранено в базе данных");
-    }
+    info!("Сообщение было сохранено в базе данных (ID: {})", row.id);
+    Ok(row.id)
+}
 
-    Ok(())
+// поиск сообщения по ID
+pub async fn find_message(pool: &DbPool, message_id: i32) -> AppResult<Option<ChatMessage>> {
+    let row = sqlx::query!(
+        "SELECT id, chat_id, sender, content, reply_to FROM messages WHERE id = $1 AND delivered",
+        message_id,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка поиска сообщения в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка поиска сообщения в БД".to_string(), source: e }
+    })?;
+
+    Ok(row.map(|row| ChatMessage {
+        id: row.id,
+        chat_id: row.chat_id,
+        sender: row.sender,
+        content: row.content,
+        reply_to: row.reply_to,
+        reactions: Vec::new(),
+    }))
 }
 
-// Загрузка истории
-pub async fn load_history(pool: &DbPool, limit: i64) -> AppResult<Vec<(String, String)>> {
+// Загрузка истории общего чата
+pub async fn load_history(pool: &DbPool, limit: i64) -> AppResult<Vec<ChatMessage>> {
     info!("Загрузка истории сообщений (limit={})", limit);
 
     let rows = sqlx::query!(
-        "SELECT sender, content FROM messages ORDER BY timestamp DESC LIMIT $1",
+        "SELECT id, chat_id, sender, content, reply_to FROM messages WHERE chat_id IS NULL AND delivered ORDER BY timestamp DESC LIMIT $1",
         limit,
     )
     .fetch_all(pool)
@@ -46,15 +79,177 @@ pub async fn load_history(pool: &DbPool, limit: i64) -> AppResult<Vec<(String, S
         }
     })?;
 
-    let history: Vec<(String, String)> = rows
+    let history: Vec<ChatMessage> = rows
         .into_iter()
-        .map(|row| {
-            let sender = row.sender;
-            let content = row.content;
-            (sender, content)
+        .map(|row| ChatMessage {
+            id: row.id,
+            chat_id: row.chat_id,
+            sender: row.sender,
+            content: row.content,
+            reply_to: row.reply_to,
+            reactions: Vec::new(),
         })
         .collect();
+    let history = reactions::attach_counts(pool, history).await?;
 
     info!("Загружено {} сообщений из базы данных", history.len());
     Ok(history)
 }
+
+// Загрузка ветки ответов, начиная с корневого сообщения
+pub async fn load_thread(pool: &DbPool, root_id: i32) -> AppResult<Vec<ChatMessage>> {
+    info!("Загрузка ветки сообщений (root_id={})", root_id);
+
+    let rows = sqlx::query!(
+        r#"
+        WITH RECURSIVE thread AS (
+            SELECT id, chat_id, sender, content, reply_to, timestamp FROM messages WHERE id = $1 AND delivered
+            UNION ALL
+            SELECT m.id, m.chat_id, m.sender, m.content, m.reply_to, m.timestamp
+            FROM messages m JOIN thread t ON m.reply_to = t.id
+            WHERE m.delivered
+        )
+        SELECT id as "id!", chat_id, sender as "sender!", content as "content!", reply_to
+        FROM thread ORDER BY timestamp
+        "#,
+        root_id,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения ветки сообщений из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения ветки сообщений из БД".to_string(), source: e }
+    })?;
+
+    let thread: Vec<ChatMessage> = rows
+        .into_iter()
+        .map(|row| ChatMessage {
+            id: row.id,
+            chat_id: row.chat_id,
+            sender: row.sender,
+            content: row.content,
+            reply_to: row.reply_to,
+            reactions: Vec::new(),
+        })
+        .collect();
+    let thread = reactions::attach_counts(pool, thread).await?;
+
+    info!("Загружено {} сообщений ветки {}", thread.len(), root_id);
+    Ok(thread)
+}
+
+// Полная история группового чата для выгрузки. Порядок по ID тот же, что у
+// постраничной выгрузки, чтобы обе давали одинаковый результат
+pub async fn load_chat_export(pool: &DbPool, chat_id: i32) -> AppResult<Vec<ExportedMessage>> {
+    info!("Выгрузка истории группового чата ID: {}", chat_id);
+
+    let messages = sqlx::query_as!(
+        ExportedMessage,
+        r#"SELECT id, sender, content, reply_to, timestamp as "sent_at!" FROM messages
+        WHERE chat_id = $1 AND delivered ORDER BY id"#,
+        chat_id,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка выгрузки истории чата из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка выгрузки истории чата из БД".to_string(), source: e }
+    })?;
+
+    info!("Выгружено {} сообщений группового чата ID: {}", messages.len(), chat_id);
+    Ok(messages)
+}
+
+// страница истории группового чата для потоковой выгрузки (сообщения с ID больше after_id)
+pub async fn load_chat_export_page(
+    pool: &DbPool,
+    chat_id: i32,
+    after_id: i32,
+    limit: i64,
+) -> AppResult<Vec<ExportedMessage>> {
+    let messages = sqlx::query_as!(
+        ExportedMessage,
+        r#"SELECT id, sender, content, reply_to, timestamp as "sent_at!" FROM messages
+        WHERE chat_id = $1 AND id > $2 AND delivered ORDER BY id LIMIT $3"#,
+        chat_id,
+        after_id,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка выгрузки истории чата из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка выгрузки истории чата из БД".to_string(), source: e }
+    })?;
+
+    Ok(messages)
+}
+
+// удаление сообщений старше указанного количества дней
+pub async fn purge_older_than(pool: &DbPool, days: i32) -> AppResult<u64> {
+    info!("Удаление сообщений старше {} дней", days);
+
+    let rows_affected = sqlx::query!(
+        "DELETE FROM messages WHERE timestamp < NOW() - make_interval(days => $1)",
+        days,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка удаления старых сообщений: {}", e);
+        ServerError::DatabaseError { context: "Ошибка удаления старых сообщений".to_string(), source: e }
+    })?
+    .rows_affected();
+
+    info!("Удалено {} сообщений", rows_affected);
+    Ok(rows_affected)
+}
+
+// Выбор отложенных сообщений, время отправки которых наступило, с пометкой о доставке.
+// SKIP LOCKED не дает нескольким экземплярам сервера доставить одно сообщение дважды
+pub async fn claim_due(pool: &DbPool) -> AppResult<Vec<ChatMessage>> {
+    let rows = sqlx::query!(
+        "UPDATE messages SET delivered = TRUE, timestamp = NOW()
+        WHERE id IN (
+            SELECT id FROM messages WHERE NOT delivered AND send_at <= NOW()
+            ORDER BY send_at FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, chat_id, sender, content, reply_to",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка выборки отложенных сообщений: {}", e);
+        ServerError::DatabaseError { context: "Ошибка выборки отложенных сообщений".to_string(), source: e }
+    })?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| ChatMessage {
+            id: row.id,
+            chat_id: row.chat_id,
+            sender: row.sender,
+            content: row.content,
+            reply_to: row.reply_to,
+            reactions: Vec::new(),
+        })
+        .collect())
+}
+
+// удаление сообщений с истекшим сроком жизни, возвращает пары (ID сообщения, ID чата)
+pub async fn delete_expired(pool: &DbPool) -> AppResult<Vec<(i32, Option<i32>)>> {
+    let rows = sqlx::query!(
+        "DELETE FROM messages WHERE expires_at <= NOW() RETURNING id, chat_id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка удаления истекших сообщений: {}", e);
+        ServerError::DatabaseError { context: "Ошибка удаления истекших сообщений".to_string(), source: e }
+    })?;
+
+    if !rows.is_empty() {
+        info!("Удалено {} истекших сообщений", rows.len());
+    }
+    Ok(rows.into_iter().map(|row| (row.id, row.chat_id)).collect())
+}
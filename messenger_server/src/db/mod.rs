@@ -2,3 +2,11 @@ pub mod db_main;
 pub mod user;
 pub mod group_chat;
 pub mod messages;
+pub mod private_messages;
+pub mod reactions;
+pub mod restrictions;
+pub mod sessions;
+pub mod read_markers;
+pub mod bots;
+pub mod webhooks;
+pub mod audit;
@@ -0,0 +1,113 @@
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{DirectChatExport, ExportedMessage}};
+
+// сохранение личного сообщения, возвращает ID сообщения
+pub async fn save(pool: &DbPool, sender: &str, recipient: &str, content: &str) -> AppResult<i32> {
+    info!("Сохранение в базу данных личного сообщения: sender={}, recipient={}", sender, recipient);
+
+    let id = sqlx::query!(
+        "INSERT INTO private_messages (sender, recipient, content) VALUES ($1, $2, $3) RETURNING id",
+        sender,
+        recipient,
+        content,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка сохранения личного сообщения в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка сохранения личного сообщения в БД".to_string(), source: e }
+    })?
+    .id;
+
+    Ok(id)
+}
+
+// страница переписки двух пользователей в обе стороны для потоковой выгрузки (сообщения с ID больше after_id)
+pub async fn load_export_page(
+    pool: &DbPool,
+    user: &str,
+    other: &str,
+    after_id: i32,
+    limit: i64,
+) -> AppResult<Vec<ExportedMessage>> {
+    let messages = sqlx::query_as!(
+        ExportedMessage,
+        r#"SELECT id, sender, content, NULL::INTEGER as "reply_to?", timestamp as "sent_at!" FROM private_messages
+        WHERE LEAST(sender, recipient) = LEAST($1, $2) AND GREATEST(sender, recipient) = GREATEST($1, $2)
+            AND id > $3
+        ORDER BY id LIMIT $4"#,
+        user,
+        other,
+        after_id,
+        limit,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка выгрузки личной переписки из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка выгрузки личной переписки из БД".to_string(), source: e }
+    })?;
+
+    Ok(messages)
+}
+
+// Загрузка выгруженной переписки, возвращает количество записанных сообщений.
+// Получатель каждого сообщения - второй участник переписки
+pub async fn import(pool: &DbPool, export: &DirectChatExport) -> AppResult<usize> {
+    let [first, second] = export.participants.as_slice() else {
+        return Err(ServerError::InvalidArgument("В личной переписке должно быть два участника".to_string()));
+    };
+    info!("Загрузка личной переписки {} и {} ({} сообщений)", first, second, export.messages.len());
+
+    let db_error = |context: &str, e: sqlx::Error| {
+        error!("{}: {}", context, e);
+        ServerError::DatabaseError { context: context.to_string(), source: e }
+    };
+
+    let mut tx = pool.begin().await.map_err(|e| db_error("Ошибка начала транзакции", e))?;
+
+    for message in &export.messages {
+        let recipient = if message.sender == *first {
+            second
+        } else if message.sender == *second {
+            first
+        } else {
+            return Err(ServerError::InvalidArgument(format!(
+                "Отправитель {} не участвует в переписке", message.sender
+            )));
+        };
+        sqlx::query!(
+            "INSERT INTO private_messages (sender, recipient, content, timestamp) VALUES ($1, $2, $3, $4)",
+            message.sender,
+            recipient,
+            message.content,
+            message.sent_at,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_error("Ошибка записи личного сообщения в БД", e))?;
+    }
+
+    tx.commit().await.map_err(|e| db_error("Ошибка завершения транзакции", e))?;
+    info!("Личная переписка {} и {} загружена", first, second);
+    Ok(export.messages.len())
+}
+
+// удаление личных сообщений старше указанного количества дней
+pub async fn purge_older_than(pool: &DbPool, days: i32) -> AppResult<u64> {
+    let rows_affected = sqlx::query!(
+        "DELETE FROM private_messages WHERE timestamp < NOW() - make_interval(days => $1)",
+        days,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка удаления старых личных сообщений: {}", e);
+        ServerError::DatabaseError { context: "Ошибка удаления старых личных сообщений".to_string(), source: e }
+    })?
+    .rows_affected();
+
+    info!("Удалено {} личных сообщений", rows_affected);
+    Ok(rows_affected)
+}
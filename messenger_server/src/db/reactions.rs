@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{ChatMessage, ReactionCount}};
+
+// добавление реакции пользователя на сообщение
+pub async fn add(pool: &DbPool, message_id: i32, username: &str, emoji: &str) -> AppResult<()> {
+    info!("Пользователь {} ставит реакцию {} на сообщение ID: {}", username, emoji, message_id);
+
+    sqlx::query!(
+        "INSERT INTO message_reactions (message_id, username, emoji) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        message_id,
+        username,
+        emoji,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка записи реакции в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка записи реакции в БД".to_string(), source: e }
+    })?;
+
+    Ok(())
+}
+
+// удаление реакции пользователя
+pub async fn remove(pool: &DbPool, message_id: i32, username: &str, emoji: &str) -> AppResult<()> {
+    info!("Пользователь {} снимает реакцию {} с сообщения ID: {}", username, emoji, message_id);
+
+    sqlx::query!(
+        "DELETE FROM message_reactions WHERE message_id = $1 AND username = $2 AND emoji = $3",
+        message_id,
+        username,
+        emoji,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка удаления реакции из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка удаления реакции из БД".to_string(), source: e }
+    })?;
+
+    Ok(())
+}
+
+// количество реакций для набора сообщений
+pub async fn get_counts(pool: &DbPool, message_ids: &[i32]) -> AppResult<HashMap<i32, Vec<ReactionCount>>> {
+    let rows = sqlx::query!(
+        r#"SELECT message_id, emoji, COUNT(*) as "count!" FROM message_reactions
+        WHERE message_id = ANY($1)
+        GROUP BY message_id, emoji
+        ORDER BY MIN(created_at)"#,
+        message_ids,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения реакций из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения реакций из БД".to_string(), source: e }
+    })?;
+
+    let mut counts: HashMap<i32, Vec<ReactionCount>> = HashMap::new();
+    for row in rows {
+        counts
+            .entry(row.message_id)
+            .or_default()
+            .push(ReactionCount { emoji: row.emoji, count: row.count });
+    }
+    Ok(counts)
+}
+
+// заполнение реакций у загруженных сообщений
+pub async fn attach_counts(pool: &DbPool, mut messages: Vec<ChatMessage>) -> AppResult<Vec<ChatMessage>> {
+    let ids: Vec<i32> = messages.iter().map(|message| message.id).collect();
+    let mut counts = get_counts(pool, &ids).await?;
+    for message in &mut messages {
+        message.reactions = counts.remove(&message.id).unwrap_or_default();
+    }
+    Ok(messages)
+}
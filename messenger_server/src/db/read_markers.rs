@@ -0,0 +1,113 @@
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{ReadMarker, UserChat}};
+
+// Сохранение отметки о прочтении. Отметка только двигается вперед,
+// возвращается итоговый ID последнего прочитанного сообщения
+pub async fn mark_read(
+    pool: &DbPool,
+    username: &str,
+    chat_id: Option<i32>,
+    message_id: i32,
+) -> AppResult<i32> {
+    info!("Отметка о прочтении: {} (chat_id={:?}, message_id={})", username, chat_id, message_id);
+
+    let row = sqlx::query!(
+        "INSERT INTO read_markers (username, chat_id, message_id) VALUES ($1, $2, $3)
+        ON CONFLICT (username, COALESCE(chat_id, 0)) DO UPDATE
+        SET message_id = GREATEST(read_markers.message_id, EXCLUDED.message_id), updated_at = NOW()
+        RETURNING message_id",
+        username,
+        chat_id,
+        message_id,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка записи отметки о прочтении в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка записи отметки о прочтении в БД".to_string(), source: e }
+    })?;
+
+    Ok(row.message_id)
+}
+
+// все отметки о прочтении пользователя
+pub async fn get_all(pool: &DbPool, username: &str) -> AppResult<Vec<ReadMarker>> {
+    let markers = sqlx::query_as!(
+        ReadMarker,
+        "SELECT chat_id, message_id FROM read_markers WHERE username = $1",
+        username,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения отметок о прочтении из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения отметок о прочтении из БД".to_string(), source: e }
+    })?;
+
+    Ok(markers)
+}
+
+// Чаты пользователя (общий и групповые, где он участник) с количеством
+// непрочитанных сообщений от других пользователей
+pub async fn get_user_chats(pool: &DbPool, username: &str) -> AppResult<Vec<UserChat>> {
+    let chats = sqlx::query_as!(
+        UserChat,
+        r#"WITH chats AS (
+            SELECT NULL::int4 AS chat_id, 'Общий чат'::varchar AS name, NULL::varchar AS creator
+            UNION ALL
+            SELECT gc.id, gc.name, gc.creator
+            FROM group_chats gc
+            JOIN group_chat_members m ON m.chat_id = gc.id
+            WHERE m.username = $1
+        )
+        SELECT
+            c.chat_id,
+            c.name AS "name!",
+            c.creator,
+            rm.message_id AS "last_read?",
+            (
+                SELECT COUNT(*) FROM messages msg
+                WHERE COALESCE(msg.chat_id, 0) = COALESCE(c.chat_id, 0)
+                    AND msg.delivered
+                    AND msg.sender <> $1
+                    AND msg.id > COALESCE(rm.message_id, 0)
+            ) AS "unread!"
+        FROM chats c
+        LEFT JOIN read_markers rm ON rm.username = $1 AND COALESCE(rm.chat_id, 0) = COALESCE(c.chat_id, 0)
+        ORDER BY c.chat_id NULLS FIRST"#,
+        username,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения чатов пользователя из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения чатов пользователя из БД".to_string(), source: e }
+    })?;
+
+    Ok(chats)
+}
+
+// количество непрочитанных сообщений в одном чате
+pub async fn count_unread(pool: &DbPool, username: &str, chat_id: Option<i32>) -> AppResult<i64> {
+    let row = sqlx::query!(
+        r#"SELECT COUNT(*) AS "unread!" FROM messages msg
+        WHERE COALESCE(msg.chat_id, 0) = COALESCE($2, 0)
+            AND msg.delivered
+            AND msg.sender <> $1
+            AND msg.id > COALESCE((
+                SELECT message_id FROM read_markers
+                WHERE username = $1 AND COALESCE(chat_id, 0) = COALESCE($2, 0)
+            ), 0)"#,
+        username,
+        chat_id,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка подсчета непрочитанных сообщений: {}", e);
+        ServerError::DatabaseError { context: "Ошибка подсчета непрочитанных сообщений".to_string(), source: e }
+    })?;
+
+    Ok(row.unread)
+}
@@ -0,0 +1,80 @@
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::RestrictionKind};
+
+// добавление ограничения пользователю
+pub async fn add(
+    pool: &DbPool,
+    username: &str,
+    chat_id: Option<i32>,
+    kind: RestrictionKind,
+    created_by: &str,
+) -> AppResult<()> {
+    info!("Ограничение {} для {} (chat_id={:?}, выдал: {})", kind.as_str(), username, chat_id, created_by);
+
+    sqlx::query!(
+        "INSERT INTO user_restrictions (username, chat_id, kind, created_by) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        username,
+        chat_id,
+        kind.as_str(),
+        created_by,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка записи ограничения пользователя в БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка записи ограничения пользователя в БД".to_string(), source: e }
+    })?;
+
+    Ok(())
+}
+
+// снятие ограничения с пользователя
+pub async fn remove(
+    pool: &DbPool,
+    username: &str,
+    chat_id: Option<i32>,
+    kind: RestrictionKind,
+) -> AppResult<()> {
+    info!("Снятие ограничения {} с {} (chat_id={:?})", kind.as_str(), username, chat_id);
+
+    let rows_affected = sqlx::query!(
+        "DELETE FROM user_restrictions WHERE username = $1 AND chat_id IS NOT DISTINCT FROM $2 AND kind = $3",
+        username,
+        chat_id,
+        kind.as_str(),
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка удаления ограничения пользователя из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка удаления ограничения пользователя из БД".to_string(), source: e }
+    })?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(ServerError::MemberNotFound);
+    }
+    Ok(())
+}
+
+// действующие ограничения пользователя: глобальные и для указанного чата
+pub async fn find_active(
+    pool: &DbPool,
+    username: &str,
+    chat_id: Option<i32>,
+) -> AppResult<Vec<RestrictionKind>> {
+    let rows = sqlx::query!(
+        "SELECT kind FROM user_restrictions WHERE username = $1 AND (chat_id IS NULL OR chat_id = $2)",
+        username,
+        chat_id,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка получения ограничений пользователя из БД: {}", e);
+        ServerError::DatabaseError { context: "Ошибка получения ограничений пользователя из БД".to_string(), source: e }
+    })?;
+
+    Ok(rows.into_iter().filter_map(|row| RestrictionKind::from_db(&row.kind)).collect())
+}
@@ -0,0 +1,131 @@
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{ClientInfo, Session}};
+
+fn db_error(context: &str, e: sqlx::Error) -> ServerError {
+    error!("{}: {}", context, e);
+    ServerError::DatabaseError { context: context.to_string(), source: e }
+}
+
+// создание сессии, возвращает ее ID
+pub async fn create(
+    pool: &DbPool,
+    username: &str,
+    access_token_hash: &str,
+    refresh_token_hash: &str,
+    access_ttl_secs: i64,
+    refresh_ttl_secs: i64,
+    client: &ClientInfo,
+) -> AppResult<i32> {
+    let row = sqlx::query!(
+        "INSERT INTO sessions (username, access_token_hash, refresh_token_hash, access_expires_at, refresh_expires_at, user_agent, ip)
+        VALUES ($1, $2, $3, NOW() + make_interval(secs => $4), NOW() + make_interval(secs => $5), $6, $7)
+        RETURNING id",
+        username,
+        access_token_hash,
+        refresh_token_hash,
+        access_ttl_secs as f64,
+        refresh_ttl_secs as f64,
+        client.user_agent,
+        client.ip,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_error("Ошибка создания сессии", e))?;
+
+    info!("Создана сессия ID: {} для {}", row.id, username);
+    Ok(row.id)
+}
+
+// действующая сессия по токену доступа, заодно отмечается время использования
+pub async fn find_by_access_token(pool: &DbPool, access_token_hash: &str) -> AppResult<Option<Session>> {
+    let session = sqlx::query_as!(
+        Session,
+        "UPDATE sessions SET last_used_at = NOW()
+        WHERE access_token_hash = $1 AND revoked_at IS NULL AND access_expires_at > NOW()
+        RETURNING id, username, user_agent, ip, created_at, last_used_at",
+        access_token_hash,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_error("Ошибка поиска сессии", e))?;
+
+    Ok(session)
+}
+
+// Замена обоих токенов действующей сессии по токену обновления.
+// Возвращает ID сессии, если токен обновления действителен
+pub async fn rotate(
+    pool: &DbPool,
+    refresh_token_hash: &str,
+    new_access_token_hash: &str,
+    new_refresh_token_hash: &str,
+    access_ttl_secs: i64,
+    refresh_ttl_secs: i64,
+) -> AppResult<Option<i32>> {
+    let row = sqlx::query!(
+        "UPDATE sessions SET
+            access_token_hash = $2,
+            refresh_token_hash = $3,
+            access_expires_at = NOW() + make_interval(secs => $4),
+            refresh_expires_at = NOW() + make_interval(secs => $5),
+            last_used_at = NOW()
+        WHERE refresh_token_hash = $1 AND revoked_at IS NULL AND refresh_expires_at > NOW()
+        RETURNING id",
+        refresh_token_hash,
+        new_access_token_hash,
+        new_refresh_token_hash,
+        access_ttl_secs as f64,
+        refresh_ttl_secs as f64,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| db_error("Ошибка обновления сессии", e))?;
+
+    Ok(row.map(|row| row.id))
+}
+
+// отзыв одной сессии пользователя
+pub async fn revoke(pool: &DbPool, username: &str, session_id: i32) -> AppResult<bool> {
+    let rows_affected = sqlx::query!(
+        "UPDATE sessions SET revoked_at = NOW() WHERE id = $1 AND username = $2 AND revoked_at IS NULL",
+        session_id,
+        username,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| db_error("Ошибка отзыва сессии", e))?
+    .rows_affected();
+
+    Ok(rows_affected > 0)
+}
+
+// отзыв всех сессий пользователя, возвращает ID отозванных сессий
+pub async fn revoke_all(pool: &DbPool, username: &str) -> AppResult<Vec<i32>> {
+    let rows = sqlx::query!(
+        "UPDATE sessions SET revoked_at = NOW() WHERE username = $1 AND revoked_at IS NULL RETURNING id",
+        username,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_error("Ошибка отзыва сессий", e))?;
+
+    info!("Отозвано {} сессий пользователя {}", rows.len(), username);
+    Ok(rows.into_iter().map(|row| row.id).collect())
+}
+
+// активные сессии пользователя
+pub async fn list_active(pool: &DbPool, username: &str) -> AppResult<Vec<Session>> {
+    let sessions = sqlx::query_as!(
+        Session,
+        "SELECT id, username, user_agent, ip, created_at, last_used_at FROM sessions
+        WHERE username = $1 AND revoked_at IS NULL AND refresh_expires_at > NOW()
+        ORDER BY last_used_at DESC",
+        username,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_error("Ошибка получения списка сессий", e))?;
+
+    Ok(sessions)
+}
@@ -25,7 +25,7 @@ pub async fn register(
     let password_hash = hash(password, DEFAULT_COST)
         .map_err(|e| {
             error!("Ошибка хэширования пароля: {}", e);
-            ServerError::BcryptError(e.into())
+            ServerError::BcryptError(e)
         })?;
 
     sqlx::query!(
@@ -43,3 +43,95 @@ pub async fn register(
     info!("Пользователь {} успешно зарегистрирован", username);
     Ok(())
 }
+
+// является ли пользователь администратором сервера
+pub async fn is_admin(pool: &DbPool, username: &str) -> AppResult<bool> {
+    let row = sqlx::query!(
+        "SELECT is_admin FROM users WHERE username = $1",
+        username,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка при проверке прав администратора: {}", e);
+        ServerError::DatabaseError { context: "Ошибка при проверке прав администратора".to_string(), source: e }
+    })?;
+    Ok(row.is_some_and(|r| r.is_admin))
+}
+
+// список всех пользователей
+pub async fn list_all(pool: &DbPool) -> AppResult<Vec<String>> {
+    let rows = sqlx::query!("SELECT username FROM users ORDER BY username")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            error!("Ошибка получения списка пользователей: {}", e);
+            ServerError::DatabaseError { context: "Ошибка получения списка пользователей".to_string(), source: e }
+        })?;
+    Ok(rows.into_iter().map(|row| row.username).collect())
+}
+
+// смена пароля пользователя
+pub async fn update_password(pool: &DbPool, username: &str, password: &str) -> AppResult<()> {
+    let password_hash = hash(password, DEFAULT_COST)
+        .map_err(|e| {
+            error!("Ошибка хэширования пароля: {}", e);
+            ServerError::BcryptError(e)
+        })?;
+
+    let rows_affected = sqlx::query!(
+        "UPDATE users SET password_hash = $2 WHERE username = $1",
+        username,
+        password_hash,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| {
+        error!("Ошибка смены пароля пользователя: {}", e);
+        ServerError::DatabaseError { context: "Ошибка смены пароля пользователя".to_string(), source: e }
+    })?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(ServerError::MemberNotFound);
+    }
+    info!("Пароль пользователя {} изменен", username);
+    Ok(())
+}
+
+// удаление пользователя вместе с его участием в чатах
+pub async fn delete(pool: &DbPool, username: &str) -> AppResult<()> {
+    let mut tx = pool.begin().await.map_err(|e| {
+        error!("Ошибка начала транзакции: {}", e);
+        ServerError::DatabaseError { context: "Ошибка начала транзакции".to_string(), source: e }
+    })?;
+
+    sqlx::query!("DELETE FROM group_chat_members WHERE username = $1", username)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Ошибка удаления пользователя из чатов: {}", e);
+            ServerError::DatabaseError { context: "Ошибка удаления пользователя из чатов".to_string(), source: e }
+        })?;
+
+    let rows_affected = sqlx::query!("DELETE FROM users WHERE username = $1", username)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            error!("Ошибка удаления пользователя: {}", e);
+            ServerError::DatabaseError { context: "Ошибка удаления пользователя".to_string(), source: e }
+        })?
+        .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(ServerError::MemberNotFound);
+    }
+
+    tx.commit().await.map_err(|e| {
+        error!("Ошибка завершения транзакции: {}", e);
+        ServerError::DatabaseError { context: "Ошибка завершения транзакции".to_string(), source: e }
+    })?;
+
+    info!("Пользователь {} удален", username);
+    Ok(())
+}
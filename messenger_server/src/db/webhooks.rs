@@ -0,0 +1,132 @@
+use tracing::{info, error};
+
+use crate::{types::{AppResult, DbPool, ServerError}, structs::{Webhook, WebhookDelivery}};
+
+fn db_error(context: &str, e: sqlx::Error) -> ServerError {
+    error!("{}: {}", context, e);
+    ServerError::DatabaseError { context: context.to_string(), source: e }
+}
+
+// регистрация вебхука, возвращает его ID
+pub async fn create(pool: &DbPool, chat_id: i32, url: &str, secret: &str, created_by: &str) -> AppResult<i32> {
+    let row = sqlx::query!(
+        "INSERT INTO webhooks (chat_id, url, secret, created_by) VALUES ($1, $2, $3, $4) RETURNING id",
+        chat_id,
+        url,
+        secret,
+        created_by,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|e| db_error("Ошибка записи вебхука в БД", e))?;
+
+    info!("Вебхук ID: {} зарегистрирован для чата ID: {}", row.id, chat_id);
+    Ok(row.id)
+}
+
+pub async fn get_for_chat(pool: &DbPool, chat_id: i32) -> AppResult<Vec<Webhook>> {
+    let webhooks = sqlx::query_as!(
+        Webhook,
+        "SELECT id, chat_id, url, created_by, created_at FROM webhooks WHERE chat_id = $1 ORDER BY id",
+        chat_id,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_error("Ошибка получения списка вебхуков", e))?;
+
+    Ok(webhooks)
+}
+
+pub async fn delete(pool: &DbPool, chat_id: i32, webhook_id: i32) -> AppResult<()> {
+    let rows_affected = sqlx::query!(
+        "DELETE FROM webhooks WHERE id = $1 AND chat_id = $2",
+        webhook_id,
+        chat_id,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| db_error("Ошибка удаления вебхука", e))?
+    .rows_affected();
+
+    if rows_affected == 0 {
+        return Err(ServerError::InvalidOperation);
+    }
+    Ok(())
+}
+
+// постановка события в очередь доставки для всех вебхуков чата
+pub async fn enqueue_for_chat(pool: &DbPool, chat_id: i32, payload: &str) -> AppResult<u64> {
+    let rows_affected = sqlx::query!(
+        "INSERT INTO webhook_deliveries (webhook_id, payload) SELECT id, $2 FROM webhooks WHERE chat_id = $1",
+        chat_id,
+        payload,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| db_error("Ошибка постановки вебхука в очередь", e))?
+    .rows_affected();
+
+    Ok(rows_affected)
+}
+
+// Выбор доставок, время которых наступило. Выбранные доставки откладываются
+// на lease_secs, чтобы другой экземпляр сервера не отправил их одновременно
+pub async fn claim_due(pool: &DbPool, limit: i64, lease_secs: f64) -> AppResult<Vec<WebhookDelivery>> {
+    let deliveries = sqlx::query_as!(
+        WebhookDelivery,
+        "UPDATE webhook_deliveries d SET next_attempt_at = NOW() + make_interval(secs => $2)
+        FROM webhooks w
+        WHERE d.webhook_id = w.id AND d.id IN (
+            SELECT id FROM webhook_deliveries
+            WHERE delivered_at IS NULL AND failed_at IS NULL AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING d.id, d.payload, d.attempts, w.url, w.secret",
+        limit,
+        lease_secs,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| db_error("Ошибка выборки вебхуков для доставки", e))?;
+
+    Ok(deliveries)
+}
+
+pub async fn mark_delivered(pool: &DbPool, delivery_id: i32) -> AppResult<()> {
+    sqlx::query!(
+        "UPDATE webhook_deliveries SET delivered_at = NOW(), attempts = attempts + 1, last_error = NULL WHERE id = $1",
+        delivery_id,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| db_error("Ошибка отметки доставки вебхука", e))?;
+
+    Ok(())
+}
+
+// неудачная попытка: следующая через retry_in_secs, None - попытки закончились
+pub async fn mark_attempt_failed(
+    pool: &DbPool,
+    delivery_id: i32,
+    error_text: &str,
+    retry_in_secs: Option<f64>,
+) -> AppResult<()> {
+    sqlx::query!(
+        "UPDATE webhook_deliveries SET
+            attempts = attempts + 1,
+            last_error = $2,
+            next_attempt_at = CASE WHEN $3::float8 IS NULL THEN next_attempt_at ELSE NOW() + make_interval(secs => $3) END,
+            failed_at = CASE WHEN $3::float8 IS NULL THEN NOW() ELSE NULL END
+        WHERE id = $1",
+        delivery_id,
+        error_text,
+        retry_in_secs,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| db_error("Ошибка отметки неудачной доставки вебхука", e))?;
+
+    Ok(())
+}
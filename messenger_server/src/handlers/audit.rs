@@ -0,0 +1,26 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use tracing::error;
+use crate::{handlers::auth, services::audit_service, structs::AuditFilter, types::{DbPool, ServerError}};
+use std::sync::Arc;
+
+// GET /audit?event_type=&actor=&target=&chat_id=&since=&until=&before_id=&limit= - только для администраторов
+pub async fn get_all(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    filter: web::Query<AuditFilter>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let ip = auth::client_info(&req).ip;
+
+    match audit_service::list(pool.get_ref(), &session.username, ip.as_deref(), &filter).await {
+        Ok(events) => HttpResponse::Ok().json(events),
+        Err(ServerError::PermissionDenied) => HttpResponse::Forbidden().body("Журнал аудита доступен только администраторам"),
+        Err(e) => {
+            error!("Ошибка загрузки журнала аудита {}", e);
+            HttpResponse::InternalServerError().body("Ошибка загрузки журнала аудита")
+        }
+    }
+}
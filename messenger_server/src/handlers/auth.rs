@@ -1,26 +1,55 @@
-use crate::{services::auth_service, types::DbPool};
-use actix_web::{web, HttpResponse, Responder};
+use crate::{
+    backplane::{Event, SharedBackplane},
+    services::{audit_service, auth_service, session_service},
+    structs::{AuditEventKind, AuditRecord, ClientInfo, Session},
+    types::{AppResult, DbPool, ServerError},
+};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
 use tracing::error;
+use messenger_protocol::requests::{LoginUser, RefreshSession, RegisterUser};
 use std::sync::Arc;
 
-#[derive(serde::Deserialize)]
-pub struct RegisterUser {
-    username: String,
-    password: String,
+// данные клиента из заголовков запроса
+pub fn client_info(req: &HttpRequest) -> ClientInfo {
+    ClientInfo {
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+        ip: req.connection_info().realip_remote_addr().map(str::to_string),
+    }
+}
+
+// токен из заголовка Authorization: Bearer <token>
+pub fn bearer_token(req: &HttpRequest) -> AppResult<&str> {
+    req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(ServerError::InvalidToken)
 }
 
-#[derive(serde::Deserialize)]
-pub struct LoginUser {
-    username: String,
-    password: String,
+// сессия по заголовку Authorization: Bearer <token>
+pub async fn current_session(pool: &DbPool, req: &HttpRequest) -> AppResult<Session> {
+    session_service::authenticate(pool, bearer_token(req)?).await
 }
 
 pub async fn register(
+    req: HttpRequest,
     pool: web::Data<Arc<DbPool>>,
     form: web::Json<RegisterUser>,
 ) -> impl Responder {
     match auth_service::register_user(pool.get_ref(), &form.username, &form.password).await {
-        Ok(_) => HttpResponse::Ok().body("Пользователь успешно зарегистрирован"),
+        Ok(_) => {
+            let record = AuditRecord::new(AuditEventKind::Register)
+                .actor(&form.username)
+                .ip(client_info(&req).ip.as_deref());
+            audit_service::record(pool.get_ref(), record).await;
+            HttpResponse::Ok().body("Пользователь успешно зарегистрирован")
+        }
+        Err(ServerError::InvalidArgument(reason)) => HttpResponse::BadRequest().body(reason),
         Err(e) => {
             error!("Ошибка регистрации пользователя: {}", e);
             HttpResponse::BadRequest().body("Ошибка регистрации")
@@ -29,15 +58,100 @@ pub async fn register(
 }
 
 pub async fn login(
+    req: HttpRequest,
     pool: web::Data<Arc<DbPool>>,
     form: web::Json<LoginUser>,
 ) -> impl Responder {
-    match auth_service::authenticate_user(pool.get_ref(), &form.username, &form.password).await {
-        Ok(true) => HttpResponse::Ok().body("Пользователь успешно вошел"),
-        Ok(false) => HttpResponse::Unauthorized().body("Неверный логин или пароль"),
+    let client = client_info(&req);
+    match session_service::login(pool.get_ref(), &form.username, &form.password, &client).await {
+        Ok(Some(tokens)) => {
+            let record = AuditRecord::new(AuditEventKind::LoginSuccess)
+                .actor(&form.username)
+                .ip(client.ip.as_deref())
+                .details(format!("сессия ID: {}", tokens.session_id));
+            audit_service::record(pool.get_ref(), record).await;
+            HttpResponse::Ok().json(tokens)
+        }
+        Ok(None) => {
+            let record = AuditRecord::new(AuditEventKind::LoginFailure)
+                .actor(&form.username)
+                .ip(client.ip.as_deref());
+            audit_service::record(pool.get_ref(), record).await;
+            HttpResponse::Unauthorized().body("Неверный логин или пароль")
+        }
         Err(e) => {
             error!("Ошибка входа пользователя: {}", e);
             HttpResponse::InternalServerError().body("Ошибка авторизации")
         },
     }
 }
+
+pub async fn refresh(
+    pool: web::Data<Arc<DbPool>>,
+    form: web::Json<RefreshSession>,
+) -> impl Responder {
+    match session_service::refresh(pool.get_ref(), &form.refresh_token).await {
+        Ok(tokens) => HttpResponse::Ok().json(tokens),
+        Err(ServerError::InvalidToken) => HttpResponse::Unauthorized().body("Сессия недействительна"),
+        Err(e) => {
+            error!("Ошибка обновления сессии: {}", e);
+            HttpResponse::InternalServerError().body("Ошибка обновления сессии")
+        },
+    }
+}
+
+// отключение живых подключений отозванных сессий на всех экземплярах сервера
+pub async fn disconnect_sessions(backplane: &SharedBackplane, session_ids: Vec<i32>) {
+    if session_ids.is_empty() {
+        return;
+    }
+    if let Err(e) = backplane.publish(Event::SessionsRevoked { session_ids }).await {
+        error!("Ошибка отправки события отзыва сессий: {}", e);
+    }
+}
+
+// выход из текущей сессии
+pub async fn logout(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    backplane: web::Data<SharedBackplane>,
+) -> impl Responder {
+    let session = match current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    match session_service::revoke_session(pool.get_ref(), &session.username, session.id).await {
+        Ok(_) => {
+            disconnect_sessions(backplane.get_ref(), vec![session.id]).await;
+            HttpResponse::Ok().body("Выход выполнен")
+        }
+        Err(e) => {
+            error!("Ошибка выхода пользователя: {}", e);
+            HttpResponse::InternalServerError().body("Ошибка выхода")
+        }
+    }
+}
+
+// выход на всех устройствах
+pub async fn logout_all(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    backplane: web::Data<SharedBackplane>,
+) -> impl Responder {
+    let session = match current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    match session_service::revoke_all_sessions(pool.get_ref(), &session.username).await {
+        Ok(session_ids) => {
+            disconnect_sessions(backplane.get_ref(), session_ids).await;
+            HttpResponse::Ok().body("Выход выполнен на всех устройствах")
+        }
+        Err(e) => {
+            error!("Ошибка выхода пользователя на всех устройствах: {}", e);
+            HttpResponse::InternalServerError().body("Ошибка выхода")
+        }
+    }
+}
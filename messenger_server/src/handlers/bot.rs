@@ -0,0 +1,86 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use tracing::error;
+use crate::{
+    backplane::SharedBackplane,
+    handlers::auth,
+    moderation::FilterChain,
+    services::{audit_service, bot_service, message_service},
+    types::{DbPool, ServerError},
+};
+use messenger_protocol::requests::{CreateBot, PostMessage};
+use std::sync::Arc;
+
+// POST /bots - создание бота текущим пользователем, токен выдается один раз
+pub async fn create(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    form: web::Json<CreateBot>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    match bot_service::create_bot(pool.get_ref(), &session.username, &form.name).await {
+        Ok((username, token)) => HttpResponse::Ok().json(json!({ "username": username, "token": token })),
+        Err(ServerError::UserExists) => HttpResponse::Conflict().body("Пользователь с таким именем уже существует"),
+        Err(ServerError::InvalidArgument(reason)) => HttpResponse::BadRequest().body(reason),
+        Err(e) => {
+            error!("Ошибка создания бота {}", e);
+            HttpResponse::InternalServerError().body("Ошибка создания бота")
+        }
+    }
+}
+
+// POST /chats/{id}/messages - сообщение бота в групповой чат, авторизация по токену бота
+pub async fn post_message(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    backplane: web::Data<SharedBackplane>,
+    filters: web::Data<Arc<FilterChain>>,
+    chat_id: web::Path<i32>,
+    form: web::Json<PostMessage>,
+) -> impl Responder {
+    let bot = match auth::bearer_token(&req) {
+        Ok(token) => match bot_service::authenticate(pool.get_ref(), token).await {
+            Ok(bot) => bot,
+            Err(_) => return HttpResponse::Unauthorized().body("Неверный токен бота"),
+        },
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let chat_id = chat_id.into_inner();
+
+    let result = match bot_service::check_can_post(pool.get_ref(), &bot, chat_id).await {
+        Ok(()) => message_service::send_message_to_group_chat(
+            backplane.get_ref(),
+            pool.get_ref(),
+            filters.get_ref(),
+            chat_id,
+            &bot,
+            &form.content,
+            &form.options,
+        ).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = &result {
+        let ip = auth::client_info(&req).ip;
+        audit_service::record_denial(pool.get_ref(), e, Some(bot.as_str()), Some(chat_id), ip.as_deref(), "сообщение бота").await;
+    }
+
+    match result {
+        Ok(scheduled) => HttpResponse::Ok().json(json!({ "scheduled_id": scheduled })),
+        Err(ServerError::ChatNotFound) => HttpResponse::NotFound().body("Чат не найден"),
+        Err(ServerError::PermissionDenied) => HttpResponse::Forbidden().body("Бот не состоит в чате"),
+        Err(e @ (ServerError::MessageRejected(_) | ServerError::UserMuted | ServerError::UserBanned)) => {
+            HttpResponse::Forbidden().body(e.to_string())
+        }
+        Err(e @ (ServerError::InvalidOperation | ServerError::MessageNotFound)) => {
+            HttpResponse::BadRequest().body(e.to_string())
+        }
+        Err(e) => {
+            error!("Ошибка отправки сообщения ботом {}", e);
+            HttpResponse::InternalServerError().body("Ошибка отправки сообщения")
+        }
+    }
+}
@@ -1,26 +1,36 @@
-use actix_web::{web, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use futures_util::StreamExt;
 use tracing::error;
-use crate::{services::chat_service, types::DbPool};
+use crate::{
+    handlers::auth,
+    services::{audit_service, chat_service::{self, ExportFormat, ExportSource}},
+    structs::{AuditEventKind, AuditRecord},
+    types::{DbPool, ServerError},
+};
+use messenger_protocol::requests::{CreateChat, DeleteChat, ExportChat};
 use std::sync::Arc;
 
-#[derive(serde::Deserialize)]
-pub struct CreateChat {
-    name: String,
-    creator: String,
-}
-
-#[derive(serde::Deserialize)]
-pub struct DeleteChat {
-    chat_id: i32,
-    requester: String,
-}
-
 pub async fn create(
+    req: HttpRequest,
     pool: web::Data<Arc<DbPool>>,
     form: web::Json<CreateChat>,
 ) -> impl Responder {
-    match chat_service::create_group_chat(pool.get_ref(), &form.name, &form.creator).await {
-        Ok(chat_id) => HttpResponse::Ok().body(format!("Чат создан с ID: {}", chat_id)),
+    // создателем становится владелец сессии
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    match chat_service::create_group_chat(pool.get_ref(), &form.name, &session.username).await {
+        Ok(chat_id) => {
+            let record = AuditRecord::new(AuditEventKind::ChatCreated)
+                .actor(&session.username)
+                .chat(chat_id)
+                .ip(auth::client_info(&req).ip.as_deref())
+                .details(form.name.clone());
+            audit_service::record(pool.get_ref(), record).await;
+            HttpResponse::Ok().body(format!("Чат создан с ID: {}", chat_id))
+        }
+        Err(ServerError::InvalidArgument(reason)) => HttpResponse::BadRequest().body(reason),
         Err(e) => {
             error!("Ошибка создания чата {}", e);
             HttpResponse::BadRequest().body("Ошибка создания чата")
@@ -29,12 +39,26 @@ pub async fn create(
 }
 
 pub async fn delete(
+    req: HttpRequest,
     pool: web::Data<Arc<DbPool>>,
     data: web::Json<DeleteChat>,
 ) -> impl Responder {
-    match chat_service::delete_group_chat(pool.get_ref(), data.chat_id, &data.requester).await {
-        Ok(_) => HttpResponse::Ok().body("Чат удален"),
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let ip = auth::client_info(&req).ip;
+    match chat_service::delete_group_chat(pool.get_ref(), data.chat_id, &session.username).await {
+        Ok(_) => {
+            let record = AuditRecord::new(AuditEventKind::ChatDeleted)
+                .actor(&session.username)
+                .chat(data.chat_id)
+                .ip(ip.as_deref());
+            audit_service::record(pool.get_ref(), record).await;
+            HttpResponse::Ok().body("Чат удален")
+        }
         Err(e) => {
+            audit_service::record_denial(pool.get_ref(), &e, Some(session.username.as_str()), Some(data.chat_id), ip.as_deref(), "удаление чата").await;
             error!("Ошибка удаления чата {}", e);
             HttpResponse::BadRequest().body("Ошибка удаления чата")
         },
@@ -50,3 +74,82 @@ pub async fn get_all(pool: web::Data<Arc<DbPool>>) -> impl Responder {
         }
     }
 }
+
+// GET /chats/{id}/export?format=json|txt, выгружает участник чата по токену сессии.
+// JSON читается как ChatExport и загружается через messenger-admin chats import
+pub async fn export(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    chat_id: web::Path<i32>,
+    query: web::Query<ExportChat>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let chat_id = chat_id.into_inner();
+    let Some(format) = ExportFormat::parse(query.format.as_deref().unwrap_or("json")) else {
+        return HttpResponse::BadRequest().body("Формат выгрузки должен быть json или txt");
+    };
+
+    match chat_service::check_export_access(pool.get_ref(), chat_id, &session.username).await {
+        Ok(_) => {}
+        Err(ServerError::ChatNotFound) => return HttpResponse::NotFound().body("Чат не найден"),
+        Err(e @ ServerError::PermissionDenied) => {
+            let ip = auth::client_info(&req).ip;
+            audit_service::record_denial(pool.get_ref(), &e, Some(session.username.as_str()), Some(chat_id), ip.as_deref(), "выгрузка чата").await;
+            return HttpResponse::Forbidden().body("Нет доступа к чату");
+        }
+        Err(e) => {
+            error!("Ошибка выгрузки чата {}", e);
+            return HttpResponse::InternalServerError().body("Ошибка выгрузки чата");
+        }
+    }
+
+    stream_export(pool.get_ref(), ExportSource::Group(chat_id), format)
+}
+
+// GET /chats/direct/{username}/export?format=json|txt, выгружает личную переписку
+// владельца сессии с пользователем username. JSON читается как DirectChatExport
+pub async fn export_direct(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    other: web::Path<String>,
+    query: web::Query<ExportChat>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let other = other.into_inner();
+    let Some(format) = ExportFormat::parse(query.format.as_deref().unwrap_or("json")) else {
+        return HttpResponse::BadRequest().body("Формат выгрузки должен быть json или txt");
+    };
+
+    match chat_service::check_direct_export_access(pool.get_ref(), &other).await {
+        Ok(_) => {}
+        Err(ServerError::ChatNotFound) => return HttpResponse::NotFound().body("Пользователь не найден"),
+        Err(e) => {
+            error!("Ошибка выгрузки переписки {}", e);
+            return HttpResponse::InternalServerError().body("Ошибка выгрузки чата");
+        }
+    }
+
+    stream_export(pool.get_ref(), ExportSource::Direct { user: session.username, other }, format)
+}
+
+fn stream_export(pool: &DbPool, source: ExportSource, format: ExportFormat) -> HttpResponse {
+    let stream = chat_service::export_stream(pool.clone(), source, format)
+        .map(|chunk| {
+            chunk
+                .map(web::Bytes::from)
+                .map_err(|e| {
+                    error!("Ошибка выгрузки чата {}", e);
+                    actix_web::error::ErrorInternalServerError("Ошибка выгрузки чата")
+                })
+        });
+
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .streaming(stream)
+}
@@ -1,2 +1,8 @@
+pub mod audit;
 pub mod auth;
 pub mod chat;
+pub mod session;
+pub mod bot;
+pub mod webhook;
+pub mod user;
+pub mod protocol;
@@ -0,0 +1,7 @@
+use actix_web::{HttpResponse, Responder};
+use messenger_protocol::version::ProtocolInfo;
+
+// GET /protocol - версия протокола и поддерживаемые подпротоколы WebSocket
+pub async fn info() -> impl Responder {
+    HttpResponse::Ok().json(ProtocolInfo::current())
+}
@@ -0,0 +1,48 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use tracing::error;
+use crate::{backplane::SharedBackplane, handlers::auth, services::session_service, types::DbPool};
+use std::sync::Arc;
+
+// GET /sessions - активные сессии текущего пользователя
+pub async fn get_all(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    match session_service::list_sessions(pool.get_ref(), &session.username).await {
+        Ok(sessions) => HttpResponse::Ok().json(sessions),
+        Err(e) => {
+            error!("Ошибка загрузки списка сессий {}", e);
+            HttpResponse::InternalServerError().body("Ошибка загрузки списка сессий")
+        }
+    }
+}
+
+// DELETE /sessions/{id} - отзыв сессии на другом устройстве
+pub async fn revoke(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    backplane: web::Data<SharedBackplane>,
+    session_id: web::Path<i32>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let session_id = session_id.into_inner();
+
+    match session_service::revoke_session(pool.get_ref(), &session.username, session_id).await {
+        Ok(_) => {
+            auth::disconnect_sessions(backplane.get_ref(), vec![session_id]).await;
+            HttpResponse::Ok().body("Сессия отозвана")
+        }
+        Err(e) => {
+            error!("Ошибка отзыва сессии {}", e);
+            HttpResponse::NotFound().body("Сессия не найдена")
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use tracing::error;
+use crate::{handlers::auth, services::unread_service, types::DbPool};
+use std::sync::Arc;
+
+// GET /users/me/chats - чаты текущего пользователя со счетчиками непрочитанных
+pub async fn my_chats(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    match unread_service::list_user_chats(pool.get_ref(), &session.username).await {
+        Ok(chats) => HttpResponse::Ok().json(chats),
+        Err(e) => {
+            error!("Ошибка загрузки чатов пользователя {}", e);
+            HttpResponse::InternalServerError().body("Ошибка загрузки чатов")
+        }
+    }
+}
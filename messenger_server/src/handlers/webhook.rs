@@ -0,0 +1,85 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use tracing::error;
+use crate::{handlers::auth, services::{audit_service, webhook_service}, types::{DbPool, ServerError}};
+use messenger_protocol::requests::CreateWebhook;
+use std::sync::Arc;
+
+async fn error_response(
+    pool: &DbPool,
+    req: &HttpRequest,
+    actor: &str,
+    chat_id: i32,
+    e: ServerError,
+    context: &str,
+) -> HttpResponse {
+    let ip = auth::client_info(req).ip;
+    audit_service::record_denial(pool, &e, Some(actor), Some(chat_id), ip.as_deref(), context).await;
+    match e {
+        ServerError::ChatNotFound => HttpResponse::NotFound().body("Чат не найден"),
+        ServerError::PermissionDenied => HttpResponse::Forbidden().body("Управлять вебхуками может только создатель чата"),
+        ServerError::InvalidOperation => HttpResponse::NotFound().body("Вебхук не найден"),
+        ServerError::InvalidArgument(reason) => HttpResponse::BadRequest().body(reason),
+        e => {
+            error!("{} {}", context, e);
+            HttpResponse::InternalServerError().body(context.to_string())
+        }
+    }
+}
+
+// POST /chats/{id}/webhooks - регистрация вебхука, секрет выдается один раз
+pub async fn create(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    chat_id: web::Path<i32>,
+    form: web::Json<CreateWebhook>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    let chat_id = chat_id.into_inner();
+
+    match webhook_service::create_webhook(pool.get_ref(), chat_id, &session.username, &form.url).await {
+        Ok((id, secret)) => HttpResponse::Ok().json(json!({ "id": id, "secret": secret })),
+        Err(e) => error_response(pool.get_ref(), &req, &session.username, chat_id, e, "Ошибка регистрации вебхука").await,
+    }
+}
+
+// GET /chats/{id}/webhooks
+pub async fn get_all(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    chat_id: web::Path<i32>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+
+    let chat_id = chat_id.into_inner();
+
+    match webhook_service::list_webhooks(pool.get_ref(), chat_id, &session.username).await {
+        Ok(webhooks) => HttpResponse::Ok().json(webhooks),
+        Err(e) => error_response(pool.get_ref(), &req, &session.username, chat_id, e, "Ошибка загрузки списка вебхуков").await,
+    }
+}
+
+// DELETE /chats/{id}/webhooks/{webhook_id}
+pub async fn delete(
+    req: HttpRequest,
+    pool: web::Data<Arc<DbPool>>,
+    path: web::Path<(i32, i32)>,
+) -> impl Responder {
+    let session = match auth::current_session(pool.get_ref(), &req).await {
+        Ok(session) => session,
+        Err(_) => return HttpResponse::Unauthorized().body("Требуется авторизация"),
+    };
+    let (chat_id, webhook_id) = path.into_inner();
+
+    match webhook_service::delete_webhook(pool.get_ref(), chat_id, &session.username, webhook_id).await {
+        Ok(()) => HttpResponse::Ok().body("Вебхук удален"),
+        Err(e) => error_response(pool.get_ref(), &req, &session.username, chat_id, e, "Ошибка удаления вебхука").await,
+    }
+}
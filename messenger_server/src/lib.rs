@@ -0,0 +1,12 @@
+// общий код сервера и служебных утилит (messenger-admin)
+pub mod backplane;
+pub mod codec;
+pub mod commands;
+pub mod db;
+pub mod services;
+pub mod handlers;
+pub mod moderation;
+pub mod presence;
+pub mod protocol;
+pub mod types;
+pub mod structs;
@@ -1,25 +1,29 @@
 use std::collections::HashMap;
+use std::env;
 use futures_util::StreamExt;
-use tokio_tungstenite::{accept_async, WebSocketStream};
+use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
 use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::HeaderValue;
 use futures_util::sink::SinkExt;
 use std::sync::Arc;
 use actix_web::{web, App, HttpServer};
 use actix_cors::Cors;
-use handlers::{auth, chat};
-use serde_json;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::Mutex;
 use tokio::net::TcpListener;
-use serde::{Serialize, Deserialize};
 use tracing::{debug, error, info, warn};
-use types::{AppResult, DbPool};
-use crate::db::{db_main, group_chat, messages};
-
-mod db;
-mod services;
-mod handlers;
-mod types;
-mod structs;
+use messenger_server::handlers::{audit, auth, bot, chat, protocol as protocol_handlers, session, user as user_handlers, webhook};
+use messenger_server::services::{audit_service, chat_service, message_service, session_service, webhook_service};
+use messenger_server::services::unread_service::UnreadTracker;
+use messenger_server::presence::{self, Clients};
+use messenger_server::protocol::Message;
+use messenger_server::types::{AppResult, DbPool, ServerError};
+use messenger_server::structs::{AuditEventKind, AuditRecord, RestrictionKind};
+use messenger_server::backplane::{self, Event, SharedBackplane};
+use messenger_server::codec::{self, Encoding};
+use messenger_server::commands::{self, CommandContext, CommandRegistry};
+use messenger_server::moderation::{self, FilterChain};
+use messenger_server::db::{db_main, group_chat, messages, private_messages, read_markers, restrictions, user};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -33,6 +37,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Подключение к базе данных успешно");
     let db_pool = Arc::new(db_pool);
 
+    // шина для доставки сообщений между экземплярами сервера
+    let backplane = backplane::from_env(&db_pool).await?;
+
+    // фильтры сообщений перед сохранением и рассылкой
+    let filters = Arc::new(FilterChain::from_env());
+
+    // команды, которые можно отправить вместо сообщения (/help, /invite, ...)
+    let commands = Arc::new(CommandRegistry::with_builtin());
+
+    let http_addr = env::var("HTTP_ADDR").unwrap_or_else(|_| "127.0.0.1:8081".to_string());
+    let ws_addr = env::var("WS_ADDR").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
+
     // // Загрузка сертификата и ключа для TLS
     // let cert_file = &mut BufReader::new(fs::File::open("cert.pem")?);
     // let key_file = &mut BufReader::new(fs::File::open("key.pem")?);
@@ -61,7 +77,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // let acceptor = TlsAcceptor::from(Arc::new(config));
 
+    // планировщик отложенных и самоудаляющихся сообщений
+    tokio::spawn(message_service::run_scheduler(backplane.clone(), db_pool.clone()));
+
+    // доставка вебхуков групповых чатов
+    tokio::spawn(webhook_service::run_worker(db_pool.clone()));
+
     let http_db_pool = Arc::clone(&db_pool);
+    let http_backplane = backplane.clone();
+    let http_filters = filters.clone();
     tokio::spawn(async move {
         HttpServer::new(move || {
             let cors = Cors::default()
@@ -80,27 +104,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             App::new()
                 .wrap(cors)
                 .app_data(web::Data::new(http_db_pool.clone()))
+                .app_data(web::Data::new(http_backplane.clone()))
+                .app_data(web::Data::new(http_filters.clone()))
                 .route("/register", web::post().to(auth::register))
                 .route("/login", web::post().to(auth::login))
+                .route("/refresh", web::post().to(auth::refresh))
+                .route("/logout", web::post().to(auth::logout))
+                .route("/logout/all", web::post().to(auth::logout_all))
+                .route("/sessions", web::get().to(session::get_all))
+                .route("/sessions/{id}", web::delete().to(session::revoke))
                 .route("/chats", web::post().to(chat::create))
                 .route("/chats", web::delete().to(chat::delete))
                 .route("/chats", web::get().to(chat::get_all))
+                .route("/chats/{id}/export", web::get().to(chat::export))
+                .route("/chats/direct/{username}/export", web::get().to(chat::export_direct))
+                .route("/chats/{id}/messages", web::post().to(bot::post_message))
+                .route("/chats/{id}/webhooks", web::post().to(webhook::create))
+                .route("/chats/{id}/webhooks", web::get().to(webhook::get_all))
+                .route("/chats/{id}/webhooks/{webhook_id}", web::delete().to(webhook::delete))
+                .route("/bots", web::post().to(bot::create))
+                .route("/audit", web::get().to(audit::get_all))
+                .route("/users/me/chats", web::get().to(user_handlers::my_chats))
+                .route("/protocol", web::get().to(protocol_handlers::info))
         })
-        .bind("127.0.0.1:8081")
+        .bind(http_addr)
         .unwrap()
         .run()
         .await
         .unwrap();
     });
 
-    // Создаем TCP-слушатель на порту 8080
-    let listener = TcpListener::bind("127.0.0.1:8080").await?;
-    info!("Сервер запущен на 127.0.0.1:8080");
-
-    // создаем канал для рассылки сообщений
-    let (tx, _) = broadcast::channel(32);
+    // Создаем TCP-слушатель (по умолчанию на порту 8080)
+    let listener = TcpListener::bind(&ws_addr).await?;
+    info!("Сервер запущен на {}", ws_addr);
 
-    // глобальное состояние сервера
+    // клиенты, подключенные к этому экземпляру сервера
     let clients: Clients = Arc::new(Mutex::new(HashMap::new()));
 
     loop {
@@ -118,40 +156,62 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         //     }
         // };
 
-        // Принимаем WebSocket-соединение
-        let mut ws_stream = match accept_async(stream).await {
+        // Принимаем WebSocket-соединение, кодировка выбирается через подпротокол
+        let mut encoding = Encoding::Json;
+        // тип ошибки задан интерфейсом tungstenite
+        #[allow(clippy::result_large_err)]
+        let negotiate = |request: &Request, mut response: Response| -> Result<Response, ErrorResponse> {
+            let protocols = request
+                .headers()
+                .get("Sec-WebSocket-Protocol")
+                .and_then(|value| value.to_str().ok());
+            if let Some(selected) = protocols.and_then(Encoding::negotiate) {
+                encoding = selected;
+                response
+                    .headers_mut()
+                    .insert("Sec-WebSocket-Protocol", HeaderValue::from_static(selected.protocol()));
+            }
+            Ok(response)
+        };
+        let mut ws_stream = match accept_hdr_async(stream, negotiate).await {
             Ok(ws_stream) => ws_stream,
             Err(e) => {
                 error!("Ошибка при установке WebSocket-соединения: {}", e);
                 continue;
             }
         };
+        debug!("Кодировка сообщений для {}: {:?}", addr, encoding);
 
         // Клонируем состояние для каждой задачи
         let clients = clients.clone();
         let db_pool = db_pool.clone();
-        let tx = tx.clone();
+        let backplane = backplane.clone();
+        let filters = filters.clone();
+        let commands = commands.clone();
 
         // Обрабатываем подключение в отдельной задаче
         tokio::spawn(async move {
             // let mut buffer = [0; 1024]; // Буфер для чтения данных
             let mut username = None;
+            // адрес клиента для журнала аудита
+            let ip = addr.ip().to_string();
+            // счетчики непрочитанных, загружаются после входа
+            let mut unread: Option<UnreadTracker> = None;
+            // сессия, под которой вошел клиент
+            let mut session_id = None;
 
             // подписываемся на получение сообщений
-            let mut rx = tx.subscribe();
+            let mut rx = backplane.subscribe();
 
             // отправляем историю сообщений новому клиенту
             let history = messages::load_history(&db_pool, 10).await.unwrap_or_default();
-            for (sender, content) in history {
-                let message = Message::ReceiveMessage {
-                    sender: sender.clone(),
-                    content: content.clone(),
-                };
-                if let Err(e) = ws_stream.send(WsMessage::Text(serde_json::to_string(&message).unwrap().into())).await {
+            for stored in history {
+                let message = Message::from(stored);
+                if let Err(e) = ws_stream.send(encoding.encode(&message).unwrap()).await {
                     error!("Ошибка отправки истории {}", e);
                     return;
                 }
-                // send_massage(&mut ws_stream, &message).await;
+                // send_massage(&mut ws_stream, encoding, &message).await;
             }
 
             loop {
@@ -162,72 +222,132 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                             // Успешно прочитано сообщение
                             Some(Ok(message)) => {
                                 match message {
-                                    WsMessage::Text(text) => {
-                                        debug!("Получено сообщение от клиента: {}", text);
-
-                                        // Десериализуем JSON в структуру Message
-                                        let message: Message = match serde_json::from_str(&text) {
-                                            Ok(msg) => msg,
-                                            Err(e) => {
-                                                error!("Ошибка десериализации JSON: {}", e);
+                                    frame @ (WsMessage::Text(_) | WsMessage::Binary(_)) => {
+                                        debug!("Получено сообщение от клиента: {}", frame);
+
+                                        // Десериализуем JSON или MessagePack в структуру Message
+                                        let message: Message = match codec::decode(&frame) {
+                                            Some(Ok(msg)) => msg,
+                                            Some(Err(e)) => {
+                                                error!("Ошибка десериализации сообщения: {}", e);
                                                 continue;
                                             }
+                                            None => continue,
                                         };
+                                        if let Err(e) = message.validate() {
+                                            send_massage(&mut ws_stream, encoding, &Message::ErrorMessage {
+                                                error: e.to_string(),
+                                            }).await;
+                                            continue;
+                                        }
 
                                         // Обрабатываем сообщение
                                         match message {
-                                            Message::Join { username: new_username } => {
+                                            Message::Join { username: new_username, token } => {
                                                 info!("Клиент {} клиент пытается присоединиться", new_username);
 
-                                                // Проверяем свободно ли имя
-                                                let mut clients_lock = clients.lock().await;
-                                                if clients_lock.contains_key(&new_username) {
-                                                    drop(clients_lock);
+                                                // проверяем токен доступа, выданный при входе
+                                                let session = match session_service::authenticate(&db_pool, &token).await {
+                                                    Ok(session) if session.username == new_username => session,
+                                                    _ => {
+                                                        send_massage(&mut ws_stream, encoding, &Message::ErrorMessage {
+                                                            error: ServerError::InvalidToken.to_string(),
+                                                        }).await;
+                                                        warn!("Клиент {} попытался присоединиться с недействительным токеном", new_username);
+                                                        break;
+                                                    }
+                                                };
+
+                                                // заблокированные глобально пользователи не могут войти
+                                                let banned = restrictions::find_active(&db_pool, &new_username, None)
+                                                    .await
+                                                    .map(|active| active.contains(&RestrictionKind::Ban))
+                                                    .unwrap_or(false);
+                                                if banned {
+                                                    send_massage(&mut ws_stream, encoding, &Message::ErrorMessage {
+                                                        error: ServerError::UserBanned.to_string(),
+                                                    }).await;
+                                                    warn!("Заблокированный пользователь {} попытался присоединиться", new_username);
+                                                    break;
+                                                }
 
+                                                // повторный вход в рамках одного подключения
+                                                if username.is_some() {
                                                     let error_message = Message::ErrorMessage {
-                                                        error: format!("Имя {} уже занято", new_username),
+                                                        error: "Вы уже присоединились к чату".to_string(),
                                                     };
-                                                    send_massage(&mut ws_stream, &error_message).await;
-                                                    warn!("Клиент {} попытался присоединиться с занятым именем", new_username);
-                                                    break;
+                                                    send_massage(&mut ws_stream, encoding, &error_message).await;
+                                                    continue;
                                                 }
 
-                                                // добавляем клиента в список
-                                                clients_lock.insert(new_username.clone(), tx.clone());
-                                                drop(clients_lock);
+                                                // у пользователя может быть несколько устройств одновременно
+                                                let first_device = presence::register_connection(&clients, &new_username).await;
 
                                                 username = Some(new_username.clone());
+                                                session_id = Some(session.id);
 
                                                 // отправляем приветственное сообщение
-                                                let response = Message::ReceiveMessage {
-                                                    sender: "Server".to_string(),
-                                                    content: format!("Добро пожаловать {}!", new_username),
-                                                };
-                                                send_massage(&mut ws_stream, &response).await;
+                                                let response = Message::server(format!("Добро пожаловать {}!", new_username));
+                                                send_massage(&mut ws_stream, encoding, &response).await;
+
+                                                // отметки о прочтении для синхронизации нового устройства
+                                                match read_markers::get_all(&db_pool, &new_username).await {
+                                                    Ok(markers) => {
+                                                        send_massage(&mut ws_stream, encoding, &Message::ReadMarkers { markers }).await;
+                                                    }
+                                                    Err(e) => error!("Ошибка загрузки отметок о прочтении {}", e),
+                                                }
+
+                                                // непрочитанные сообщения во всех чатах пользователя
+                                                match UnreadTracker::load(&db_pool, &new_username).await {
+                                                    Ok((tracker, counts)) => {
+                                                        send_massage(&mut ws_stream, encoding, &counts).await;
+                                                        unread = Some(tracker);
+                                                    }
+                                                    Err(e) => error!("Ошибка подсчета непрочитанных сообщений {}", e),
+                                                }
 
                                                 // уведомляем других участников о новом клиенте
-                                                let notification = Message::ReceiveMessage {
-                                                    sender: "Server".to_string(),
-                                                    content: format!("{} присоединился к чату", new_username),
-                                                };
-                                                let notification_json = serde_json::to_string(&notification).unwrap();
-                                                tx.send(notification_json).unwrap();
+                                                if first_device {
+                                                    let notification = Message::server(format!("{} присоединился к чату", new_username));
+                                                    publish(&backplane, Event::Global {
+                                                        payload: serde_json::to_string(&notification).unwrap(),
+                                                    }).await;
+                                                }
                                             }
-                                            Message::SendMessage { content } => {
+                                            Message::SendMessage { content, options } => {
                                                 if let Some(sender) = &username {
-                                                    info!("Получено сообщение от {}: {}", sender, content);
-                                                    // сохраняем сообщение в базу данных
-                                                    if let Err(e) = messages::save_message(&db_pool, sender, &content).await {
-                                                        error!("Ошибка загрузки сообщения в базу данных: {}", e);
+                                                    let context = CommandContext {
+                                                        db_pool: &db_pool,
+                                                        backplane: &backplane,
+                                                        filters: &filters,
+                                                        clients: &clients,
+                                                        sender,
+                                                        chat_id: None,
+                                                        ip: Some(ip.as_str()),
                                                     };
-                                                    let message = Message::ReceiveMessage {
-                                                        sender: sender.clone(),
-                                                        content: content.clone(),
-                                                    };
-                                                    let message_json = serde_json::to_string(&message).unwrap();
+                                                    if let Some(result) = commands.execute(&context, &content).await {
+                                                        if let Some(response) = command_response(result) {
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        continue;
+                                                    }
+                                                    let content = commands::unescape(&content);
 
-                                                    if let Err(e) = tx.send(message_json) {
-                                                        error!("Ошибка отправки в канал: {}", e);
+                                                    info!("Получено сообщение от {}: {}", sender, content);
+                                                    match message_service::send_message_to_general_chat(&backplane, &db_pool, &filters, sender, content, &options).await {
+                                                        Ok(Some(id)) => {
+                                                            let response = Message::server(format!("Сообщение ID: {} будет отправлено {:?}", id, options.send_at));
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        Ok(None) => {}
+                                                        Err(e) => {
+                                                            error!("Ошибка отправки сообщения в общий чат {}", e);
+                                                            let response = Message::ErrorMessage {
+                                                                error: e.to_string(),
+                                                            };
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
                                                     }
                                                 }
                                             }
@@ -235,18 +355,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                                 if let Some(sender) = &username {
                                                     info!("Клиент {} покидает чат", sender);
 
-                                                    // оповещаем других участников о выходе клиента
-                                                    let notification = Message::ReceiveMessage {
-                                                        sender: "Server".to_string(),
-                                                        content: format!("{} покинул чат", sender),
-                                                    };
-                                                    let notification_json = serde_json::to_string(&notification).unwrap();
-                                                    tx.send(notification_json).unwrap();
-
-                                                    // удаляем клиента из списка
-                                                    let mut clients_lock = clients.lock().await;
-                                                    clients_lock.remove(sender);
-                                                    drop(clients_lock);
+                                                    // удаляем подключение из списка, другие устройства остаются в чате
+                                                    if presence::unregister_connection(&clients, sender).await {
+                                                        // оповещаем других участников о выходе клиента
+                                                        let notification = Message::server(format!("{} покинул чат", sender));
+                                                        publish(&backplane, Event::Global {
+                                                            payload: serde_json::to_string(&notification).unwrap(),
+                                                        }).await;
+                                                    }
+                                                    username = None;
 
                                                     // завершение задачи для этого клиента
                                                     break;
@@ -256,80 +373,223 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                                                 if let Some(sender) = &username {
                                                     info!("Приватное сообщение от {} для {}: {}", sender, recipient, content);
 
-                                                    // находим получателя
-                                                    let clients_lock = clients.lock().await;
-                                                    if let Some(recipient_sender) = clients_lock.get(&recipient) {
-                                                        // отправляем сообщение получателю
-                                                        let private_message = Message::ReceivePrivateMessage {
-                                                            sender: sender.clone(),
-                                                            content: format!("[Приватно] {}", content),
-                                                        };
-                                                        let private_message_json = serde_json::to_string(&private_message).unwrap();
-                                                        if let Err(e) = recipient_sender.send(private_message_json) {
-                                                            error!("Ошибка отправки приватного сообщения клиенту {}: {}", recipient, e);
+                                                    // получатель может быть подключен к другому экземпляру сервера,
+                                                    // поэтому проверяем только его существование
+                                                    let recipient_exists = matches!(user::find_user_by_username(&db_pool, &recipient).await, Ok(Some(_)));
+                                                    if recipient_exists {
+                                                        // переписка сохраняется, чтобы ее можно было выгрузить
+                                                        let saved = async {
+                                                            let content = moderation::moderate(&db_pool, &filters, sender, None, &content).await?;
+                                                            private_messages::save(&db_pool, sender, &recipient, &content).await?;
+                                                            Ok::<_, ServerError>(content)
+                                                        }.await;
+                                                        match saved {
+                                                            Ok(content) => {
+                                                                // отправляем сообщение получателю
+                                                                let private_message = Message::ReceivePrivateMessage {
+                                                                    sender: sender.clone(),
+                                                                    recipient: recipient.clone(),
+                                                                    content: format!("[Приватно] {}", content),
+                                                                };
+                                                                let payload = serde_json::to_string(&private_message).unwrap();
+                                                                // копия для остальных устройств отправителя
+                                                                if recipient != *sender {
+                                                                    publish(&backplane, Event::Private {
+                                                                        recipient: sender.clone(),
+                                                                        payload: payload.clone(),
+                                                                    }).await;
+                                                                }
+                                                                publish(&backplane, Event::Private {
+                                                                    recipient: recipient.clone(),
+                                                                    payload,
+                                                                }).await;
+                                                            }
+                                                            Err(e) => {
+                                                                let error_message = Message::ErrorMessage {
+                                                                    error: e.to_string(),
+                                                                };
+                                                                send_massage(&mut ws_stream, encoding, &error_message).await;
+                                                            }
                                                         }
                                                     } else {
-                                                        // отправитель не найден
-                                                        drop(clients_lock);
+                                                        // получатель не найден
                                                         let error_message = Message::ErrorMessage {
                                                             error: format!("Пользователь {} не найден", recipient),
                                                         };
-                                                        send_massage(&mut ws_stream, &error_message).await;
+                                                        send_massage(&mut ws_stream, encoding, &error_message).await;
                                                         warn!("Клиент {} попытался отправить сообщение не существующему пользователю {}", sender, recipient);
                                                     };
                                                 };
 
                                             }
-                                            Message::AddMemberToGroupChat { chat_id, username } => {
-                                                match  group_chat::add_member(&db_pool, chat_id, &username).await {
-                                                    Ok(_) => {
-                                                        let response = Message::ReceiveMessage {
-                                                            sender: "Server".to_string(),
-                                                            content: format!("Участник '{}' успешно добавлен в групповой чат ID: {}", username, chat_id),
-                                                        };
-                                                        send_massage(&mut ws_stream, &response).await;
-                                                    }
-                                                    Err(e) => {
-                                                        let response = Message::ErrorMessage {
-                                                            error: e.to_string(),
-                                                        };
-                                                        send_massage(&mut ws_stream, &response).await;
+                                            Message::AddMemberToGroupChat { chat_id, username: member } => {
+                                                // добавлять может только вошедший участник чата, как и командой /invite
+                                                if let Some(requester) = &username {
+                                                    match chat_service::invite_member(&db_pool, chat_id, requester, &member).await {
+                                                        Ok(_) => {
+                                                            let record = AuditRecord::new(AuditEventKind::MemberAdded)
+                                                                .actor(requester)
+                                                                .target(&member)
+                                                                .chat(chat_id)
+                                                                .ip(Some(ip.as_str()));
+                                                            audit_service::record(&db_pool, record).await;
+
+                                                            let response = Message::server(format!("Участник '{}' успешно добавлен в групповой чат ID: {}", member, chat_id));
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        Err(e) => {
+                                                            audit_service::record_denial(&db_pool, &e, Some(requester.as_str()), Some(chat_id), Some(ip.as_str()), "добавление участника").await;
+                                                            let response = Message::ErrorMessage {
+                                                                error: e.to_string(),
+                                                            };
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
                                                     }
+                                                } else {
+                                                    warn!("Попытка добавить участника без авторизации");
+                                                    let response = Message::ErrorMessage {
+                                                        error: ServerError::PermissionDenied.to_string(),
+                                                    };
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
                                                 }
                                             }
-                                            Message::SendMessageToGroupChat { chat_id, content } => {
+                                            Message::SendMessageToGroupChat { chat_id, content, options } => {
                                                 if let Some(sender_username) = &username {
-                                                    match send_message_to_group_chat(&mut ws_stream, &db_pool, chat_id, sender_username, &content).await {
-                                                        Ok(_) => {
+                                                    let context = CommandContext {
+                                                        db_pool: &db_pool,
+                                                        backplane: &backplane,
+                                                        filters: &filters,
+                                                        clients: &clients,
+                                                        sender: sender_username,
+                                                        chat_id: Some(chat_id),
+                                                        ip: Some(ip.as_str()),
+                                                    };
+                                                    if let Some(result) = commands.execute(&context, &content).await {
+                                                        if let Some(response) = command_response(result) {
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        continue;
+                                                    }
+                                                    let content = commands::unescape(&content);
+
+                                                    match message_service::send_message_to_group_chat(&backplane, &db_pool, &filters, chat_id, sender_username, content, &options).await {
+                                                        Ok(Some(id)) => {
+                                                            info!("Сообщение ID: {} запланировано в групповой чат ID: {}", id, chat_id);
+                                                            let response = Message::server(format!("Сообщение ID: {} будет отправлено {:?}", id, options.send_at));
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        Ok(None) => {
                                                             info!("Сообщение '{}' успешно отправлено в групповой чат ID: {}", content, chat_id);
                                                         }
                                                         Err(e) => {
+                                                            audit_service::record_denial(&db_pool, &e, Some(sender_username.as_str()), Some(chat_id), Some(ip.as_str()), "сообщение в групповой чат").await;
                                                             error!("Ошибка отправки сообщения в групповой чат {}", e);
                                                             let response = Message::ErrorMessage {
                                                                 error: e.to_string(),
                                                             };
-                                                            send_massage(&mut ws_stream, &response).await;
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
                                                         }
                                                     }
                                                 } else {
                                                     warn!("Попытка отправить сообщение без авторизации");
                                                 }
                                             }
-                                            Message::RemoveMemberFromGroupChat { chat_id, username, requester } => {
-                                                match group_chat::remove_member(&db_pool, chat_id, &username, &requester).await {
-                                                    Ok(_) => {
-                                                        let response = Message::ReceiveMessage {
-                                                            sender: "Server".to_string(),
-                                                            content: format!("Участник {} удален из группового чата ID: {}", username, chat_id),
+                                            Message::RemoveMemberFromGroupChat { chat_id, username: member } => {
+                                                // удаляет тот, кто вошел в этом подключении, а не имя из кадра
+                                                if let Some(requester) = &username {
+                                                    match group_chat::remove_member(&db_pool, chat_id, &member, requester).await {
+                                                        Ok(_) => {
+                                                            let record = AuditRecord::new(AuditEventKind::MemberRemoved)
+                                                                .actor(requester)
+                                                                .target(&member)
+                                                                .chat(chat_id)
+                                                                .ip(Some(ip.as_str()));
+                                                            audit_service::record(&db_pool, record).await;
+
+                                                            let response = Message::server(format!("Участник {} удален из группового чата ID: {}", member, chat_id));
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                        Err(e) => {
+                                                            audit_service::record_denial(&db_pool, &e, Some(requester.as_str()), Some(chat_id), Some(ip.as_str()), "удаление участника").await;
+                                                            error!("Ошибка удаления пользователя из группового чата {}", e);
+                                                            let response = Message::ErrorMessage {
+                                                                error: e.to_string(),
+                                                            };
+                                                            send_massage(&mut ws_stream, encoding, &response).await;
+                                                        }
+                                                    }
+                                                } else {
+                                                    warn!("Попытка удалить участника без авторизации");
+                                                }
+                                            }
+                                            Message::React { message_id, emoji } => {
+                                                if let Some(sender) = &username {
+                                                    if let Err(e) = message_service::update_reaction(&backplane, &db_pool, message_id, sender, &emoji, true).await {
+                                                        audit_service::record_denial(&db_pool, &e, Some(sender.as_str()), None, Some(ip.as_str()), "реакция на сообщение").await;
+                                                        error!("Ошибка добавления реакции на сообщение {}: {}", message_id, e);
+                                                        let response = Message::ErrorMessage {
+                                                            error: e.to_string(),
+                                                        };
+                                                        send_massage(&mut ws_stream, encoding, &response).await;
+                                                    }
+                                                }
+                                            }
+                                            Message::Unreact { message_id, emoji } => {
+                                                if let Some(sender) = &username {
+                                                    if let Err(e) = message_service::update_reaction(&backplane, &db_pool, message_id, sender, &emoji, false).await {
+                                                        audit_service::record_denial(&db_pool, &e, Some(sender.as_str()), None, Some(ip.as_str()), "реакция на сообщение").await;
+                                                        error!("Ошибка удаления реакции с сообщения {}: {}", message_id, e);
+                                                        let response = Message::ErrorMessage {
+                                                            error: e.to_string(),
                                                         };
-                                                        send_massage(&mut ws_stream, &response).await;
+                                                        send_massage(&mut ws_stream, encoding, &response).await;
                                                     }
-                                                    Err(e) => {
-                                                        error!("Ошибка удаления пользователя из группового чата {}", e);
+                                                }
+                                            }
+                                            Message::LoadThread { root_id } => {
+                                                if let Some(requester) = &username {
+                                                    let response = match message_service::load_thread(&db_pool, root_id, requester).await {
+                                                        Ok(messages) => Message::Thread { root_id, messages },
+                                                        Err(e) => {
+                                                            audit_service::record_denial(&db_pool, &e, Some(requester.as_str()), None, Some(ip.as_str()), "загрузка ветки").await;
+                                                            error!("Ошибка загрузки ветки сообщений {}: {}", root_id, e);
+                                                            Message::ErrorMessage {
+                                                                error: e.to_string(),
+                                                            }
+                                                        }
+                                                    };
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
+                                                }
+                                            }
+                                            Message::MuteUser { username: target, chat_id } => {
+                                                if let Some(response) = handle_restriction(&db_pool, username.as_deref(), &ip, &target, chat_id, RestrictionKind::Mute, true).await {
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
+                                                }
+                                            }
+                                            Message::UnmuteUser { username: target, chat_id } => {
+                                                if let Some(response) = handle_restriction(&db_pool, username.as_deref(), &ip, &target, chat_id, RestrictionKind::Mute, false).await {
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
+                                                }
+                                            }
+                                            Message::BanUser { username: target, chat_id } => {
+                                                if let Some(response) = handle_restriction(&db_pool, username.as_deref(), &ip, &target, chat_id, RestrictionKind::Ban, true).await {
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
+                                                }
+                                            }
+                                            Message::UnbanUser { username: target, chat_id } => {
+                                                if let Some(response) = handle_restriction(&db_pool, username.as_deref(), &ip, &target, chat_id, RestrictionKind::Ban, false).await {
+                                                    send_massage(&mut ws_stream, encoding, &response).await;
+                                                }
+                                            }
+                                            Message::MarkRead { chat_id, message_id } => {
+                                                if let Some(reader) = &username {
+                                                    if let Err(e) = message_service::mark_read(&backplane, &db_pool, reader, chat_id, message_id).await {
+                                                        audit_service::record_denial(&db_pool, &e, Some(reader.as_str()), chat_id, Some(ip.as_str()), "отметка о прочтении").await;
+                                                        error!("Ошибка сохранения отметки о прочтении {}", e);
                                                         let response = Message::ErrorMessage {
                                                             error: e.to_string(),
                                                         };
-                                                        send_massage(&mut ws_stream, &response).await;
+                                                        send_massage(&mut ws_stream, encoding, &response).await;
                                                     }
                                                 }
                                             }
@@ -354,14 +614,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
 
-                    // получаем сообщения из канала
+                    // получаем сообщения из шины
                     result = rx.recv() => {
                         match result {
-                            Ok(msg) => {
-                                if let Err(e) = ws_stream.send(WsMessage::Text(msg.into())).await {
+                            Ok(event) => {
+                                // сессия отозвана (выход на другом устройстве) - закрываем подключение
+                                if let Event::SessionsRevoked { session_ids } = &event {
+                                    if session_id.is_some_and(|id| session_ids.contains(&id)) {
+                                        info!("Сессия клиента {:?} отозвана, отключаем", username);
+                                        send_massage(&mut ws_stream, encoding, &Message::ErrorMessage {
+                                            error: "Сессия завершена".to_string(),
+                                        }).await;
+                                        let _ = ws_stream.close(None).await;
+                                        break;
+                                    }
+                                    continue;
+                                }
+
+                                let Some(payload) = event.payload().filter(|_| event.is_for(username.as_deref())) else {
+                                    continue;
+                                };
+                                let frame = match encoding.encode_json(payload) {
+                                    Ok(frame) => frame,
+                                    Err(e) => {
+                                        error!("Ошибка перекодирования сообщения: {}", e);
+                                        continue;
+                                    }
+                                };
+                                if let Err(e) = ws_stream.send(frame).await {
                                     error!("Ошибка записи: {}", e);
                                     break;
                                 } 
+
+                                // новое сообщение или отметка о прочтении меняют счетчик непрочитанных
+                                if let Some(tracker) = unread.as_mut() {
+                                    if let Ok(message) = serde_json::from_str::<Message>(payload) {
+                                        match tracker.on_event(&db_pool, &message).await {
+                                            Ok(Some(update)) => send_massage(&mut ws_stream, encoding, &update).await,
+                                            Ok(None) => {}
+                                            Err(e) => error!("Ошибка обновления счетчика непрочитанных {}", e),
+                                        }
+                                    }
+                                }
                             }
                             Err(e) => {
                                 error!("Ошибка получения из канала: {}", e);
@@ -373,32 +667,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             if let Some(username) = username {
-                let mut clients_lock = clients.lock().await;
-                clients_lock.remove(&username);
+                presence::unregister_connection(&clients, &username).await;
                 info!("Клиент {} отключился", username);
             }
         });
     }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-#[serde(tag = "type")] // Указываем поле `type` для различения типов сообщений
-enum Message {
-    Join { username: String }, // Клиент присоединяется к чату
-    SendMessage { content: String }, // Клиент отправляет сообщение
-    ReceiveMessage { sender: String, content: String }, // Сообщение для клиента,
-    SendPrivateMessage { recipient: String, content: String }, // Отправка приватных сообщений
-    ReceivePrivateMessage { sender: String, content: String }, // Получение приватных сообщений
-    Leave, // выход пользователя
-    ErrorMessage { error: String }, // Ответ об ошибке
-    AddMemberToGroupChat { chat_id: i32, username: String }, // добавить пользователя в групповой чат
-    SendMessageToGroupChat { chat_id: i32, content: String }, // отправить сообщение в группвой чат
-    ReceiveGroupChatMessage { chat_id: i32, sender: String, content: String }, // получение соощения из группового чата
-    RemoveMemberFromGroupChat { chat_id: i32, username: String, requester: String }, // удалить пользователя из чата
-}
-
-type Clients = Arc<Mutex<HashMap<String, broadcast::Sender<String>>>>;
-
 // async fn send_massage(stream: &mut tokio_rustls::server::TlsStream<tokio::net::TcpStream>, message: &Message) {
 //     let json_message = serde_json::to_string(message).unwrap();
 //     if let Err(e) = stream.write_all(json_message.as_bytes()).await {
@@ -406,28 +681,63 @@ type Clients = Arc<Mutex<HashMap<String, broadcast::Sender<String>>>>;
 //     }
 // }
 
-async fn send_massage(stream: &mut WebSocketStream<tokio::net::TcpStream>, message: &Message) {
-    let json_message = serde_json::to_string(message).unwrap();
-    if let Err(e) = stream.send(WsMessage::Text(json_message.into())).await {
+async fn send_massage(stream: &mut WebSocketStream<tokio::net::TcpStream>, encoding: Encoding, message: &Message) {
+    let frame = match encoding.encode(message) {
+        Ok(frame) => frame,
+        Err(e) => {
+            error!("Ошибка сериализации сообщения {}", e);
+            return;
+        }
+    };
+    if let Err(e) = stream.send(frame).await {
         error!("Ошибка записи {}", e);
     }
 }
 
-async fn send_message_to_group_chat(
-    stream: &mut WebSocketStream<tokio::net::TcpStream>,
-    db_pool: &DbPool,
-    chat_id: i32,
-    sender: &str,
-    content: &str,
-) -> AppResult<()> {
-    let members = group_chat::get_members(db_pool, chat_id).await?;
-    let message = Message::ReceiveGroupChatMessage {
-        chat_id, sender: sender.to_string(), content: content.to_string(),
-    };
+// ответ автору команды: результат или текст ошибки
+fn command_response(result: AppResult<Option<Message>>) -> Option<Message> {
+    match result {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Ошибка выполнения команды: {}", e);
+            Some(Message::ErrorMessage { error: e.to_string() })
+        }
+    }
+}
 
-    for _ in members {
-        send_massage(stream, &message).await;
+// публикация события в шину с логированием ошибки
+async fn publish(backplane: &SharedBackplane, event: Event) {
+    if let Err(e) = backplane.publish(event).await {
+        error!("Ошибка отправки в шину сообщений: {}", e);
     }
+}
 
-    Ok(())
+async fn handle_restriction(
+    db_pool: &DbPool,
+    requester: Option<&str>,
+    ip: &str,
+    target: &str,
+    chat_id: Option<i32>,
+    kind: RestrictionKind,
+    add: bool,
+) -> Option<Message> {
+    let Some(requester) = requester else {
+        warn!("Попытка изменить ограничения без авторизации");
+        return None;
+    };
+
+    let response = match moderation::update_restriction(db_pool, requester, target, chat_id, kind, add).await {
+        Ok(_) => Message::server(format!(
+            "Ограничение '{}' для {} {}",
+            kind.as_str(),
+            target,
+            if add { "установлено" } else { "снято" },
+        )),
+        Err(e) => {
+            audit_service::record_denial(db_pool, &e, Some(requester), chat_id, Some(ip), "изменение ограничений").await;
+            error!("Ошибка изменения ограничения для {}: {}", target, e);
+            Message::ErrorMessage { error: e.to_string() }
+        }
+    };
+    Some(response)
 }
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use super::{FilterDecision, MessageContext, MessageFilter};
+
+pub const DEFAULT_MAX_LENGTH: usize = 2000;
+pub const DEFAULT_SPAM_WINDOW_SECS: u64 = 10;
+
+// ограничение длины сообщения
+pub struct MaxLengthFilter {
+    max_length: usize,
+}
+
+impl MaxLengthFilter {
+    pub fn new(max_length: usize) -> Self {
+        MaxLengthFilter { max_length }
+    }
+}
+
+impl MessageFilter for MaxLengthFilter {
+    fn check(&self, message: &MessageContext) -> FilterDecision {
+        if message.content.trim().is_empty() {
+            return FilterDecision::Reject("Пустое сообщение".to_string());
+        }
+        if message.content.chars().count() > self.max_length {
+            return FilterDecision::Reject(format!("Сообщение длиннее {} символов", self.max_length));
+        }
+        FilterDecision::Allow
+    }
+}
+
+// замена запрещенных слов звездочками
+pub struct BannedWordsFilter {
+    words: Vec<String>,
+}
+
+impl BannedWordsFilter {
+    pub fn new(words: Vec<String>) -> Self {
+        BannedWordsFilter {
+            words: words.into_iter().map(|word| word.to_lowercase()).collect(),
+        }
+    }
+}
+
+impl MessageFilter for BannedWordsFilter {
+    fn check(&self, message: &MessageContext) -> FilterDecision {
+        let mut redacted = false;
+        let words: Vec<String> = message.content
+            .split(' ')
+            .map(|word| {
+                let normalized: String = word
+                    .chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase();
+                if self.words.contains(&normalized) {
+                    redacted = true;
+                    "*".repeat(word.chars().count())
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect();
+
+        if redacted {
+            FilterDecision::Redact(words.join(" "))
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+// запрет ссылок
+pub struct LinkFilter;
+
+impl MessageFilter for LinkFilter {
+    fn check(&self, message: &MessageContext) -> FilterDecision {
+        let has_link = message.content.split_whitespace().any(|word| {
+            let word = word.to_lowercase();
+            word.starts_with("http://") || word.starts_with("https://") || word.starts_with("www.")
+        });
+        if has_link {
+            FilterDecision::Reject("Ссылки запрещены".to_string())
+        } else {
+            FilterDecision::Allow
+        }
+    }
+}
+
+// запрет повторной отправки одного и того же текста в течение окна
+pub struct DuplicateSpamFilter {
+    window: Duration,
+    last_messages: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl DuplicateSpamFilter {
+    pub fn new(window_secs: u64) -> Self {
+        DuplicateSpamFilter {
+            window: Duration::from_secs(window_secs),
+            last_messages: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl MessageFilter for DuplicateSpamFilter {
+    fn check(&self, message: &MessageContext) -> FilterDecision {
+        let mut last_messages = self.last_messages.lock().unwrap();
+        let now = Instant::now();
+        // ключ учитывает чат, чтобы одинаковое сообщение можно было отправить в разные чаты
+        let key = format!("{}:{:?}", message.sender, message.chat_id);
+
+        if let Some((content, sent_at)) = last_messages.get(&key) {
+            if content == message.content && now.duration_since(*sent_at) < self.window {
+                return FilterDecision::Reject("Повторная отправка одного и того же сообщения".to_string());
+            }
+        }
+
+        last_messages.retain(|_, (_, sent_at)| now.duration_since(*sent_at) < self.window);
+        last_messages.insert(key, (message.content.to_string(), now));
+        FilterDecision::Allow
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message<'a>(sender: &'a str, chat_id: Option<i32>, content: &'a str) -> MessageContext<'a> {
+        MessageContext { sender, chat_id, content }
+    }
+
+    fn is_reject(decision: FilterDecision) -> bool {
+        matches!(decision, FilterDecision::Reject(_))
+    }
+
+    #[test]
+    fn max_length_filter() {
+        let filter = MaxLengthFilter::new(5);
+        assert_eq!(filter.check(&message("alice", None, "привет")), FilterDecision::Reject("Сообщение длиннее 5 символов".to_string()));
+        // длина считается в символах, а не в байтах
+        assert_eq!(filter.check(&message("alice", None, "йцуке")), FilterDecision::Allow);
+        assert!(is_reject(filter.check(&message("alice", None, "   "))));
+    }
+
+    #[test]
+    fn banned_words_filter() {
+        let filter = BannedWordsFilter::new(vec!["Спам".to_string()]);
+        assert_eq!(filter.check(&message("alice", None, "обычный текст")), FilterDecision::Allow);
+        assert_eq!(
+            filter.check(&message("alice", None, "это СПАМ! точно")),
+            FilterDecision::Redact("это ***** точно".to_string())
+        );
+    }
+
+    #[test]
+    fn link_filter() {
+        assert_eq!(LinkFilter.check(&message("alice", None, "без ссылок, http нет")), FilterDecision::Allow);
+        for content in ["смотри https://example.com", "HTTP://EXAMPLE.COM", "www.example.com"] {
+            assert!(is_reject(LinkFilter.check(&message("alice", None, content))), "{}", content);
+        }
+    }
+
+    #[test]
+    fn duplicate_spam_filter() {
+        let filter = DuplicateSpamFilter::new(60);
+        assert_eq!(filter.check(&message("alice", None, "привет")), FilterDecision::Allow);
+        assert!(is_reject(filter.check(&message("alice", None, "привет"))));
+        // другой чат, другой отправитель и другой текст не считаются повтором
+        assert_eq!(filter.check(&message("alice", Some(1), "привет")), FilterDecision::Allow);
+        assert_eq!(filter.check(&message("bob", None, "привет")), FilterDecision::Allow);
+        assert_eq!(filter.check(&message("alice", None, "пока")), FilterDecision::Allow);
+
+        // после окна повтор снова разрешен
+        let filter = DuplicateSpamFilter::new(0);
+        assert_eq!(filter.check(&message("alice", None, "привет")), FilterDecision::Allow);
+        assert_eq!(filter.check(&message("alice", None, "привет")), FilterDecision::Allow);
+    }
+}
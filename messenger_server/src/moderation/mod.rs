@@ -0,0 +1,180 @@
+use std::env;
+use tracing::{info, warn};
+use crate::db::{group_chat, restrictions, user};
+use crate::structs::RestrictionKind;
+use crate::types::{AppResult, DbPool, ServerError};
+
+pub mod filters;
+
+// решение фильтра по сообщению
+#[derive(Debug, PartialEq)]
+pub enum FilterDecision {
+    Allow, // пропустить без изменений
+    Redact(String), // заменить текст сообщения
+    Reject(String), // отклонить с указанной причиной
+}
+
+// проверяемое сообщение; chat_id = None для общего чата и приватных сообщений
+pub struct MessageContext<'a> {
+    pub sender: &'a str,
+    pub chat_id: Option<i32>,
+    pub content: &'a str,
+}
+
+pub trait MessageFilter: Send + Sync {
+    fn check(&self, message: &MessageContext) -> FilterDecision;
+}
+
+// цепочка фильтров, которые применяются по очереди
+pub struct FilterChain {
+    filters: Vec<Box<dyn MessageFilter>>,
+}
+
+impl FilterChain {
+    pub fn new(filters: Vec<Box<dyn MessageFilter>>) -> Self {
+        FilterChain { filters }
+    }
+
+    // Настройка через переменные окружения:
+    // MAX_MESSAGE_LENGTH, BANNED_WORDS (через запятую), BLOCK_LINKS, SPAM_WINDOW_SECS
+    pub fn from_env() -> Self {
+        let max_length = env::var("MAX_MESSAGE_LENGTH")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(filters::DEFAULT_MAX_LENGTH);
+        let banned_words: Vec<String> = env::var("BANNED_WORDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|word| word.trim().to_string())
+            .filter(|word| !word.is_empty())
+            .collect();
+        let block_links = env::var("BLOCK_LINKS").is_ok_and(|value| value == "true" || value == "1");
+        let spam_window = env::var("SPAM_WINDOW_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(filters::DEFAULT_SPAM_WINDOW_SECS);
+
+        let mut chain: Vec<Box<dyn MessageFilter>> = vec![Box::new(filters::MaxLengthFilter::new(max_length))];
+        if !banned_words.is_empty() {
+            chain.push(Box::new(filters::BannedWordsFilter::new(banned_words)));
+        }
+        if block_links {
+            chain.push(Box::new(filters::LinkFilter));
+        }
+        chain.push(Box::new(filters::DuplicateSpamFilter::new(spam_window)));
+
+        info!("Загружено {} фильтров сообщений", chain.len());
+        FilterChain::new(chain)
+    }
+
+    // возвращает итоговый текст сообщения или причину отказа
+    pub fn run(&self, sender: &str, chat_id: Option<i32>, content: &str) -> Result<String, String> {
+        let mut content = content.to_string();
+        for filter in &self.filters {
+            let message = MessageContext { sender, chat_id, content: &content };
+            match filter.check(&message) {
+                FilterDecision::Allow => {}
+                FilterDecision::Redact(redacted) => content = redacted,
+                FilterDecision::Reject(reason) => return Err(reason),
+            }
+        }
+        Ok(content)
+    }
+}
+
+// полная проверка перед сохранением и рассылкой: ограничения пользователя и фильтры
+pub async fn moderate(
+    pool: &DbPool,
+    filters: &FilterChain,
+    sender: &str,
+    chat_id: Option<i32>,
+    content: &str,
+) -> AppResult<String> {
+    let active = restrictions::find_active(pool, sender, chat_id).await?;
+    if active.contains(&RestrictionKind::Ban) {
+        warn!("Заблокированный пользователь {} пытается отправить сообщение (chat_id={:?})", sender, chat_id);
+        return Err(ServerError::UserBanned);
+    }
+    if active.contains(&RestrictionKind::Mute) {
+        warn!("Пользователь {} без права писать пытается отправить сообщение (chat_id={:?})", sender, chat_id);
+        return Err(ServerError::UserMuted);
+    }
+
+    filters.run(sender, chat_id, content).map_err(|reason| {
+        warn!("Сообщение от {} отклонено: {}", sender, reason);
+        ServerError::MessageRejected(reason)
+    })
+}
+
+// Ограничения в групповом чате выдает его создатель или администратор,
+// глобальные - только администратор
+pub async fn update_restriction(
+    db_pool: &DbPool,
+    requester: &str,
+    target: &str,
+    chat_id: Option<i32>,
+    kind: RestrictionKind,
+    add: bool,
+) -> AppResult<()> {
+    let allowed = user::is_admin(db_pool, requester).await? || match chat_id {
+        Some(chat_id) => group_chat::check_if_creator(db_pool, chat_id, requester).await?,
+        None => false,
+    };
+    if !allowed {
+        warn!("Пользователь {} не может изменять ограничения (chat_id={:?})", requester, chat_id);
+        return Err(ServerError::PermissionDenied);
+    }
+
+    if add {
+        restrictions::add(db_pool, target, chat_id, kind, requester).await?;
+        // заблокированный в чате пользователь перестает быть его участником
+        if let (Some(chat_id), RestrictionKind::Ban) = (chat_id, kind) {
+            if let Err(e) = group_chat::remove_member(db_pool, chat_id, target, requester).await {
+                warn!("Пользователь {} не удален из чата ID: {}: {}", target, chat_id, e);
+            }
+        }
+        Ok(())
+    } else {
+        restrictions::remove(db_pool, target, chat_id, kind).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    // считает вызовы, чтобы проверить, дошла ли до него цепочка
+    struct CountingFilter(Arc<AtomicUsize>);
+
+    impl MessageFilter for CountingFilter {
+        fn check(&self, _message: &MessageContext) -> FilterDecision {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            FilterDecision::Allow
+        }
+    }
+
+    #[test]
+    fn chain_applies_redactions_in_order() {
+        let chain = FilterChain::new(vec![
+            Box::new(filters::BannedWordsFilter::new(vec!["спам".to_string()])),
+            Box::new(filters::MaxLengthFilter::new(20)),
+        ]);
+        assert_eq!(chain.run("alice", None, "это спам"), Ok("это ****".to_string()));
+    }
+
+    #[test]
+    fn chain_stops_at_first_rejection() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let chain = FilterChain::new(vec![
+            Box::new(filters::LinkFilter),
+            Box::new(CountingFilter(calls.clone())),
+        ]);
+
+        assert_eq!(chain.run("alice", None, "www.example.com"), Err("Ссылки запрещены".to_string()));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(chain.run("alice", None, "привет").is_ok());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+// количество подключений (устройств) каждого пользователя на этом экземпляре сервера
+pub type Clients = Arc<Mutex<HashMap<String, usize>>>;
+
+// учет нового подключения, true - если это первое устройство пользователя
+pub async fn register_connection(clients: &Clients, username: &str) -> bool {
+    let mut clients_lock = clients.lock().await;
+    let connections = clients_lock.entry(username.to_string()).or_insert(0);
+    *connections += 1;
+    *connections == 1
+}
+
+// снятие подключения с учета, true - если отключилось последнее устройство пользователя
+pub async fn unregister_connection(clients: &Clients, username: &str) -> bool {
+    let mut clients_lock = clients.lock().await;
+    match clients_lock.get_mut(username) {
+        Some(connections) if *connections > 1 => {
+            *connections -= 1;
+            false
+        }
+        Some(_) => {
+            clients_lock.remove(username);
+            true
+        }
+        None => false,
+    }
+}
+
+// пользователи, подключенные к этому экземпляру сервера, по алфавиту
+pub async fn online_users(clients: &Clients) -> Vec<String> {
+    let mut users: Vec<String> = clients.lock().await.keys().cloned().collect();
+    users.sort();
+    users
+}
@@ -0,0 +1,2 @@
+// кадры WebSocket описаны в общем крейте протокола, чтобы клиенты не копировали их
+pub use messenger_protocol::Message;
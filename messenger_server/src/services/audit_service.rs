@@ -0,0 +1,47 @@
+use tracing::{error, warn};
+use crate::db::{audit, user};
+use crate::structs::{AuditEvent, AuditEventKind, AuditFilter, AuditRecord};
+use crate::types::{AppResult, DbPool, ServerError};
+
+// размер страницы журнала по умолчанию и наибольший допустимый
+pub const DEFAULT_PAGE_SIZE: i64 = 100;
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+// Запись события. Ошибка записи не должна прерывать основное действие,
+// поэтому она только логируется
+pub async fn record(pool: &DbPool, record: AuditRecord) {
+    if let Err(e) = audit::insert(pool, &record).await {
+        error!("Событие аудита {:?} не записано: {}", record, e);
+    }
+}
+
+// Отказ в доступе записывается в журнал; остальные ошибки - нет
+pub async fn record_denial(
+    pool: &DbPool,
+    error: &ServerError,
+    actor: Option<&str>,
+    chat_id: Option<i32>,
+    ip: Option<&str>,
+    action: &str,
+) {
+    if !matches!(error, ServerError::PermissionDenied) {
+        return;
+    }
+    warn!("Отказ в доступе: {:?} -> {} (chat_id={:?})", actor, action, chat_id);
+
+    let mut entry = AuditRecord::new(AuditEventKind::PermissionDenied).ip(ip).details(action);
+    entry.actor = actor.map(str::to_string);
+    entry.chat_id = chat_id;
+    record(pool, entry).await;
+}
+
+// журнал доступен только администраторам
+pub async fn list(pool: &DbPool, requester: &str, ip: Option<&str>, filter: &AuditFilter) -> AppResult<Vec<AuditEvent>> {
+    if !user::is_admin(pool, requester).await? {
+        record_denial(pool, &ServerError::PermissionDenied, Some(requester), None, ip, "просмотр журнала аудита").await;
+        return Err(ServerError::PermissionDenied);
+    }
+
+    let limit = filter.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    audit::list(pool, filter, limit).await
+}
@@ -2,8 +2,11 @@ use crate::types::{AppResult, DbPool, ServerError};
 use bcrypt::verify;
 use tracing::error;
 use crate::db::user;
+use messenger_protocol::validation::{validate_password, validate_username};
 
 pub async fn register_user(pool: &DbPool, username: &str, password: &str) -> AppResult<()> {
+    validate_username(username)?;
+    validate_password(password)?;
     if user::find_user_by_username(pool, username).await?.is_some() {
         return Err(ServerError::UserExists);
     }
@@ -0,0 +1,51 @@
+use bcrypt::{hash, DEFAULT_COST};
+use tracing::{error, info};
+use crate::db::{bots, group_chat, user};
+use crate::services::session_service::{generate_token, hash_token};
+use crate::types::{AppResult, DbPool, ServerError};
+
+// имена ботов отличаются от имен людей суффиксом
+pub const BOT_SUFFIX: &str = "_bot";
+
+// Создание бота. Возвращает имя бота и API-токен, который показывается только один раз
+pub async fn create_bot(pool: &DbPool, owner: &str, name: &str) -> AppResult<(String, String)> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ServerError::InvalidArgument(
+            "Имя бота может содержать только латинские буквы, цифры и _".to_string(),
+        ));
+    }
+
+    let username = if name.ends_with(BOT_SUFFIX) { name.to_string() } else { format!("{}{}", name, BOT_SUFFIX) };
+    if user::find_user_by_username(pool, &username).await?.is_some() {
+        return Err(ServerError::UserExists);
+    }
+
+    // пароль никому не известен, бот работает только по токену
+    let password_hash = hash(generate_token(), DEFAULT_COST).map_err(|e| {
+        error!("Ошибка хэширования пароля: {}", e);
+        ServerError::BcryptError(e)
+    })?;
+    let token = generate_token();
+    bots::create(pool, &username, owner, &password_hash, &hash_token(&token)).await?;
+
+    Ok((username, token))
+}
+
+// имя бота по API-токену
+pub async fn authenticate(pool: &DbPool, token: &str) -> AppResult<String> {
+    bots::find_by_token(pool, &hash_token(token))
+        .await?
+        .ok_or(ServerError::InvalidToken)
+}
+
+// писать в групповой чат бот может, только если его туда добавили
+pub async fn check_can_post(pool: &DbPool, bot: &str, chat_id: i32) -> AppResult<()> {
+    if group_chat::get_by_id(pool, chat_id).await?.is_none() {
+        return Err(ServerError::ChatNotFound);
+    }
+    if !group_chat::is_member(pool, chat_id, bot).await? {
+        info!("Бот {} не состоит в чате ID: {}", bot, chat_id);
+        return Err(ServerError::PermissionDenied);
+    }
+    Ok(())
+}
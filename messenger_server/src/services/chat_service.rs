@@ -1,7 +1,116 @@
-use crate::{db::group_chat, types::{AppResult, DbPool, ServerError}, structs::Chat};
+use crate::{db::{group_chat, messages, private_messages, restrictions, user}, services::message_service, types::{AppResult, DbPool, ServerError}, structs::{Chat, ChatExport, DirectChatExport, ExportedMessage, RestrictionKind}};
+use futures_util::stream::{self, Stream};
+use serde::Serialize;
 use tracing::error;
+use messenger_protocol::validation::validate_chat_name;
+
+// количество сообщений, загружаемых из БД за один шаг потоковой выгрузки
+const EXPORT_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ExportFormat {
+    Json,
+    Text,
+}
+
+impl ExportFormat {
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "json" => Some(ExportFormat::Json),
+            "txt" => Some(ExportFormat::Text),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json; charset=utf-8",
+            ExportFormat::Text => "text/plain; charset=utf-8",
+        }
+    }
+
+    // JSON-выгрузка - заголовок (ChatExport или DirectChatExport) с массивом messages в конце,
+    // сообщения между началом и концом дописываются постранично
+    fn opening<T: Serialize>(&self, header: &T) -> String {
+        match self {
+            ExportFormat::Json => {
+                let json = serde_json::to_string(header).unwrap();
+                json.strip_suffix("]}").unwrap_or(&json).to_string()
+            }
+            ExportFormat::Text => String::new(),
+        }
+    }
+
+    fn closing(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "]}",
+            ExportFormat::Text => "",
+        }
+    }
+
+    fn format_message(&self, message: &ExportedMessage, first: bool) -> String {
+        match self {
+            ExportFormat::Json => {
+                let json = serde_json::to_string(message).unwrap();
+                if first { json } else { format!(",{}", json) }
+            }
+            ExportFormat::Text => format!(
+                "[{}] {}: {}\n",
+                message.sent_at.format("%Y-%m-%d %H:%M:%S"),
+                message.sender,
+                message.content,
+            ),
+        }
+    }
+}
+
+// что выгружается: групповой чат или личная переписка пользователя с собеседником
+#[derive(Debug, Clone)]
+pub enum ExportSource {
+    Group(i32),
+    Direct { user: String, other: String },
+}
+
+impl ExportSource {
+    // начало выгрузки: заголовок с описанием чата без сообщений
+    async fn opening(&self, pool: &DbPool, format: ExportFormat) -> AppResult<String> {
+        match self {
+            ExportSource::Group(chat_id) => {
+                let chat = group_chat::get_by_id(pool, *chat_id)
+                    .await?
+                    .ok_or(ServerError::ChatNotFound)?;
+                let members = group_chat::get_members(pool, *chat_id).await?;
+                Ok(format.opening(&ChatExport { chat, members, messages: Vec::new() }))
+            }
+            ExportSource::Direct { user, other } => {
+                let participants = vec![user.clone(), other.clone()];
+                Ok(format.opening(&DirectChatExport { participants, messages: Vec::new() }))
+            }
+        }
+    }
+
+    async fn load_page(&self, pool: &DbPool, after_id: i32) -> AppResult<Vec<ExportedMessage>> {
+        match self {
+            ExportSource::Group(chat_id) => {
+                messages::load_chat_export_page(pool, *chat_id, after_id, EXPORT_PAGE_SIZE).await
+            }
+            ExportSource::Direct { user, other } => {
+                private_messages::load_export_page(pool, user, other, after_id, EXPORT_PAGE_SIZE).await
+            }
+        }
+    }
+}
+
+// состояние потоковой выгрузки
+enum ExportState {
+    Start,
+    Page { after_id: i32, first: bool },
+    Finish,
+    Done,
+}
 
 pub async fn create_group_chat(pool: &DbPool, name: &str, creator: &str) -> AppResult<i32> {
+    validate_chat_name(name)?;
     group_chat::create(pool, name, creator)
         .await
         .map_err(|e| {
@@ -10,12 +119,31 @@ pub async fn create_group_chat(pool: &DbPool, name: &str, creator: &str) -> AppR
         })
 }
 
+// заблокированного в чате пользователя нельзя добавить обратно
+pub async fn add_member(pool: &DbPool, chat_id: i32, username: &str) -> AppResult<()> {
+    let active = restrictions::find_active(pool, username, Some(chat_id)).await?;
+    if active.contains(&RestrictionKind::Ban) {
+        return Err(ServerError::UserBanned);
+    }
+    group_chat::add_member(pool, chat_id, username).await
+}
+
+// добавление участника кадром WebSocket с теми же правами, что и у /invite: только участник чата
+pub async fn invite_member(pool: &DbPool, chat_id: i32, requester: &str, member: &str) -> AppResult<()> {
+    message_service::check_chat_access(pool, Some(chat_id), requester).await?;
+    add_member(pool, chat_id, member).await
+}
+
 pub async fn delete_group_chat(pool: &DbPool, chat_id: i32, requester: &str) -> AppResult<()> {
     group_chat::delete(pool, chat_id, requester)
         .await
-        .map_err(|e| {
-            error!("Ошибка удаления группового чата: {}", e);
-            ServerError::DeleteGroupChatError
+        .map_err(|e| match e {
+            // отказ в доступе передается дальше для журнала аудита
+            ServerError::PermissionDenied => e,
+            e => {
+                error!("Ошибка удаления группового чата: {}", e);
+                ServerError::DeleteGroupChatError
+            }
         })
 }
 
@@ -27,3 +155,141 @@ pub async fn get_all_group_chats(pool: &DbPool) -> AppResult<Vec<Chat>> {
             ServerError::GetAllGroupChatsError
         })
 }
+
+// выгрузка группового чата вместе с участниками и историей
+pub async fn export_group_chat(pool: &DbPool, chat_id: i32) -> AppResult<ChatExport> {
+    let chat = group_chat::get_by_id(pool, chat_id)
+        .await?
+        .ok_or(ServerError::ChatNotFound)?;
+    let members = group_chat::get_members(pool, chat_id).await?;
+    let messages = messages::load_chat_export(pool, chat_id).await?;
+
+    Ok(ChatExport { chat, members, messages })
+}
+
+// проверка доступа к выгрузке: выгружать чат могут только его участники
+pub async fn check_export_access(pool: &DbPool, chat_id: i32, requester: &str) -> AppResult<()> {
+    if group_chat::get_by_id(pool, chat_id).await?.is_none() {
+        return Err(ServerError::ChatNotFound);
+    }
+    if !group_chat::is_member(pool, chat_id, requester).await? {
+        return Err(ServerError::PermissionDenied);
+    }
+    Ok(())
+}
+
+// проверка доступа к выгрузке личной переписки: собеседник должен существовать
+pub async fn check_direct_export_access(pool: &DbPool, other: &str) -> AppResult<()> {
+    match user::find_user_by_username(pool, other).await? {
+        Some(_) => Ok(()),
+        None => Err(ServerError::ChatNotFound),
+    }
+}
+
+// Потоковая выгрузка истории чата частями по EXPORT_PAGE_SIZE сообщений.
+// JSON собирается в ChatExport или DirectChatExport - те же файлы, что читает messenger-admin chats import
+pub fn export_stream(pool: DbPool, source: ExportSource, format: ExportFormat) -> impl Stream<Item = AppResult<String>> {
+    stream::unfold(ExportState::Start, move |state| {
+        let pool = pool.clone();
+        let source = source.clone();
+        async move {
+            match state {
+                ExportState::Start => match source.opening(&pool, format).await {
+                    Ok(opening) => Some((Ok(opening), ExportState::Page { after_id: 0, first: true })),
+                    Err(e) => Some((Err(e), ExportState::Done)),
+                },
+                ExportState::Page { after_id, first } => {
+                    match source.load_page(&pool, after_id).await {
+                        Ok(page) if page.is_empty() => Some((Ok(String::new()), ExportState::Finish)),
+                        Ok(page) => {
+                            let last_id = page.last().map(|message| message.id).unwrap_or(after_id);
+                            let chunk: String = page
+                                .iter()
+                                .enumerate()
+                                .map(|(i, message)| format.format_message(message, first && i == 0))
+                                .collect();
+                            Some((Ok(chunk), ExportState::Page { after_id: last_id, first: false }))
+                        }
+                        Err(e) => Some((Err(e), ExportState::Done)),
+                    }
+                }
+                ExportState::Finish => Some((Ok(format.closing().to_string()), ExportState::Done)),
+                ExportState::Done => None,
+            }
+        }
+    })
+}
+
+// загрузка чата из файла выгрузки
+pub async fn import_group_chat(pool: &DbPool, export: &ChatExport) -> AppResult<i32> {
+    group_chat::import(pool, export)
+        .await
+        .map_err(|e| {
+            error!("Ошибка загрузки группового чата {}: {}", export.chat.name, e);
+            e
+        })
+}
+
+// загрузка личной переписки из файла выгрузки, возвращает количество сообщений
+pub async fn import_direct_chat(pool: &DbPool, export: &DirectChatExport) -> AppResult<usize> {
+    private_messages::import(pool, export)
+        .await
+        .map_err(|e| {
+            error!("Ошибка загрузки личной переписки {:?}: {}", export.participants, e);
+            e
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn message(id: i32, sender: &str, content: &str) -> ExportedMessage {
+        let sent_at = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap().and_hms_opt(12, 0, id as u32).unwrap();
+        ExportedMessage { id, sender: sender.to_string(), content: content.to_string(), reply_to: None, sent_at }
+    }
+
+    // части потока, как их отдает export_stream: начало, страницы сообщений, конец
+    fn stream_json<T: Serialize>(header: &T, pages: &[Vec<ExportedMessage>]) -> String {
+        let format = ExportFormat::Json;
+        let mut json = format.opening(header);
+        let mut first = true;
+        for page in pages {
+            for message in page {
+                json.push_str(&format.format_message(message, first));
+                first = false;
+            }
+        }
+        json.push_str(format.closing());
+        json
+    }
+
+    #[test]
+    fn json_stream_reads_as_chat_export() {
+        let chat = Chat { id: 7, name: "team".to_string(), creator: "alice".to_string() };
+        let header = ChatExport { chat, members: vec!["alice".to_string(), "bob".to_string()], messages: Vec::new() };
+        let pages = vec![vec![message(1, "alice", "привет"), message(2, "bob", "\"]}")], vec![message(3, "alice", "пока")]];
+
+        let export: ChatExport = serde_json::from_str(&stream_json(&header, &pages)).unwrap();
+        assert_eq!(export.chat.name, "team");
+        assert_eq!(export.members, ["alice", "bob"]);
+        let contents: Vec<_> = export.messages.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["привет", "\"]}", "пока"]);
+
+        // пустой чат - тоже корректная выгрузка
+        let empty: ChatExport = serde_json::from_str(&stream_json(&header, &[])).unwrap();
+        assert!(empty.messages.is_empty());
+    }
+
+    #[test]
+    fn json_stream_reads_as_direct_chat_export() {
+        let header = DirectChatExport { participants: vec!["alice".to_string(), "bob".to_string()], messages: Vec::new() };
+        let pages = vec![vec![message(10, "alice", "привет"), message(12, "bob", "привет")]];
+
+        let export: DirectChatExport = serde_json::from_str(&stream_json(&header, &pages)).unwrap();
+        assert_eq!(export.participants, ["alice", "bob"]);
+        assert_eq!(export.messages.len(), 2);
+        assert_eq!(export.messages[1].sender, "bob");
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+use crate::backplane::{Event, SharedBackplane};
+use crate::db::{group_chat, messages, reactions, read_markers};
+use crate::moderation::{self, FilterChain};
+use crate::protocol::Message;
+use crate::services::webhook_service;
+use crate::structs::{ChatMessage, SendOptions};
+use crate::types::{AppResult, DbPool, ServerError};
+use messenger_protocol::validation::validate_emoji;
+
+// как часто планировщик проверяет отложенные и истекшие сообщения
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(1);
+
+// ответить можно только на сообщение из того же чата
+async fn check_reply_target(db_pool: &DbPool, reply_to: Option<i32>, chat_id: Option<i32>) -> AppResult<()> {
+    if let Some(reply_to) = reply_to {
+        let target = messages::find_message(db_pool, reply_to)
+            .await?
+            .ok_or(ServerError::MessageNotFound)?;
+        if target.chat_id != chat_id {
+            return Err(ServerError::InvalidOperation);
+        }
+    }
+    Ok(())
+}
+
+// рассылка события тем, кто видит сообщение: всем для общего чата или участникам группового
+pub async fn publish_for_chat(
+    backplane: &SharedBackplane,
+    db_pool: &DbPool,
+    chat_id: Option<i32>,
+    message: &Message,
+) -> AppResult<()> {
+    let payload = serde_json::to_string(message).unwrap();
+    match chat_id {
+        Some(chat_id) => {
+            let members = group_chat::get_members(db_pool, chat_id).await?;
+            backplane.publish(Event::Group { chat_id, members, payload }).await
+        }
+        None => backplane.publish(Event::Global { payload }).await,
+    }
+}
+
+// доступ к сообщениям группового чата есть только у его участников
+pub async fn check_chat_access(db_pool: &DbPool, chat_id: Option<i32>, username: &str) -> AppResult<()> {
+    if let Some(chat_id) = chat_id {
+        if !group_chat::is_member(db_pool, chat_id, username).await? {
+            return Err(ServerError::PermissionDenied);
+        }
+    }
+    Ok(())
+}
+
+// писать в групповой чат могут только его участники
+fn check_sender_is_member(members: &[String], sender: &str) -> AppResult<()> {
+    if !members.iter().any(|member| member == sender) {
+        return Err(ServerError::PermissionDenied);
+    }
+    Ok(())
+}
+
+// срок жизни сообщения должен быть положительным
+fn check_send_options(options: &SendOptions) -> AppResult<()> {
+    if options.expires_after.is_some_and(|secs| secs <= 0) {
+        return Err(ServerError::InvalidOperation);
+    }
+    Ok(())
+}
+
+// Отправка в общий чат. Для отложенного сообщения возвращает его ID,
+// его разошлет планировщик в указанное время
+pub async fn send_message_to_general_chat(
+    backplane: &SharedBackplane,
+    db_pool: &DbPool,
+    filters: &FilterChain,
+    sender: &str,
+    content: &str,
+    options: &SendOptions,
+) -> AppResult<Option<i32>> {
+    check_send_options(options)?;
+    check_reply_target(db_pool, options.reply_to, None).await?;
+    let content = &moderation::moderate(db_pool, filters, sender, None, content).await?;
+
+    // сохраняем сообщение в базу данных
+    let id = messages::save_message(db_pool, sender, content, None, options).await?;
+    if options.delay_secs().is_some() {
+        return Ok(Some(id));
+    }
+
+    let message = Message::ReceiveMessage {
+        id: Some(id),
+        sender: sender.to_string(),
+        content: content.to_string(),
+        reply_to: options.reply_to,
+        reactions: Vec::new(),
+    };
+
+    publish_for_chat(backplane, db_pool, None, &message).await?;
+    Ok(None)
+}
+
+pub async fn send_message_to_group_chat(
+    backplane: &SharedBackplane,
+    db_pool: &DbPool,
+    filters: &FilterChain,
+    chat_id: i32,
+    sender: &str,
+    content: &str,
+    options: &SendOptions,
+) -> AppResult<Option<i32>> {
+    check_send_options(options)?;
+    // проверка до модерации, сохранения и рассылки: чужой чат не должен ничего узнать
+    let members = group_chat::get_members(db_pool, chat_id).await?;
+    check_sender_is_member(&members, sender)?;
+    check_reply_target(db_pool, options.reply_to, Some(chat_id)).await?;
+    let content = &moderation::moderate(db_pool, filters, sender, Some(chat_id), content).await?;
+
+    let id = messages::save_message(db_pool, sender, content, Some(chat_id), options).await?;
+    if options.delay_secs().is_some() {
+        return Ok(Some(id));
+    }
+
+    let stored = ChatMessage {
+        id,
+        chat_id: Some(chat_id),
+        sender: sender.to_string(),
+        content: content.to_string(),
+        reply_to: options.reply_to,
+        reactions: Vec::new(),
+    };
+    webhook_service::notify_new_message(db_pool, &stored).await;
+
+    backplane.publish(Event::Group {
+        chat_id,
+        members,
+        payload: serde_json::to_string(&Message::from(stored)).unwrap(),
+    }).await?;
+    Ok(None)
+}
+
+// добавление или удаление реакции и рассылка новых количеств
+pub async fn update_reaction(
+    backplane: &SharedBackplane,
+    db_pool: &DbPool,
+    message_id: i32,
+    username: &str,
+    emoji: &str,
+    add: bool,
+) -> AppResult<()> {
+    validate_emoji(emoji)?;
+
+    let message = messages::find_message(db_pool, message_id)
+        .await?
+        .ok_or(ServerError::MessageNotFound)?;
+    check_chat_access(db_pool, message.chat_id, username).await?;
+
+    if add {
+        reactions::add(db_pool, message_id, username, emoji).await?;
+    } else {
+        reactions::remove(db_pool, message_id, username, emoji).await?;
+    }
+
+    let reactions = reactions::get_counts(db_pool, &[message_id])
+        .await?
+        .remove(&message_id)
+        .unwrap_or_default();
+    let update = Message::ReactionsUpdated { message_id, reactions };
+    publish_for_chat(backplane, db_pool, message.chat_id, &update).await
+}
+
+pub async fn load_thread(db_pool: &DbPool, root_id: i32, requester: &str) -> AppResult<Vec<ChatMessage>> {
+    let root = messages::find_message(db_pool, root_id)
+        .await?
+        .ok_or(ServerError::MessageNotFound)?;
+    check_chat_access(db_pool, root.chat_id, requester).await?;
+
+    messages::load_thread(db_pool, root_id).await
+}
+
+// сохранение отметки о прочтении и рассылка ее всем устройствам пользователя
+pub async fn mark_read(
+    backplane: &SharedBackplane,
+    db_pool: &DbPool,
+    username: &str,
+    chat_id: Option<i32>,
+    message_id: i32,
+) -> AppResult<()> {
+    let message = messages::find_message(db_pool, message_id)
+        .await?
+        .ok_or(ServerError::MessageNotFound)?;
+    if message.chat_id != chat_id {
+        return Err(ServerError::InvalidOperation);
+    }
+    check_chat_access(db_pool, chat_id, username).await?;
+
+    // отметка не сдвигается назад, если устройство прислало устаревшую
+    let message_id = read_markers::mark_read(db_pool, username, chat_id, message_id).await?;
+    let update = Message::ReadMarkerUpdated { chat_id, message_id };
+    backplane.publish(Event::Private {
+        recipient: username.to_string(),
+        payload: serde_json::to_string(&update).unwrap(),
+    }).await
+}
+
+// Фоновая задача: рассылает отложенные сообщения, когда наступает их время,
+// и удаляет сообщения с истекшим сроком жизни. Состояние хранится в БД,
+// поэтому переживает перезапуск сервера
+pub async fn run_scheduler(backplane: SharedBackplane, db_pool: Arc<DbPool>) {
+    let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+
+        match messages::claim_due(&db_pool).await {
+            Ok(due) => {
+                for stored in due {
+                    info!("Отправка отложенного сообщения ID: {}", stored.id);
+                    let chat_id = stored.chat_id;
+                    webhook_service::notify_new_message(&db_pool, &stored).await;
+                    if let Err(e) = publish_for_chat(&backplane, &db_pool, chat_id, &Message::from(stored)).await {
+                        error!("Ошибка отправки отложенного сообщения {}", e);
+                    }
+                }
+            }
+            Err(e) => error!("Ошибка выборки отложенных сообщений {}", e),
+        }
+
+        match messages::delete_expired(&db_pool).await {
+            Ok(deleted) => {
+                for (message_id, chat_id) in deleted {
+                    let notification = Message::MessageDeleted { message_id, chat_id };
+                    if let Err(e) = publish_for_chat(&backplane, &db_pool, chat_id, &notification).await {
+                        error!("Ошибка отправки уведомления об удалении сообщения {}", e);
+                    }
+                }
+            }
+            Err(e) => error!("Ошибка удаления истекших сообщений {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, Utc};
+
+    #[test]
+    fn non_member_cannot_send_to_group_chat() {
+        let members = vec!["alice".to_string(), "bob".to_string()];
+        assert!(check_sender_is_member(&members, "alice").is_ok());
+        assert!(matches!(check_sender_is_member(&members, "mallory"), Err(ServerError::PermissionDenied)));
+        assert!(matches!(check_sender_is_member(&[], "alice"), Err(ServerError::PermissionDenied)));
+    }
+
+    #[test]
+    fn lifetime_must_be_positive() {
+        let options = |expires_after| SendOptions { expires_after, ..Default::default() };
+        assert!(check_send_options(&options(None)).is_ok());
+        assert!(check_send_options(&options(Some(30))).is_ok());
+        assert!(matches!(check_send_options(&options(Some(0))), Err(ServerError::InvalidOperation)));
+        assert!(matches!(check_send_options(&options(Some(-5))), Err(ServerError::InvalidOperation)));
+    }
+
+    #[test]
+    fn only_future_send_time_delays_message() {
+        let options = |send_at| SendOptions { send_at, ..Default::default() };
+        assert_eq!(options(None).delay_secs(), None);
+        // время в прошлом - сообщение уходит сразу, а не висит в планировщике
+        assert_eq!(options(Some(Utc::now() - TimeDelta::seconds(10))).delay_secs(), None);
+        let delay = options(Some(Utc::now() + TimeDelta::seconds(60))).delay_secs().unwrap();
+        assert!(delay > 59.0 && delay <= 60.0, "задержка: {}", delay);
+    }
+}
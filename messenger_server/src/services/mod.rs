@@ -1,2 +1,8 @@
 pub mod auth_service;
 pub mod chat_service;
+pub mod session_service;
+pub mod message_service;
+pub mod bot_service;
+pub mod webhook_service;
+pub mod audit_service;
+pub mod unread_service;
@@ -0,0 +1,115 @@
+use rand::{distr::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use crate::db::sessions;
+use crate::services::auth_service;
+use crate::structs::{ClientInfo, Session, SessionTokens};
+use crate::types::{AppResult, DbPool, ServerError};
+
+// время жизни токена доступа и токена обновления
+pub const ACCESS_TOKEN_TTL_SECS: i64 = 60 * 60;
+pub const REFRESH_TOKEN_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+
+const TOKEN_LENGTH: usize = 48;
+
+pub fn generate_token() -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+// в базе хранится только хэш токена
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+// вход по логину и паролю с созданием новой сессии
+pub async fn login(pool: &DbPool, username: &str, password: &str, client: &ClientInfo) -> AppResult<Option<SessionTokens>> {
+    if !auth_service::authenticate_user(pool, username, password).await? {
+        return Ok(None);
+    }
+
+    let token = generate_token();
+    let refresh_token = generate_token();
+    let session_id = sessions::create(
+        pool,
+        username,
+        &hash_token(&token),
+        &hash_token(&refresh_token),
+        ACCESS_TOKEN_TTL_SECS,
+        REFRESH_TOKEN_TTL_SECS,
+        client,
+    ).await?;
+
+    Ok(Some(SessionTokens { session_id, token, refresh_token, expires_in: ACCESS_TOKEN_TTL_SECS }))
+}
+
+// выдача новой пары токенов, старый токен обновления больше не действует
+pub async fn refresh(pool: &DbPool, refresh_token: &str) -> AppResult<SessionTokens> {
+    let token = generate_token();
+    let new_refresh_token = generate_token();
+    let session_id = sessions::rotate(
+        pool,
+        &hash_token(refresh_token),
+        &hash_token(&token),
+        &hash_token(&new_refresh_token),
+        ACCESS_TOKEN_TTL_SECS,
+        REFRESH_TOKEN_TTL_SECS,
+    )
+    .await?
+    .ok_or_else(|| {
+        warn!("Попытка обновить сессию недействительным токеном");
+        ServerError::InvalidToken
+    })?;
+
+    info!("Сессия ID: {} обновлена", session_id);
+    Ok(SessionTokens { session_id, token, refresh_token: new_refresh_token, expires_in: ACCESS_TOKEN_TTL_SECS })
+}
+
+// проверка токена доступа
+pub async fn authenticate(pool: &DbPool, token: &str) -> AppResult<Session> {
+    sessions::find_by_access_token(pool, &hash_token(token))
+        .await?
+        .ok_or(ServerError::InvalidToken)
+}
+
+pub async fn list_sessions(pool: &DbPool, username: &str) -> AppResult<Vec<Session>> {
+    sessions::list_active(pool, username).await
+}
+
+// отзыв одной сессии пользователя
+pub async fn revoke_session(pool: &DbPool, username: &str, session_id: i32) -> AppResult<()> {
+    if !sessions::revoke(pool, username, session_id).await? {
+        return Err(ServerError::InvalidOperation);
+    }
+    info!("Сессия ID: {} пользователя {} отозвана", session_id, username);
+    Ok(())
+}
+
+// выход на всех устройствах, возвращает ID отозванных сессий
+pub async fn revoke_all_sessions(pool: &DbPool, username: &str) -> AppResult<Vec<i32>> {
+    sessions::revoke_all(pool, username).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tokens_are_random_alphanumeric() {
+        let token = generate_token();
+        assert_eq!(token.len(), TOKEN_LENGTH);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric()));
+        assert_ne!(token, generate_token());
+    }
+
+    #[test]
+    fn token_hash_is_stable_sha256_hex() {
+        // SHA-256 от "abc"
+        assert_eq!(hash_token("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(hash_token("token"), hash_token("token"));
+        assert_ne!(hash_token("token"), hash_token("token2"));
+    }
+}
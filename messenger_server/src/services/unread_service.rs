@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use crate::db::read_markers;
+use crate::protocol::Message;
+use crate::structs::{UnreadCount, UserChat};
+use crate::types::{AppResult, DbPool};
+
+pub async fn list_user_chats(pool: &DbPool, username: &str) -> AppResult<Vec<UserChat>> {
+    read_markers::get_user_chats(pool, username).await
+}
+
+// Счетчики непрочитанных одного подключения. После входа загружаются из БД,
+// дальше новые сообщения увеличивают их на месте, а отметки о прочтении
+// и удаления пересчитываются по БД
+pub struct UnreadTracker {
+    username: String,
+    counts: HashMap<Option<i32>, i64>,
+}
+
+impl UnreadTracker {
+    // загрузка счетчиков и кадр UnreadCounts для клиента
+    pub async fn load(pool: &DbPool, username: &str) -> AppResult<(Self, Message)> {
+        let chats = read_markers::get_user_chats(pool, username).await?;
+        let counts: Vec<UnreadCount> = chats
+            .into_iter()
+            .map(|chat| UnreadCount { chat_id: chat.chat_id, unread: chat.unread })
+            .collect();
+
+        let tracker = UnreadTracker {
+            username: username.to_string(),
+            counts: counts.iter().map(|count| (count.chat_id, count.unread)).collect(),
+        };
+        Ok((tracker, Message::UnreadCounts { counts }))
+    }
+
+    async fn recount(&mut self, pool: &DbPool, chat_id: Option<i32>) -> AppResult<Option<Message>> {
+        let unread = read_markers::count_unread(pool, &self.username, chat_id).await?;
+        Ok(self.set(chat_id, unread))
+    }
+
+    fn set(&mut self, chat_id: Option<i32>, unread: i64) -> Option<Message> {
+        let previous = self.counts.insert(chat_id, unread);
+        (previous != Some(unread)).then_some(Message::UnreadCountUpdated { chat_id, unread })
+    }
+
+    // Обработка события, доставленного клиенту. Возвращает обновление счетчика,
+    // если он изменился
+    pub async fn on_event(&mut self, pool: &DbPool, message: &Message) -> AppResult<Option<Message>> {
+        let (chat_id, sender) = match message {
+            Message::ReceiveMessage { id: Some(_), sender, .. } => (None, sender),
+            Message::ReceiveGroupChatMessage { id: Some(_), chat_id, sender, .. } => (Some(*chat_id), sender),
+            Message::ReadMarkerUpdated { chat_id, .. } | Message::MessageDeleted { chat_id, .. } => {
+                return self.recount(pool, *chat_id).await;
+            }
+            _ => return Ok(None),
+        };
+        if *sender == self.username {
+            return Ok(None);
+        }
+
+        match self.counts.get(&chat_id) {
+            Some(unread) => Ok(self.set(chat_id, unread + 1)),
+            // новый чат (например, пользователя только что пригласили) - считаем по БД
+            None => self.recount(pool, chat_id).await,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::time::Duration;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::{error, info, warn};
+use crate::db::{group_chat, webhooks};
+use crate::services::session_service::generate_token;
+use crate::structs::{ChatMessage, Webhook, WebhookDelivery, WebhookPayload};
+use crate::types::{AppResult, DbPool, ServerError};
+
+// заголовки запроса вебхука
+pub const SIGNATURE_HEADER: &str = "X-Messenger-Signature";
+pub const DELIVERY_HEADER: &str = "X-Messenger-Delivery";
+
+// после стольких неудачных попыток доставка считается проваленной
+pub const MAX_ATTEMPTS: i32 = 8;
+
+// задержка перед второй попыткой, дальше она удваивается
+const BASE_RETRY_DELAY_SECS: f64 = 5.0;
+
+// сколько доставок обрабатывается за один проход и на сколько они резервируются
+const BATCH_SIZE: i64 = 32;
+const LEASE_SECS: f64 = 60.0;
+
+const WORKER_INTERVAL: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Подпись тела запроса: HMAC-SHA256 секретом вебхука в виде "sha256=<hex>"
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC принимает ключ любой длины");
+    mac.update(body.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("sha256={}", hex)
+}
+
+fn retry_delay_secs(attempts: i32) -> f64 {
+    BASE_RETRY_DELAY_SECS * 2f64.powi(attempts.max(0))
+}
+
+async fn check_creator(pool: &DbPool, chat_id: i32, requester: &str) -> AppResult<()> {
+    if group_chat::get_by_id(pool, chat_id).await?.is_none() {
+        return Err(ServerError::ChatNotFound);
+    }
+    if !group_chat::check_if_creator(pool, chat_id, requester).await? {
+        return Err(ServerError::PermissionDenied);
+    }
+    Ok(())
+}
+
+// Регистрация вебхука создателем чата. Возвращает ID и секрет для проверки подписи
+pub async fn create_webhook(pool: &DbPool, chat_id: i32, requester: &str, url: &str) -> AppResult<(i32, String)> {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(ServerError::InvalidArgument("Адрес вебхука должен начинаться с http:// или https://".to_string()));
+    }
+    check_creator(pool, chat_id, requester).await?;
+
+    let secret = generate_token();
+    let id = webhooks::create(pool, chat_id, url, &secret, requester).await?;
+    Ok((id, secret))
+}
+
+pub async fn list_webhooks(pool: &DbPool, chat_id: i32, requester: &str) -> AppResult<Vec<Webhook>> {
+    check_creator(pool, chat_id, requester).await?;
+    webhooks::get_for_chat(pool, chat_id).await
+}
+
+pub async fn delete_webhook(pool: &DbPool, chat_id: i32, requester: &str, webhook_id: i32) -> AppResult<()> {
+    check_creator(pool, chat_id, requester).await?;
+    webhooks::delete(pool, chat_id, webhook_id).await
+}
+
+// Постановка нового сообщения группового чата в очередь вебхуков.
+// Ошибка не мешает отправке сообщения, поэтому только логируется
+pub async fn notify_new_message(pool: &DbPool, message: &ChatMessage) {
+    let Some(chat_id) = message.chat_id else {
+        return;
+    };
+    let payload = WebhookPayload {
+        event: "message.created".to_string(),
+        chat_id,
+        message: message.clone(),
+    };
+    let payload = serde_json::to_string(&payload).unwrap();
+    if let Err(e) = webhooks::enqueue_for_chat(pool, chat_id, &payload).await {
+        error!("Ошибка постановки вебхука в очередь {}", e);
+    }
+}
+
+async fn send(client: &reqwest::Client, delivery: &WebhookDelivery) -> Result<(), String> {
+    let response = client
+        .post(&delivery.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, sign(&delivery.secret, &delivery.payload))
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Ответ {}", response.status()))
+    }
+}
+
+// Один проход доставки: отправляет все доставки, время которых наступило.
+// Возвращает количество успешно доставленных
+pub async fn deliver_due(pool: &DbPool, client: &reqwest::Client) -> AppResult<usize> {
+    let deliveries = webhooks::claim_due(pool, BATCH_SIZE, LEASE_SECS).await?;
+    let mut delivered = 0;
+
+    for delivery in deliveries {
+        match send(client, &delivery).await {
+            Ok(()) => {
+                webhooks::mark_delivered(pool, delivery.id).await?;
+                delivered += 1;
+            }
+            Err(e) => {
+                let attempts = delivery.attempts + 1;
+                let retry_in = (attempts < MAX_ATTEMPTS).then(|| retry_delay_secs(delivery.attempts));
+                match retry_in {
+                    Some(secs) => warn!("Вебхук {} не доставлен ({}), повтор через {} с", delivery.id, e, secs),
+                    None => warn!("Вебхук {} не доставлен после {} попыток: {}", delivery.id, attempts, e),
+                }
+                webhooks::mark_attempt_failed(pool, delivery.id, &e, retry_in).await?;
+            }
+        }
+    }
+
+    Ok(delivered)
+}
+
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("Не удалось создать HTTP-клиент")
+}
+
+// Фоновая задача доставки вебхуков. Очередь хранится в БД, поэтому
+// недоставленные события переживают перезапуск сервера
+pub async fn run_worker(db_pool: Arc<DbPool>) {
+    let client = http_client();
+    let mut interval = tokio::time::interval(WORKER_INTERVAL);
+    loop {
+        interval.tick().await;
+        match deliver_due(&db_pool, &client).await {
+            Ok(0) => {}
+            Ok(delivered) => info!("Доставлено вебхуков: {}", delivered),
+            Err(e) => error!("Ошибка доставки вебхуков {}", e),
+        }
+    }
+}
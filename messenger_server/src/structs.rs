@@ -1,8 +1,152 @@
-use crate::Serialize;
+use chrono::NaiveDateTime;
+use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Serialize)]
-pub struct Chat {
+// типы, которые видят клиенты, описаны в общем крейте протокола
+pub use messenger_protocol::structs::{
+    AuditEvent, Chat, ChatExport, ChatMessage, DirectChatExport, ExportedMessage, ReactionCount,
+    ReadMarker, SendOptions, Session, SessionTokens, UnreadCount, UserChat, Webhook,
+    WebhookPayload,
+};
+
+// вид ограничения пользователя
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RestrictionKind {
+    Mute, // не может писать
+    Ban, // не может писать и входить
+}
+
+impl RestrictionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestrictionKind::Mute => "mute",
+            RestrictionKind::Ban => "ban",
+        }
+    }
+
+    pub fn from_db(kind: &str) -> Option<Self> {
+        match kind {
+            "mute" => Some(RestrictionKind::Mute),
+            "ban" => Some(RestrictionKind::Ban),
+            _ => None,
+        }
+    }
+}
+
+
+// данные клиента, с которого пришел запрос
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+}
+
+
+// доставка вебхука, ожидающая отправки
+#[derive(Debug, Clone)]
+pub struct WebhookDelivery {
     pub id: i32,
-    pub name: String,
-    pub creator: String,
+    pub payload: String,
+    pub attempts: i32,
+    pub url: String,
+    pub secret: String,
+}
+
+
+// тип события журнала аудита
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventKind {
+    Register,
+    LoginSuccess,
+    LoginFailure,
+    ChatCreated,
+    ChatDeleted,
+    MemberAdded,
+    MemberRemoved,
+    PermissionDenied,
+}
+
+impl AuditEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventKind::Register => "register",
+            AuditEventKind::LoginSuccess => "login_success",
+            AuditEventKind::LoginFailure => "login_failure",
+            AuditEventKind::ChatCreated => "chat_created",
+            AuditEventKind::ChatDeleted => "chat_deleted",
+            AuditEventKind::MemberAdded => "member_added",
+            AuditEventKind::MemberRemoved => "member_removed",
+            AuditEventKind::PermissionDenied => "permission_denied",
+        }
+    }
+
+    pub fn from_db(kind: &str) -> Option<Self> {
+        match kind {
+            "register" => Some(AuditEventKind::Register),
+            "login_success" => Some(AuditEventKind::LoginSuccess),
+            "login_failure" => Some(AuditEventKind::LoginFailure),
+            "chat_created" => Some(AuditEventKind::ChatCreated),
+            "chat_deleted" => Some(AuditEventKind::ChatDeleted),
+            "member_added" => Some(AuditEventKind::MemberAdded),
+            "member_removed" => Some(AuditEventKind::MemberRemoved),
+            "permission_denied" => Some(AuditEventKind::PermissionDenied),
+            _ => None,
+        }
+    }
+}
+
+// новая запись журнала аудита
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub kind: AuditEventKind,
+    pub actor: Option<String>, // кто выполнил действие
+    pub target: Option<String>, // над кем выполнено действие
+    pub chat_id: Option<i32>,
+    pub ip: Option<String>,
+    pub details: Option<String>,
+}
+
+impl AuditRecord {
+    pub fn new(kind: AuditEventKind) -> Self {
+        AuditRecord { kind, actor: None, target: None, chat_id: None, ip: None, details: None }
+    }
+
+    pub fn actor(mut self, actor: &str) -> Self {
+        self.actor = Some(actor.to_string());
+        self
+    }
+
+    pub fn target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self
+    }
+
+    pub fn chat(mut self, chat_id: i32) -> Self {
+        self.chat_id = Some(chat_id);
+        self
+    }
+
+    pub fn ip(mut self, ip: Option<&str>) -> Self {
+        self.ip = ip.map(str::to_string);
+        self
+    }
+
+    pub fn details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+}
+
+
+// фильтры выборки журнала аудита, все необязательные
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditFilter {
+    pub event_type: Option<AuditEventKind>,
+    pub actor: Option<String>,
+    pub target: Option<String>,
+    pub chat_id: Option<i32>,
+    pub since: Option<NaiveDateTime>,
+    pub until: Option<NaiveDateTime>,
+    pub before_id: Option<i64>, // для постраничной загрузки
+    pub limit: Option<i64>,
 }
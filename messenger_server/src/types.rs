@@ -2,6 +2,7 @@ use thiserror::Error;
 use std::env::VarError;
 use sqlx::{PgPool, Error as SqlxError};
 use bcrypt::BcryptError;
+use messenger_protocol::ValidationError;
 
 #[derive(Error, Debug)]
 pub enum ServerError {
@@ -33,6 +34,29 @@ pub enum ServerError {
     DeleteGroupChatError,
     #[error("Ошибка загрузки списка чатов")]
     GetAllGroupChatsError,
+    #[error("{0}")]
+    InvalidArgument(String),
+    #[error("Неверный или просроченный токен")]
+    InvalidToken,
+    #[error("Групповой чат не найден")]
+    ChatNotFound,
+    #[error("Сообщение не найдено")]
+    MessageNotFound,
+    #[error("Сообщение отклонено: {0}")]
+    MessageRejected(String),
+    #[error("Вы не можете отправлять сообщения в этот чат")]
+    UserMuted,
+    #[error("Доступ заблокирован")]
+    UserBanned,
+    #[error("Ошибка шины сообщений: {0}")]
+    BackplaneError(String),
+}
+
+// ошибки проверки данных протокола отдаются клиенту как есть
+impl From<ValidationError> for ServerError {
+    fn from(e: ValidationError) -> Self {
+        ServerError::InvalidArgument(e.to_string())
+    }
 }
 
 pub type AppResult<T> = Result<T, ServerError>;
@@ -0,0 +1,73 @@
+// интеграционный тест журнала аудита
+// запуск (нужна база из .env): cargo test --test audit
+use std::env;
+use messenger_server::db::{db_main, user};
+use messenger_server::services::audit_service;
+use messenger_server::structs::{AuditEventKind, AuditFilter, AuditRecord};
+use messenger_server::types::ServerError;
+use sqlx::PgPool;
+
+#[tokio::test]
+async fn audit_log_is_filtered_and_append_only() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+
+    let suffix = std::process::id();
+    let admin = format!("audit_admin_{}", suffix);
+    let member = format!("audit_member_{}", suffix);
+    for username in [&admin, &member] {
+        user::register(&pool, username, "secret").await.unwrap();
+    }
+    sqlx::query("UPDATE users SET is_admin = TRUE WHERE username = $1")
+        .bind(&admin)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    audit_service::record(&pool, AuditRecord::new(AuditEventKind::LoginFailure).actor(&member).ip(Some("10.0.0.1"))).await;
+    audit_service::record(&pool, AuditRecord::new(AuditEventKind::LoginSuccess).actor(&member).ip(Some("10.0.0.1"))).await;
+    audit_service::record(&pool, AuditRecord::new(AuditEventKind::MemberAdded).actor(&admin).target(&member).chat(1)).await;
+
+    // журнал доступен только администраторам, отказ тоже записывается
+    let result = audit_service::list(&pool, &member, Some("10.0.0.1"), &AuditFilter::default()).await;
+    assert!(matches!(result, Err(ServerError::PermissionDenied)));
+
+    let filter = AuditFilter { actor: Some(member.clone()), ..Default::default() };
+    let events = audit_service::list(&pool, &admin, None, &filter).await.unwrap();
+    let kinds: Vec<&str> = events.iter().map(|event| event.event_type.as_str()).collect();
+    assert_eq!(kinds, ["permission_denied", "login_success", "login_failure"]);
+
+    let filter = AuditFilter {
+        event_type: Some(AuditEventKind::MemberAdded),
+        target: Some(member.clone()),
+        ..Default::default()
+    };
+    let events = audit_service::list(&pool, &admin, None, &filter).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].actor.as_deref(), Some(admin.as_str()));
+
+    // постраничная загрузка
+    let filter = AuditFilter { actor: Some(member.clone()), limit: Some(1), ..Default::default() };
+    let first_page = audit_service::list(&pool, &admin, None, &filter).await.unwrap();
+    let filter = AuditFilter { before_id: Some(first_page[0].id), ..filter };
+    let second_page = audit_service::list(&pool, &admin, None, &filter).await.unwrap();
+    assert_eq!(second_page[0].event_type, "login_success");
+
+    // записи нельзя изменить или удалить
+    let deleted = sqlx::query("DELETE FROM audit_events WHERE actor = $1")
+        .bind(&member)
+        .execute(&pool)
+        .await;
+    assert!(deleted.is_err());
+
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![admin.clone(), member.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,184 @@
+// интеграционный тест: два экземпляра сервера с общей базой данных и шиной postgres
+// запуск (нужна база из .env): cargo test --test backplane
+use std::env;
+use std::net::TcpStream as StdTcpStream;
+use std::process::{Child, Command};
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use messenger_server::db::{db_main, group_chat, user};
+use messenger_server::moderation::filters;
+use messenger_server::services::session_service;
+use messenger_server::structs::ClientInfo;
+use messenger_server::types::ServerError;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+type Ws = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+// экземпляр сервера, который завершается вместе с тестом
+struct ServerProcess {
+    child: Child,
+    ws_addr: String,
+}
+
+impl ServerProcess {
+    fn start(ws_port: u16, http_port: u16) -> Self {
+        let ws_addr = format!("127.0.0.1:{}", ws_port);
+        let child = Command::new(env!("CARGO_BIN_EXE_messenger_server"))
+            .env("BACKPLANE", "postgres")
+            .env("WS_ADDR", &ws_addr)
+            .env("HTTP_ADDR", format!("127.0.0.1:{}", http_port))
+            .spawn()
+            .expect("Не удалось запустить сервер");
+
+        // ждем, пока сервер начнет принимать подключения
+        for _ in 0..100 {
+            if StdTcpStream::connect(&ws_addr).is_ok() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        ServerProcess { child, ws_addr }
+    }
+}
+
+impl Drop for ServerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+async fn join(addr: &str, username: &str, token: &str) -> Ws {
+    let (mut ws, _) = connect_async(format!("ws://{}", addr))
+        .await
+        .expect("Ошибка подключения по WebSocket");
+    send(&mut ws, json!({ "type": "Join", "username": username, "token": token })).await;
+    ws
+}
+
+async fn send(ws: &mut Ws, message: Value) {
+    ws.send(WsMessage::Text(message.to_string().into())).await.unwrap();
+}
+
+// читаем сообщения, пока не встретится подходящее
+async fn wait_for(ws: &mut Ws, predicate: impl Fn(&Value) -> bool) -> Value {
+    timeout(Duration::from_secs(5), async {
+        while let Some(Ok(frame)) = ws.next().await {
+            if let WsMessage::Text(text) = frame {
+                let message: Value = serde_json::from_str(&text).unwrap();
+                if predicate(&message) {
+                    return message;
+                }
+            }
+        }
+        panic!("Соединение закрыто до получения сообщения");
+    })
+    .await
+    .expect("Сообщение не получено")
+}
+
+// создание пользователя и вход, возвращает токен доступа
+async fn create_user(pool: &PgPool, username: &str) -> String {
+    user::register(pool, username, "secret").await.unwrap();
+    session_service::login(pool, username, "secret", &ClientInfo::default())
+        .await
+        .unwrap()
+        .expect("Не удалось войти")
+        .token
+}
+
+#[tokio::test]
+async fn messages_are_delivered_across_instances() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+
+    let suffix = std::process::id();
+    let alice = format!("alice_{}", suffix);
+    let bob = format!("bob_{}", suffix);
+    let alice_token = create_user(&pool, &alice).await;
+    let bob_token = create_user(&pool, &bob).await;
+
+    let chat_id: i32 = sqlx::query_scalar("INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id")
+        .bind(format!("backplane_{}", suffix))
+        .bind(&alice)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    for member in [&alice, &bob] {
+        sqlx::query("INSERT INTO group_chat_members (chat_id, username) VALUES ($1, $2)")
+            .bind(chat_id)
+            .bind(member)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+
+    let first = ServerProcess::start(18080, 18081);
+    let second = ServerProcess::start(18090, 18091);
+
+    let mut alice_ws = join(&first.ws_addr, &alice, &alice_token).await;
+    let mut bob_ws = join(&second.ws_addr, &bob, &bob_token).await;
+    // даем обоим подключениям подписаться на шину
+    sleep(Duration::from_millis(300)).await;
+
+    // общий чат
+    send(&mut alice_ws, json!({ "type": "SendMessage", "content": "всем привет" })).await;
+    wait_for(&mut bob_ws, |m| {
+        m["type"] == "ReceiveMessage" && m["sender"] == alice.as_str() && m["content"] == "всем привет"
+    }).await;
+
+    // приватное сообщение
+    send(&mut bob_ws, json!({ "type": "SendPrivateMessage", "recipient": alice, "content": "привет" })).await;
+    wait_for(&mut alice_ws, |m| {
+        m["type"] == "ReceivePrivateMessage" && m["sender"] == bob.as_str()
+    }).await;
+
+    // групповой чат
+    send(&mut alice_ws, json!({ "type": "SendMessageToGroupChat", "chat_id": chat_id, "content": "группе" })).await;
+    wait_for(&mut bob_ws, |m| {
+        m["type"] == "ReceiveGroupChatMessage" && m["chat_id"] == chat_id && m["content"] == "группе"
+    }).await;
+
+    // сообщение максимальной длины из четырехбайтовых символов больше лимита payload NOTIFY
+    let long_content = "😀".repeat(filters::DEFAULT_MAX_LENGTH);
+    send(&mut alice_ws, json!({ "type": "SendMessageToGroupChat", "chat_id": chat_id, "content": long_content })).await;
+    wait_for(&mut bob_ws, |m| {
+        m["type"] == "ReceiveGroupChatMessage" && m["chat_id"] == chat_id && m["content"] == long_content.as_str()
+    }).await;
+
+    // без входа и не участником добавить в чат нельзя, в том числе самого себя
+    let carol = format!("carol_{}", suffix);
+    let carol_token = create_user(&pool, &carol).await;
+    let add_carol = json!({ "type": "AddMemberToGroupChat", "chat_id": chat_id, "username": carol });
+    let denied = ServerError::PermissionDenied.to_string();
+    let (mut anonymous_ws, _) = connect_async(format!("ws://{}", first.ws_addr)).await.unwrap();
+    send(&mut anonymous_ws, add_carol.clone()).await;
+    wait_for(&mut anonymous_ws, |m| m["type"] == "ErrorMessage" && m["error"] == denied.as_str()).await;
+    let mut carol_ws = join(&second.ws_addr, &carol, &carol_token).await;
+    send(&mut carol_ws, add_carol).await;
+    wait_for(&mut carol_ws, |m| m["type"] == "ErrorMessage" && m["error"] == denied.as_str()).await;
+    assert!(!group_chat::is_member(&pool, chat_id, &carol).await.unwrap());
+
+    sqlx::query("DELETE FROM group_chats WHERE id = $1").bind(chat_id).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM messages WHERE sender = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone(), carol.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
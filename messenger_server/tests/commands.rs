@@ -0,0 +1,119 @@
+// интеграционный тест команд чата
+// запуск (нужна база из .env): cargo test --test commands
+use std::collections::HashMap;
+use std::env;
+use std::sync::Arc;
+use messenger_server::backplane::{local::LocalBackplane, SharedBackplane};
+use messenger_server::commands::{self, CommandContext, CommandRegistry};
+use messenger_server::db::{db_main, group_chat, user};
+use messenger_server::moderation::FilterChain;
+use messenger_server::presence::{self, Clients};
+use messenger_server::protocol::Message;
+use messenger_server::services::chat_service;
+use messenger_server::types::ServerError;
+use sqlx::PgPool;
+use tokio::sync::Mutex;
+
+#[test]
+fn parses_commands_and_escapes() {
+    assert_eq!(commands::parse("/invite  bob "), Some(("invite", "bob")));
+    assert_eq!(commands::parse("/help"), Some(("help", "")));
+    assert_eq!(commands::parse("привет"), None);
+    assert_eq!(commands::parse("//не команда"), None);
+    assert_eq!(commands::unescape("//не команда"), "/не команда");
+    assert_eq!(commands::unescape("/help"), "/help");
+}
+
+#[tokio::test]
+async fn group_chat_commands() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+    let backplane: SharedBackplane = Arc::new(LocalBackplane::new());
+    let filters = FilterChain::new(Vec::new());
+    let clients: Clients = Arc::new(Mutex::new(HashMap::new()));
+    let registry = CommandRegistry::with_builtin();
+
+    let suffix = std::process::id();
+    let alice = format!("cmd_alice_{}", suffix);
+    let bob = format!("cmd_bob_{}", suffix);
+    for username in [&alice, &bob] {
+        user::register(&pool, username, "secret").await.unwrap();
+    }
+    presence::register_connection(&clients, &bob).await;
+
+    let chat_id: i32 = sqlx::query_scalar("INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id")
+        .bind(format!("commands_{}", suffix))
+        .bind(&alice)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    group_chat::add_member(&pool, chat_id, &alice).await.unwrap();
+
+    let as_alice = CommandContext {
+        db_pool: &pool,
+        backplane: &backplane,
+        filters: &filters,
+        clients: &clients,
+        sender: &alice,
+        chat_id: Some(chat_id),
+        ip: None,
+    };
+    let as_bob = CommandContext { sender: &bob, ..as_alice };
+
+    // обычный текст не является командой
+    assert!(registry.execute(&as_alice, "привет").await.is_none());
+
+    // справка перечисляет все команды
+    let Some(Ok(Some(Message::ReceiveMessage { content, .. }))) = registry.execute(&as_alice, "/help").await else {
+        panic!("ожидалась справка");
+    };
+    for name in ["/me", "/topic", "/invite", "/kick", "/mute", "/who"] {
+        assert!(content.contains(name), "в справке нет {}", name);
+    }
+
+    // ошибки использования и неизвестные команды
+    assert!(matches!(registry.execute(&as_alice, "/invite").await, Some(Err(ServerError::InvalidArgument(_)))));
+    assert!(matches!(registry.execute(&as_alice, "/unknown").await, Some(Err(ServerError::InvalidArgument(_)))));
+
+    // не участник не может выполнять команды чата
+    assert!(matches!(registry.execute(&as_bob, "/who").await, Some(Err(ServerError::PermissionDenied))));
+
+    // кадр AddMemberToGroupChat проверяет те же права, что и /invite
+    assert!(matches!(
+        chat_service::invite_member(&pool, chat_id, &bob, &bob).await,
+        Err(ServerError::PermissionDenied)
+    ));
+    assert!(!group_chat::is_member(&pool, chat_id, &bob).await.unwrap());
+
+    registry.execute(&as_alice, &format!("/invite {}", bob)).await.unwrap().unwrap();
+    assert!(group_chat::is_member(&pool, chat_id, &bob).await.unwrap());
+
+    let Some(Ok(Some(Message::ReceiveMessage { content, .. }))) = registry.execute(&as_bob, "/who").await else {
+        panic!("ожидался список участников");
+    };
+    assert!(content.contains(&format!("{} (в сети)", bob)));
+
+    // тему и состав меняет только создатель
+    assert!(matches!(registry.execute(&as_bob, "/topic релиз").await, Some(Err(ServerError::PermissionDenied))));
+    assert!(matches!(
+        registry.execute(&as_bob, &format!("/kick {}", alice)).await,
+        Some(Err(ServerError::PermissionDenied))
+    ));
+    registry.execute(&as_alice, "/topic релиз в пятницу").await.unwrap().unwrap();
+    assert_eq!(group_chat::get_topic(&pool, chat_id).await.unwrap().as_deref(), Some("релиз в пятницу"));
+
+    registry.execute(&as_alice, &format!("/kick {}", bob)).await.unwrap().unwrap();
+    assert!(!group_chat::is_member(&pool, chat_id, &bob).await.unwrap());
+
+    sqlx::query("DELETE FROM group_chats WHERE id = $1").bind(chat_id).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
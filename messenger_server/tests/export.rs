@@ -0,0 +1,113 @@
+// интеграционный тест выгрузки и загрузки чатов: поток GET /chats/.../export читается messenger-admin chats import
+// запуск (нужна база из .env): cargo test --test export
+use std::env;
+use futures_util::TryStreamExt;
+use messenger_server::db::{db_main, group_chat, messages, private_messages, user};
+use messenger_server::services::chat_service::{self, ExportFormat, ExportSource};
+use messenger_server::structs::{ChatExport, DirectChatExport, ExportedMessage, SendOptions};
+use messenger_server::types::DbPool;
+use sqlx::PgPool;
+
+async fn export_json(pool: &DbPool, source: ExportSource) -> String {
+    chat_service::export_stream(pool.clone(), source, ExportFormat::Json)
+        .try_collect::<Vec<String>>()
+        .await
+        .unwrap()
+        .concat()
+}
+
+// сообщения без ID: после загрузки они получают новые
+fn contents(messages: &[ExportedMessage]) -> Vec<(String, String, chrono::NaiveDateTime)> {
+    messages
+        .iter()
+        .map(|message| (message.sender.clone(), message.content.clone(), message.sent_at))
+        .collect()
+}
+
+#[tokio::test]
+async fn export_import_round_trip() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+
+    let suffix = std::process::id();
+    let alice = format!("export_alice_{}", suffix);
+    let bob = format!("export_bob_{}", suffix);
+    let carol = format!("export_carol_{}", suffix);
+    for username in [&alice, &bob, &carol] {
+        user::register(&pool, username, "secret").await.unwrap();
+    }
+
+    // групповой чат: участники, ответ на сообщение и сохранение порядка
+    let name = format!("export_{}", suffix);
+    let chat_id = chat_service::create_group_chat(&pool, &name, &alice).await.unwrap();
+    for member in [&alice, &bob] {
+        group_chat::add_member(&pool, chat_id, member).await.unwrap();
+    }
+    let first = messages::save_message(&pool, &alice, "привет", Some(chat_id), &SendOptions::default()).await.unwrap();
+    let reply = SendOptions { reply_to: Some(first), ..Default::default() };
+    messages::save_message(&pool, &bob, "\"]} не ломает JSON", Some(chat_id), &reply).await.unwrap();
+
+    let mut export: ChatExport = serde_json::from_str(&export_json(&pool, ExportSource::Group(chat_id)).await).unwrap();
+    assert_eq!(export.chat.name, name);
+    assert_eq!(export.messages.len(), 2);
+
+    // загрузка на "другом" сервере: имя занято исходным чатом, поэтому меняем его
+    let copy_name = format!("export_copy_{}", suffix);
+    export.chat.name = copy_name.clone();
+    let copy_id = chat_service::import_group_chat(&pool, &export).await.unwrap();
+    let copy: ChatExport = serde_json::from_str(&export_json(&pool, ExportSource::Group(copy_id)).await).unwrap();
+    assert_eq!(copy.chat.name, copy_name);
+    assert_eq!(copy.chat.creator, alice);
+    let mut members = copy.members.clone();
+    members.sort();
+    let mut expected = export.members.clone();
+    expected.sort();
+    assert_eq!(members, expected);
+    assert_eq!(contents(&copy.messages), contents(&export.messages));
+    assert_eq!(copy.messages[1].reply_to, Some(copy.messages[0].id));
+
+    // личная переписка: сообщения в обе стороны, чужая переписка не попадает
+    private_messages::save(&pool, &alice, &bob, "как дела?").await.unwrap();
+    private_messages::save(&pool, &bob, &alice, "хорошо").await.unwrap();
+    private_messages::save(&pool, &alice, &carol, "не для bob").await.unwrap();
+
+    let direct = ExportSource::Direct { user: bob.clone(), other: alice.clone() };
+    let export: DirectChatExport = serde_json::from_str(&export_json(&pool, direct.clone()).await).unwrap();
+    assert_eq!(export.participants, [bob.clone(), alice.clone()]);
+    assert_eq!(export.messages.len(), 2);
+
+    sqlx::query("DELETE FROM private_messages WHERE sender = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+    assert_eq!(chat_service::import_direct_chat(&pool, &export).await.unwrap(), 2);
+    let copy: DirectChatExport = serde_json::from_str(&export_json(&pool, direct).await).unwrap();
+    assert_eq!(contents(&copy.messages), contents(&export.messages));
+
+    // в переписку нельзя загрузить сообщение постороннего
+    let mut foreign = export;
+    foreign.messages[0].sender = carol.clone();
+    assert!(chat_service::import_direct_chat(&pool, &foreign).await.is_err());
+
+    sqlx::query("DELETE FROM private_messages WHERE sender = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone(), carol.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM group_chats WHERE id = ANY($1)")
+        .bind(vec![chat_id, copy_id])
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![alice, bob, carol])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
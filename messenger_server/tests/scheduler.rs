@@ -0,0 +1,82 @@
+// интеграционный тест планировщика: отложенная доставка и самоудаление сообщений
+// запуск (нужна база из .env): cargo test --test scheduler
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::{TimeDelta, Utc};
+use messenger_server::backplane::{local::LocalBackplane, Event, SharedBackplane};
+use messenger_server::db::{db_main, group_chat, messages, user};
+use messenger_server::moderation::FilterChain;
+use messenger_server::services::message_service;
+use messenger_server::structs::SendOptions;
+use serde_json::Value;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use tokio::time::timeout;
+
+// ждем событие группового чата с сообщением нужного типа
+async fn wait_for(events: &mut broadcast::Receiver<Event>, chat_id: i32, kind: &str) -> Value {
+    timeout(Duration::from_secs(10), async {
+        loop {
+            if let Event::Group { chat_id: id, payload, .. } = events.recv().await.unwrap() {
+                let message: Value = serde_json::from_str(&payload).unwrap();
+                if id == chat_id && message["type"] == kind {
+                    return message;
+                }
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("Не дождались {}", kind))
+}
+
+#[tokio::test]
+async fn scheduled_message_is_delivered_then_deleted() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+    let backplane: SharedBackplane = Arc::new(LocalBackplane::new());
+    let filters = FilterChain::new(Vec::new());
+
+    let suffix = std::process::id();
+    let alice = format!("scheduler_alice_{}", suffix);
+    user::register(&pool, &alice, "secret").await.unwrap();
+    let chat_id: i32 = sqlx::query_scalar("INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id")
+        .bind(format!("scheduler_{}", suffix))
+        .bind(&alice)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    group_chat::add_member(&pool, chat_id, &alice).await.unwrap();
+
+    let mut events = backplane.subscribe();
+    let options = SendOptions {
+        send_at: Some(Utc::now() + TimeDelta::seconds(2)),
+        expires_after: Some(1),
+        ..Default::default()
+    };
+    let id = message_service::send_message_to_group_chat(&backplane, &pool, &filters, chat_id, &alice, "позже", &options)
+        .await
+        .unwrap()
+        .expect("отложенное сообщение возвращает свой ID");
+
+    // до времени отправки сообщение не рассылается и не видно в истории
+    assert!(events.try_recv().is_err());
+    assert!(messages::find_message(&pool, id).await.unwrap().is_none());
+
+    tokio::spawn(message_service::run_scheduler(backplane.clone(), Arc::new(pool.clone())));
+
+    let delivered = wait_for(&mut events, chat_id, "ReceiveGroupChatMessage").await;
+    assert_eq!(delivered["id"], id);
+    assert_eq!(delivered["content"], "позже");
+    assert!(messages::find_message(&pool, id).await.unwrap().is_some());
+
+    // срок жизни отсчитывается от времени отправки
+    let deleted = wait_for(&mut events, chat_id, "MessageDeleted").await;
+    assert_eq!(deleted["message_id"], id);
+    assert!(messages::find_message(&pool, id).await.unwrap().is_none());
+}
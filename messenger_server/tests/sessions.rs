@@ -0,0 +1,71 @@
+// интеграционный тест сессий: вход, обновление токенов, истечение и отзыв
+// запуск (нужна база из .env): cargo test --test sessions
+use std::env;
+use messenger_server::db::{db_main, sessions, user};
+use messenger_server::services::session_service;
+use messenger_server::structs::ClientInfo;
+use messenger_server::types::ServerError;
+use sqlx::PgPool;
+
+#[tokio::test]
+async fn sessions_rotate_expire_and_revoke() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+    let client = ClientInfo { user_agent: Some("sessions-test".to_string()), ip: None };
+
+    let username = format!("sessions_user_{}", std::process::id());
+    user::register(&pool, &username, "secret").await.unwrap();
+
+    // неверный пароль сессию не создает
+    assert!(session_service::login(&pool, &username, "wrong", &client).await.unwrap().is_none());
+    let first = session_service::login(&pool, &username, "secret", &client).await.unwrap().unwrap();
+    let session = session_service::authenticate(&pool, &first.token).await.unwrap();
+    assert_eq!(session.id, first.session_id);
+    assert_eq!(session.username, username);
+
+    // после обновления старые токены больше не действуют, сессия та же
+    let rotated = session_service::refresh(&pool, &first.refresh_token).await.unwrap();
+    assert_eq!(rotated.session_id, first.session_id);
+    assert!(matches!(session_service::authenticate(&pool, &first.token).await, Err(ServerError::InvalidToken)));
+    assert!(matches!(session_service::refresh(&pool, &first.refresh_token).await, Err(ServerError::InvalidToken)));
+    assert_eq!(session_service::authenticate(&pool, &rotated.token).await.unwrap().id, first.session_id);
+
+    // токен доступа с истекшим сроком не принимается, а токен обновления еще действует
+    let expired_token = session_service::generate_token();
+    let expired_refresh = session_service::generate_token();
+    let expired_id = sessions::create(
+        &pool,
+        &username,
+        &session_service::hash_token(&expired_token),
+        &session_service::hash_token(&expired_refresh),
+        -1,
+        session_service::REFRESH_TOKEN_TTL_SECS,
+        &client,
+    ).await.unwrap();
+    assert!(matches!(session_service::authenticate(&pool, &expired_token).await, Err(ServerError::InvalidToken)));
+    let renewed = session_service::refresh(&pool, &expired_refresh).await.unwrap();
+    assert_eq!(renewed.session_id, expired_id);
+
+    let active = session_service::list_sessions(&pool, &username).await.unwrap();
+    assert_eq!(active.len(), 2);
+
+    // отзыв одной сессии и повторный отзыв
+    session_service::revoke_session(&pool, &username, expired_id).await.unwrap();
+    assert!(matches!(session_service::authenticate(&pool, &renewed.token).await, Err(ServerError::InvalidToken)));
+    assert!(matches!(
+        session_service::revoke_session(&pool, &username, expired_id).await,
+        Err(ServerError::InvalidOperation)
+    ));
+    // чужую сессию отозвать нельзя
+    assert!(session_service::revoke_session(&pool, "someone_else", first.session_id).await.is_err());
+
+    let revoked = session_service::revoke_all_sessions(&pool, &username).await.unwrap();
+    assert_eq!(revoked, vec![first.session_id]);
+    assert!(session_service::authenticate(&pool, &rotated.token).await.is_err());
+    assert!(session_service::list_sessions(&pool, &username).await.unwrap().is_empty());
+}
@@ -0,0 +1,94 @@
+// интеграционный тест счетчиков непрочитанных сообщений
+// запуск (нужна база из .env): cargo test --test unread
+use std::env;
+use std::sync::Arc;
+use messenger_server::backplane::{local::LocalBackplane, SharedBackplane};
+use messenger_server::db::{db_main, group_chat, user};
+use messenger_server::moderation::FilterChain;
+use messenger_server::protocol::Message;
+use messenger_server::services::{message_service, unread_service::{self, UnreadTracker}};
+use messenger_server::structs::{SendOptions, UserChat};
+use sqlx::PgPool;
+
+#[tokio::test]
+async fn unread_counts_follow_messages_and_read_markers() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+    let backplane: SharedBackplane = Arc::new(LocalBackplane::new());
+    let filters = FilterChain::new(Vec::new());
+    let options = SendOptions::default();
+
+    let suffix = std::process::id();
+    let alice = format!("unread_alice_{}", suffix);
+    let bob = format!("unread_bob_{}", suffix);
+    for username in [&alice, &bob] {
+        user::register(&pool, username, "secret").await.unwrap();
+    }
+    let chat_id: i32 = sqlx::query_scalar("INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id")
+        .bind(format!("unread_{}", suffix))
+        .bind(&alice)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    for member in [&alice, &bob] {
+        group_chat::add_member(&pool, chat_id, member).await.unwrap();
+    }
+
+    for content in ["первое", "второе"] {
+        message_service::send_message_to_group_chat(&backplane, &pool, &filters, chat_id, &alice, content, &options)
+            .await
+            .unwrap();
+    }
+
+    // свои сообщения не считаются непрочитанными
+    let unread_in = |chats: &[UserChat]| {
+        chats.iter().find(|chat| chat.chat_id == Some(chat_id)).map(|chat| chat.unread)
+    };
+    assert_eq!(unread_in(&unread_service::list_user_chats(&pool, &alice).await.unwrap()), Some(0));
+    assert_eq!(unread_in(&unread_service::list_user_chats(&pool, &bob).await.unwrap()), Some(2));
+
+    let (mut tracker, counts) = UnreadTracker::load(&pool, &bob).await.unwrap();
+    let Message::UnreadCounts { counts } = counts else {
+        panic!("ожидался кадр UnreadCounts");
+    };
+    assert!(counts.iter().any(|count| count.chat_id == Some(chat_id) && count.unread == 2));
+
+    // новое сообщение увеличивает счетчик
+    let incoming = Message::ReceiveGroupChatMessage {
+        id: Some(i32::MAX),
+        chat_id,
+        sender: alice.clone(),
+        content: "третье".to_string(),
+        reply_to: None,
+        reactions: Vec::new(),
+    };
+    let update = tracker.on_event(&pool, &incoming).await.unwrap();
+    assert!(matches!(update, Some(Message::UnreadCountUpdated { unread: 3, .. })));
+
+    // отметка о прочтении пересчитывает счетчик по БД
+    let last_id: i32 = sqlx::query_scalar("SELECT MAX(id) FROM messages WHERE chat_id = $1")
+        .bind(chat_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    message_service::mark_read(&backplane, &pool, &bob, Some(chat_id), last_id).await.unwrap();
+    let marker = Message::ReadMarkerUpdated { chat_id: Some(chat_id), message_id: last_id };
+    let update = tracker.on_event(&pool, &marker).await.unwrap();
+    assert!(matches!(update, Some(Message::UnreadCountUpdated { unread: 0, .. })));
+
+    let chats = unread_service::list_user_chats(&pool, &bob).await.unwrap();
+    let chat = chats.iter().find(|chat| chat.chat_id == Some(chat_id)).unwrap();
+    assert_eq!((chat.unread, chat.last_read), (0, Some(last_id)));
+
+    sqlx::query("DELETE FROM group_chats WHERE id = $1").bind(chat_id).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![alice.clone(), bob.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,168 @@
+// интеграционный тест: сообщение бота попадает в очередь вебхука и доставляется
+// с подписью и повтором после ошибки
+// запуск (нужна база из .env): cargo test --test webhooks
+use std::env;
+use std::sync::Arc;
+use messenger_server::backplane::{local::LocalBackplane, SharedBackplane};
+use messenger_server::db::{db_main, user};
+use messenger_server::moderation::FilterChain;
+use messenger_server::services::{bot_service, message_service, webhook_service};
+use messenger_server::structs::{SendOptions, WebhookPayload};
+use sqlx::PgPool;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+// принятый HTTP-запрос: заголовки в нижнем регистре и тело
+struct Captured {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Captured {
+    fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+// HTTP-сервер, отвечающий по очереди заданными статусами
+async fn start_receiver(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<Captured>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        for status in statuses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0u8; 4096];
+
+            // читаем заголовки, затем тело по Content-Length
+            let captured = loop {
+                let read = socket.read(&mut chunk).await.unwrap();
+                buffer.extend_from_slice(&chunk[..read]);
+                let text = String::from_utf8_lossy(&buffer).to_string();
+                let Some(head_end) = text.find("\r\n\r\n") else {
+                    continue;
+                };
+                let headers: Vec<(String, String)> = text[..head_end]
+                    .lines()
+                    .skip(1)
+                    .filter_map(|line| line.split_once(": "))
+                    .map(|(key, value)| (key.to_lowercase(), value.to_string()))
+                    .collect();
+                let length: usize = headers
+                    .iter()
+                    .find(|(key, _)| key == "content-length")
+                    .map(|(_, value)| value.parse().unwrap())
+                    .unwrap_or(0);
+                if buffer.len() >= head_end + 4 + length {
+                    let body = String::from_utf8_lossy(&buffer[head_end + 4..head_end + 4 + length]).to_string();
+                    break Captured { headers, body };
+                }
+            };
+
+            let response = format!("HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            socket.shutdown().await.ok();
+            tx.send(captured).ok();
+        }
+    });
+
+    (url, rx)
+}
+
+#[tokio::test]
+async fn bot_message_is_delivered_to_webhook() {
+    dotenv::dotenv().ok();
+    let Ok(database_url) = env::var("DATABASE_URL") else {
+        eprintln!("DATABASE_URL не задан, тест пропущен");
+        return;
+    };
+    let pool = PgPool::connect(&database_url).await.unwrap();
+    db_main::run_migrations(&pool).await.unwrap();
+    let backplane: SharedBackplane = Arc::new(LocalBackplane::new());
+
+    let suffix = std::process::id();
+    let owner = format!("owner_{}", suffix);
+    user::register(&pool, &owner, "secret").await.unwrap();
+    let (bot, token) = bot_service::create_bot(&pool, &owner, &format!("hooks_{}", suffix)).await.unwrap();
+    assert_eq!(bot_service::authenticate(&pool, &token).await.unwrap(), bot);
+
+    let chat_id: i32 = sqlx::query_scalar("INSERT INTO group_chats (name, creator) VALUES ($1, $2) RETURNING id")
+        .bind(format!("webhooks_{}", suffix))
+        .bind(&owner)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+    // пока бота нет в чате, писать он не может
+    assert!(bot_service::check_can_post(&pool, &bot, chat_id).await.is_err());
+    for member in [&owner, &bot] {
+        sqlx::query("INSERT INTO group_chat_members (chat_id, username) VALUES ($1, $2)")
+            .bind(chat_id)
+            .bind(member)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+    bot_service::check_can_post(&pool, &bot, chat_id).await.unwrap();
+
+    // первый ответ - ошибка, второй - успех
+    let (url, mut requests) = start_receiver(vec![500, 200]).await;
+    assert!(webhook_service::create_webhook(&pool, chat_id, &bot, &url).await.is_err());
+    let (webhook_id, secret) = webhook_service::create_webhook(&pool, chat_id, &owner, &url).await.unwrap();
+
+    message_service::send_message_to_group_chat(
+        &backplane,
+        &pool,
+        &FilterChain::new(Vec::new()),
+        chat_id,
+        &bot,
+        "сборка прошла",
+        &SendOptions::default(),
+    ).await.unwrap();
+
+    let client = webhook_service::http_client();
+    webhook_service::deliver_due(&pool, &client).await.unwrap();
+    let first = requests.recv().await.unwrap();
+
+    // после ошибки доставка отложена, переносим ее на сейчас
+    sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = NOW() WHERE webhook_id = $1")
+        .bind(webhook_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    webhook_service::deliver_due(&pool, &client).await.unwrap();
+    let second = requests.recv().await.unwrap();
+
+    assert_eq!(first.body, second.body);
+    assert_eq!(first.header(webhook_service::DELIVERY_HEADER), second.header(webhook_service::DELIVERY_HEADER));
+    assert_eq!(
+        second.header(webhook_service::SIGNATURE_HEADER),
+        Some(webhook_service::sign(&secret, &second.body).as_str())
+    );
+    let payload: WebhookPayload = serde_json::from_str(&second.body).unwrap();
+    assert_eq!(payload.chat_id, chat_id);
+    assert_eq!(payload.message.sender, bot);
+    assert_eq!(payload.message.content, "сборка прошла");
+
+    let (attempts, delivered): (i32, bool) = sqlx::query_as(
+        "SELECT attempts, delivered_at IS NOT NULL FROM webhook_deliveries WHERE webhook_id = $1",
+    )
+    .bind(webhook_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(attempts, 2);
+    assert!(delivered);
+
+    sqlx::query("DELETE FROM group_chats WHERE id = $1").bind(chat_id).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM messages WHERE sender = $1").bind(&bot).execute(&pool).await.unwrap();
+    sqlx::query("DELETE FROM users WHERE username = ANY($1)")
+        .bind(vec![owner.clone(), bot.clone()])
+        .execute(&pool)
+        .await
+        .unwrap();
+}